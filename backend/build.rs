@@ -0,0 +1,33 @@
+use std::process::Command;
+
+fn main() {
+    // Rebuild when a migration is added, since they are embedded by `sqlx::migrate!`.
+    println!("cargo:rerun-if-changed=migrations");
+
+    // Build metadata for `/version`. Builds without a checkout, such as Docker
+    // images, can pass GIT_SHA in the environment instead.
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    let git_sha = std::env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| git(&["rev-parse", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_SHA={}", git_sha);
+    println!("cargo:rustc-env=BUILD_TIME={}", chrono::Utc::now().to_rfc3339());
+
+    // Pick up new commits without rebuilding on every file change.
+    for path in ["HEAD", "refs/heads"] {
+        if let Some(path) = git(&["rev-parse", "--git-path", path]) {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8(output.stdout).ok().map(|s| s.trim().to_string())
+}
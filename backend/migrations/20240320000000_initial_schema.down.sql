@@ -0,0 +1,14 @@
+DROP TABLE IF EXISTS applications;
+DROP TABLE IF EXISTS jobs;
+DROP TABLE IF EXISTS companies;
+DROP TABLE IF EXISTS users;
+
+DROP FUNCTION IF EXISTS update_updated_at_column();
+
+DROP TYPE IF EXISTS company_size;
+DROP TYPE IF EXISTS application_status;
+DROP TYPE IF EXISTS experience_level;
+DROP TYPE IF EXISTS job_type;
+DROP TYPE IF EXISTS user_role;
+
+DROP EXTENSION IF EXISTS "uuid-ossp";
@@ -0,0 +1,12 @@
+DROP TABLE IF EXISTS offers;
+DROP TYPE IF EXISTS offer_status;
+
+ALTER TABLE jobs DROP COLUMN IF EXISTS close_applications_on_accept;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS company_id,
+    DROP COLUMN IF EXISTS role;
+
+-- Postgres cannot drop enum values: 'recruiter' stays in user_role and 'closed'
+-- in application_status. Rows that use them are moved back to the old values.
+UPDATE applications SET status = 'rejected' WHERE status = 'closed';
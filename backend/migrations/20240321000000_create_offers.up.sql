@@ -0,0 +1,41 @@
+-- Recruiters belong to a company and manage its applications
+ALTER TYPE user_role ADD VALUE IF NOT EXISTS 'recruiter';
+
+ALTER TABLE users
+    ADD COLUMN role user_role NOT NULL DEFAULT 'user',
+    ADD COLUMN company_id UUID REFERENCES companies(id) ON DELETE SET NULL;
+
+-- Applications closed because another candidate accepted an offer
+ALTER TYPE application_status ADD VALUE IF NOT EXISTS 'closed';
+
+ALTER TABLE jobs
+    ADD COLUMN close_applications_on_accept BOOLEAN NOT NULL DEFAULT false;
+
+-- Create offers table
+CREATE TYPE offer_status AS ENUM ('pending', 'accepted', 'declined', 'expired', 'withdrawn');
+
+CREATE TABLE offers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    application_id UUID NOT NULL REFERENCES applications(id) ON DELETE CASCADE,
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    created_by UUID NOT NULL REFERENCES users(id),
+    salary_amount BIGINT NOT NULL CHECK (salary_amount > 0),
+    salary_currency VARCHAR(3) NOT NULL,
+    start_date DATE NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    terms_url VARCHAR(255),
+    status offer_status NOT NULL DEFAULT 'pending',
+    responded_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Only one open offer per application
+CREATE UNIQUE INDEX idx_offers_application_pending ON offers(application_id) WHERE status = 'pending';
+CREATE INDEX idx_offers_job_id ON offers(job_id);
+CREATE INDEX idx_offers_expires_at ON offers(expires_at) WHERE status = 'pending';
+
+CREATE TRIGGER update_offers_updated_at
+    BEFORE UPDATE ON offers
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
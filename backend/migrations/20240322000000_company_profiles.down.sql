@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS idx_companies_slug;
+
+ALTER TABLE companies
+    DROP COLUMN IF EXISTS verified,
+    DROP COLUMN IF EXISTS size,
+    DROP COLUMN IF EXISTS industry,
+    DROP COLUMN IF EXISTS logo_url,
+    DROP COLUMN IF EXISTS slug;
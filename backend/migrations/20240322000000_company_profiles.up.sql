@@ -0,0 +1,23 @@
+-- Richer company profiles
+ALTER TABLE companies
+    ADD COLUMN slug VARCHAR(120),
+    ADD COLUMN logo_url VARCHAR(255),
+    ADD COLUMN industry VARCHAR(100),
+    ADD COLUMN size company_size,
+    ADD COLUMN verified BOOLEAN NOT NULL DEFAULT false;
+
+-- Backfill slugs from names, disambiguating duplicates with the id prefix
+UPDATE companies
+SET slug = trim(both '-' from lower(regexp_replace(name, '[^a-zA-Z0-9]+', '-', 'g')));
+
+UPDATE companies c
+SET slug = concat_ws('-', nullif(c.slug, ''), left(c.id::text, 8))
+WHERE c.slug = ''
+   OR EXISTS (
+       SELECT 1 FROM companies other
+       WHERE other.slug = c.slug AND other.id < c.id
+   );
+
+ALTER TABLE companies ALTER COLUMN slug SET NOT NULL;
+
+CREATE UNIQUE INDEX idx_companies_slug ON companies(slug);
@@ -0,0 +1,8 @@
+ALTER TABLE companies
+    DROP COLUMN IF EXISTS review_count,
+    DROP COLUMN IF EXISTS rating_average;
+
+DROP TABLE IF EXISTS company_reviews;
+
+DROP TYPE IF EXISTS employment_status;
+DROP TYPE IF EXISTS review_status;
@@ -0,0 +1,41 @@
+-- Create review types
+CREATE TYPE review_status AS ENUM ('pending', 'approved', 'rejected');
+CREATE TYPE employment_status AS ENUM ('current', 'former');
+
+-- Create company reviews table
+CREATE TABLE company_reviews (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(150) NOT NULL,
+    overall_rating SMALLINT NOT NULL CHECK (overall_rating BETWEEN 1 AND 5),
+    work_life_balance_rating SMALLINT CHECK (work_life_balance_rating BETWEEN 1 AND 5),
+    compensation_rating SMALLINT CHECK (compensation_rating BETWEEN 1 AND 5),
+    culture_rating SMALLINT CHECK (culture_rating BETWEEN 1 AND 5),
+    management_rating SMALLINT CHECK (management_rating BETWEEN 1 AND 5),
+    career_growth_rating SMALLINT CHECK (career_growth_rating BETWEEN 1 AND 5),
+    pros TEXT NOT NULL,
+    cons TEXT NOT NULL,
+    employment_status employment_status NOT NULL,
+    job_title VARCHAR(100),
+    status review_status NOT NULL DEFAULT 'pending',
+    moderation_note TEXT,
+    moderated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    moderated_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_company_reviews_company_user UNIQUE (company_id, user_id)
+);
+
+CREATE INDEX idx_company_reviews_company_status ON company_reviews(company_id, status);
+CREATE INDEX idx_company_reviews_status ON company_reviews(status);
+
+CREATE TRIGGER update_company_reviews_updated_at
+    BEFORE UPDATE ON company_reviews
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Aggregate of approved reviews, cached on the company
+ALTER TABLE companies
+    ADD COLUMN rating_average DOUBLE PRECISION,
+    ADD COLUMN review_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,14 @@
+DROP INDEX IF EXISTS idx_users_suspended_at;
+
+ALTER TABLE companies
+    DROP COLUMN IF EXISTS takedown_reason,
+    DROP COLUMN IF EXISTS taken_down_at;
+
+ALTER TABLE jobs
+    DROP COLUMN IF EXISTS takedown_reason,
+    DROP COLUMN IF EXISTS taken_down_at;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS tokens_revoked_at,
+    DROP COLUMN IF EXISTS suspension_reason,
+    DROP COLUMN IF EXISTS suspended_at;
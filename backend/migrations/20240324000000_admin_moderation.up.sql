@@ -0,0 +1,16 @@
+-- Account suspension and token revocation
+ALTER TABLE users
+    ADD COLUMN suspended_at TIMESTAMPTZ,
+    ADD COLUMN suspension_reason TEXT,
+    ADD COLUMN tokens_revoked_at TIMESTAMPTZ;
+
+-- Moderator takedowns
+ALTER TABLE jobs
+    ADD COLUMN taken_down_at TIMESTAMPTZ,
+    ADD COLUMN takedown_reason TEXT;
+
+ALTER TABLE companies
+    ADD COLUMN taken_down_at TIMESTAMPTZ,
+    ADD COLUMN takedown_reason TEXT;
+
+CREATE INDEX idx_users_suspended_at ON users(suspended_at) WHERE suspended_at IS NOT NULL;
@@ -0,0 +1,31 @@
+-- Rows still soft-deleted would reappear, so they are purged first. As in the
+-- retention job, companies with jobs left and users who made offers are kept.
+DELETE FROM applications WHERE job_id IN (SELECT id FROM jobs WHERE deleted_at IS NOT NULL);
+DELETE FROM jobs WHERE deleted_at IS NOT NULL;
+DELETE FROM companies c
+WHERE c.deleted_at IS NOT NULL
+  AND NOT EXISTS (SELECT 1 FROM jobs j WHERE j.company_id = c.id);
+DELETE FROM applications
+WHERE user_id IN (
+    SELECT id FROM users u
+    WHERE u.deleted_at IS NOT NULL
+      AND NOT EXISTS (SELECT 1 FROM offers o WHERE o.created_by = u.id)
+);
+DELETE FROM users u
+WHERE u.deleted_at IS NOT NULL
+  AND NOT EXISTS (SELECT 1 FROM offers o WHERE o.created_by = u.id);
+
+ALTER TABLE jobs DROP CONSTRAINT fk_jobs_company;
+ALTER TABLE jobs
+    ADD CONSTRAINT fk_jobs_company
+    FOREIGN KEY (company_id)
+    REFERENCES companies(id)
+    ON DELETE CASCADE;
+
+DROP INDEX IF EXISTS idx_jobs_deleted_at;
+DROP INDEX IF EXISTS idx_companies_deleted_at;
+DROP INDEX IF EXISTS idx_users_deleted_at;
+
+ALTER TABLE jobs DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE companies DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE users DROP COLUMN IF EXISTS deleted_at;
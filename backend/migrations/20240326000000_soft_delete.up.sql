@@ -0,0 +1,17 @@
+-- Soft delete: rows are hidden via deleted_at and purged after a retention period
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE companies ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE jobs ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- Deleting a company must no longer silently take its jobs (and their
+-- application history) with it.
+ALTER TABLE jobs DROP CONSTRAINT fk_jobs_company;
+ALTER TABLE jobs
+    ADD CONSTRAINT fk_jobs_company
+    FOREIGN KEY (company_id)
+    REFERENCES companies(id)
+    ON DELETE RESTRICT;
+
+CREATE INDEX idx_users_deleted_at ON users(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_companies_deleted_at ON companies(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_jobs_deleted_at ON jobs(deleted_at) WHERE deleted_at IS NOT NULL;
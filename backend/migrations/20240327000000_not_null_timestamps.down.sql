@@ -0,0 +1,12 @@
+ALTER TABLE jobs
+    ALTER COLUMN is_active DROP NOT NULL,
+    ALTER COLUMN updated_at DROP NOT NULL,
+    ALTER COLUMN created_at DROP NOT NULL;
+
+ALTER TABLE companies
+    ALTER COLUMN updated_at DROP NOT NULL,
+    ALTER COLUMN created_at DROP NOT NULL;
+
+ALTER TABLE users
+    ALTER COLUMN updated_at DROP NOT NULL,
+    ALTER COLUMN created_at DROP NOT NULL;
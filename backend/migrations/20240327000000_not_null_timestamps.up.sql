@@ -0,0 +1,21 @@
+-- The initial schema left these columns nullable although every row gets a
+-- default and the models treat them as always present.
+UPDATE users SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE users SET updated_at = created_at WHERE updated_at IS NULL;
+ALTER TABLE users
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at SET NOT NULL;
+
+UPDATE companies SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE companies SET updated_at = created_at WHERE updated_at IS NULL;
+ALTER TABLE companies
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at SET NOT NULL;
+
+UPDATE jobs SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE jobs SET updated_at = created_at WHERE updated_at IS NULL;
+UPDATE jobs SET is_active = true WHERE is_active IS NULL;
+ALTER TABLE jobs
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at SET NOT NULL,
+    ALTER COLUMN is_active SET NOT NULL;
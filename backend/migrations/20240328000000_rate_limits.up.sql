@@ -0,0 +1,9 @@
+-- Rate-limit counters shared by every instance. Losing them on a crash only
+-- resets the limits, so the table skips the WAL.
+CREATE UNLOGGED TABLE rate_limits (
+    key TEXT PRIMARY KEY,
+    count INTEGER NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_rate_limits_expires_at ON rate_limits(expires_at);
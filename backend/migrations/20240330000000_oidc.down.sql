@@ -0,0 +1,6 @@
+DROP TABLE oidc_login_states;
+DROP TABLE user_identities;
+
+-- Passwordless accounts get a hash that no password matches
+UPDATE users SET password_hash = '!' WHERE password_hash IS NULL;
+ALTER TABLE users ALTER COLUMN password_hash SET NOT NULL;
@@ -0,0 +1,21 @@
+-- Company-scoped keys for partner integrations. Only a SHA-256 hash of each
+-- key is stored; the prefix identifies a key without revealing it.
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    name VARCHAR(100) NOT NULL,
+    prefix VARCHAR(16) NOT NULL UNIQUE,
+    key_hash TEXT NOT NULL,
+    scopes TEXT[] NOT NULL,
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_api_keys_company_id ON api_keys(company_id);
+
+-- Requests made with an API key are attributed to the key
+ALTER TABLE audit_events ADD COLUMN api_key_id UUID REFERENCES api_keys(id) ON DELETE SET NULL;
+CREATE INDEX idx_audit_events_api_key_id ON audit_events(api_key_id);
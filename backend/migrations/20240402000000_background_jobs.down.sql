@@ -0,0 +1,2 @@
+DROP TABLE background_jobs;
+DROP TYPE background_job_status;
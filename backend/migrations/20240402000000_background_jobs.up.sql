@@ -0,0 +1,26 @@
+CREATE TYPE background_job_status AS ENUM ('pending', 'running', 'dead');
+
+-- Work for the worker pool. Finished jobs are deleted; dead ones stay until
+-- an admin retries them.
+CREATE TABLE background_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Names the handler, e.g. 'offers.expire'
+    kind VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    status background_job_status NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    max_attempts INT NOT NULL,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- While running: when the job is presumed lost and run again
+    locked_until TIMESTAMPTZ,
+    -- At most one waiting or running job per key
+    unique_key TEXT,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_background_jobs_due ON background_jobs(run_at) WHERE status = 'pending';
+CREATE INDEX idx_background_jobs_running ON background_jobs(locked_until) WHERE status = 'running';
+CREATE UNIQUE INDEX idx_background_jobs_unique_key ON background_jobs(unique_key)
+    WHERE status IN ('pending', 'running');
@@ -0,0 +1,57 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpMessage, HttpRequest};
+use futures::future::{ready, Ready};
+use uuid::Uuid;
+use crate::{
+    auth::jwt::Claims, models::api_keys::ApiKey, rate_limit::RateLimiter, request_id::RequestId,
+};
+
+/// Who made a request and from where, captured for the audit log.
+#[derive(Debug, Clone)]
+pub struct AuditContext {
+    pub actor_id: Option<Uuid>,
+    /// Set instead of `actor_id` when the request authenticated with an API key.
+    pub api_key_id: Option<Uuid>,
+    pub request_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub method: String,
+    pub path: String,
+}
+
+impl FromRequest for AuditContext {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let actor_id = req
+            .extensions()
+            .get::<Claims>()
+            .and_then(|claims| Uuid::parse_str(&claims.sub).ok());
+
+        let api_key_id = req.extensions().get::<ApiKey>().map(|api_key| api_key.id);
+
+        let request_id = req.extensions().get::<RequestId>().map(|id| id.0.clone());
+
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+
+        // Forwarded headers are only believed where the rate limiter believes them.
+        let ip_address = req
+            .app_data::<web::Data<RateLimiter>>()
+            .and_then(|limiter| limiter.client_address(&req.connection_info()));
+
+        ready(Ok(AuditContext {
+            actor_id,
+            api_key_id,
+            request_id,
+            ip_address,
+            user_agent: header("User-Agent"),
+            method: req.method().to_string(),
+            path: req.path().to_string(),
+        }))
+    }
+}
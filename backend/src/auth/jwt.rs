@@ -1,54 +1,304 @@
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    errors::ErrorKind,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters,
+        OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use ring::{
+    hmac,
+    signature::{Ed25519KeyPair, KeyPair, RsaKeyPair, RsaPublicKeyComponents},
+};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use std::sync::Arc;
+use thiserror::Error;
 use chrono::{Duration, Utc};
-use std::env;
+use crate::models::users::{User, UserRole};
+
+/// How long a user has to enter their second factor after the password step.
+pub const CHALLENGE_EXPIRATION_SECS: i64 = 5 * 60;
+
+pub const DEFAULT_ISSUER: &str = "careerhub";
+pub const DEFAULT_AUDIENCE: &str = "careerhub";
+
+/// The audience of challenge tokens, which no session check accepts.
+pub const CHALLENGE_AUDIENCE: &str = "careerhub-2fa-challenge";
+/// The `typ` header of challenge tokens.
+pub const CHALLENGE_TYPE: &str = "2fa-challenge+jwt";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub sub: String,  // user id
     pub exp: i64,     // expiration time
     pub iat: i64,     // issued at
+    pub iss: String,
+    pub aud: String,
+    /// As of issue; services acting on them should re-check the user.
+    pub email: String,
+    pub role: UserRole,
+    /// Set on challenge tokens, which only prove the password step of a
+    /// two-step login and are not accepted as sessions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub two_factor_pending: bool,
+}
+
+#[derive(Error, Debug)]
+pub enum JwtKeyError {
+    #[error("{0:?} is not supported; use RS256 or EdDSA")]
+    UnsupportedAlgorithm(Algorithm),
+
+    #[error("not a usable private key: {0}")]
+    InvalidKey(String),
+}
+
+/// A key tokens are signed or verified with. Asymmetric keys carry a `kid`
+/// and are published at `/.well-known/jwks.json`; the HS256 shared secret is
+/// neither.
+pub struct JwtKey {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    jwk: Option<Jwk>,
+    /// Signs and verifies challenge tokens. Derived from the private half, so
+    /// every instance sharing the key agrees on it and it is never published.
+    challenge: ChallengeKey,
+}
+
+struct ChallengeKey {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+impl ChallengeKey {
+    /// An HS256 key bound to `material` and to its use for challenges.
+    fn derive(material: &[u8]) -> Self {
+        let tag = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, material), CHALLENGE_AUDIENCE.as_bytes());
+
+        Self {
+            encoding: EncodingKey::from_secret(tag.as_ref()),
+            decoding: DecodingKey::from_secret(tag.as_ref()),
+        }
+    }
+}
+
+impl JwtKey {
+    /// An HS256 key; every service verifying its tokens must know the secret.
+    pub fn hmac(secret: &[u8]) -> Self {
+        Self {
+            kid: None,
+            algorithm: Algorithm::HS256,
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+            jwk: None,
+            challenge: ChallengeKey::derive(secret),
+        }
+    }
+
+    /// An RS256 or EdDSA (Ed25519) key from a PEM private key. RSA keys may
+    /// be PKCS#1 or PKCS#8, Ed25519 keys PKCS#8.
+    pub fn from_pem(kid: &str, algorithm: Algorithm, pem: &[u8]) -> Result<Self, JwtKeyError> {
+        let invalid = |e: &dyn std::fmt::Display| JwtKeyError::InvalidKey(e.to_string());
+
+        let parsed = pem::parse(pem).map_err(|e| invalid(&e))?;
+        let der = parsed.contents();
+
+        let (encoding, key_algorithm, parameters) = match algorithm {
+            Algorithm::RS256 => {
+                let pair = match parsed.tag() {
+                    "RSA PRIVATE KEY" => RsaKeyPair::from_der(der),
+                    "PRIVATE KEY" => RsaKeyPair::from_pkcs8(der),
+                    tag => return Err(JwtKeyError::InvalidKey(format!("expected a private key, found {}", tag))),
+                }
+                .map_err(|e| invalid(&e))?;
+                let public = RsaPublicKeyComponents::<Vec<u8>>::from(pair.public());
+
+                (
+                    EncodingKey::from_rsa_pem(pem).map_err(|e| invalid(&e))?,
+                    KeyAlgorithm::RS256,
+                    AlgorithmParameters::RSA(RSAKeyParameters {
+                        key_type: RSAKeyType::RSA,
+                        n: URL_SAFE_NO_PAD.encode(public.n),
+                        e: URL_SAFE_NO_PAD.encode(public.e),
+                    }),
+                )
+            }
+            Algorithm::EdDSA => {
+                let pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der).map_err(|e| invalid(&e))?;
+
+                (
+                    EncodingKey::from_ed_der(der),
+                    KeyAlgorithm::EdDSA,
+                    AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                        key_type: OctetKeyPairType::OctetKeyPair,
+                        curve: EllipticCurve::Ed25519,
+                        x: URL_SAFE_NO_PAD.encode(pair.public_key()),
+                    }),
+                )
+            }
+            other => return Err(JwtKeyError::UnsupportedAlgorithm(other)),
+        };
+
+        let jwk = Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(key_algorithm),
+                key_id: Some(kid.to_string()),
+                ..CommonParameters::default()
+            },
+            algorithm: parameters,
+        };
+
+        Ok(Self {
+            kid: Some(kid.to_string()),
+            algorithm,
+            encoding,
+            decoding: DecodingKey::from_jwk(&jwk).map_err(|e| invalid(&e))?,
+            jwk: Some(jwk),
+            challenge: ChallengeKey::derive(der),
+        })
+    }
+
+    pub fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
 }
 
 #[derive(Clone)]
 pub struct JwtConfig {
-    pub secret: String,
+    /// Signs new tokens.
+    pub signing_key: Arc<JwtKey>,
+    /// Accepted alongside the signing key, so tokens signed with a retired key
+    /// stay valid until they expire and a new key can be published before it
+    /// signs anything.
+    pub verification_keys: Vec<Arc<JwtKey>>,
     pub expiration: Duration,
+    pub issuer: String,
+    pub audience: String,
 }
 
 impl JwtConfig {
-    pub fn from_env() -> Self {
+    pub fn new(signing_key: JwtKey, expiration: Duration) -> Self {
         Self {
-            secret: env::var("JWT_SECRET").expect("JWT_SECRET must be set"),
-            expiration: Duration::hours(24),
+            signing_key: Arc::new(signing_key),
+            verification_keys: Vec::new(),
+            expiration,
+            issuer: DEFAULT_ISSUER.to_string(),
+            audience: DEFAULT_AUDIENCE.to_string(),
         }
     }
+
+    pub fn with_verification_key(mut self, key: JwtKey) -> Self {
+        self.verification_keys.push(Arc::new(key));
+        self
+    }
+
+    /// The public half of every asymmetric key, for other services to verify
+    /// our tokens with.
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self.keys().filter_map(|key| key.jwk.clone()).collect(),
+        }
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &JwtKey> {
+        std::iter::once(&*self.signing_key).chain(self.verification_keys.iter().map(|key| &**key))
+    }
 }
 
-pub fn generate_token(user_id: Uuid, config: &JwtConfig) -> Result<String, jsonwebtoken::errors::Error> {
-    let now = Utc::now();
-    let exp = (now + config.expiration).timestamp();
-    let iat = now.timestamp();
+pub fn generate_token(user: &User, config: &JwtConfig) -> Result<String, jsonwebtoken::errors::Error> {
+    let claims = claims(user, config.expiration, &config.audience, false, config);
+
+    let key = &config.signing_key;
+    let mut header = Header::new(key.algorithm);
+    header.kid = key.kid.clone();
+
+    encode(&header, &claims, &key.encoding)
+}
+
+/// A token to exchange, together with a second factor, for a session token.
+/// It has its own audience and `typ`, and is signed with the signing key's
+/// [`ChallengeKey`], so nothing verifying sessions against the JWKS accepts it.
+pub fn generate_challenge_token(user: &User, config: &JwtConfig) -> Result<String, jsonwebtoken::errors::Error> {
+    let claims = claims(user, Duration::seconds(CHALLENGE_EXPIRATION_SECS), CHALLENGE_AUDIENCE, true, config);
 
-    let claims = Claims {
-        sub: user_id.to_string(),
-        exp,
-        iat,
-    };
+    let mut header = Header::new(Algorithm::HS256);
+    header.typ = Some(CHALLENGE_TYPE.to_string());
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(config.secret.as_bytes()),
-    )
+    encode(&header, &claims, &config.signing_key.challenge.encoding)
 }
 
+/// Checks a session token; challenge tokens are refused.
 pub fn validate_token(token: &str, config: &JwtConfig) -> Result<Claims, jsonwebtoken::errors::Error> {
-    decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(config.secret.as_bytes()),
-        &Validation::default(),
-    )
-    .map(|data| data.claims)
-} 
\ No newline at end of file
+    let claims = verify(token, config)?;
+    if claims.two_factor_pending {
+        return Err(ErrorKind::InvalidToken.into());
+    }
+
+    Ok(claims)
+}
+
+/// Checks a token from [`generate_challenge_token`]; session tokens are refused.
+/// Challenges from a key that has since been rotated out of signing still pass
+/// while it verifies.
+pub fn validate_challenge_token(token: &str, config: &JwtConfig) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let header = decode_header(token)?;
+    if header.alg != Algorithm::HS256 || header.typ.as_deref() != Some(CHALLENGE_TYPE) {
+        return Err(ErrorKind::InvalidToken.into());
+    }
+
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_issuer(&[&config.issuer]);
+    validation.set_audience(&[CHALLENGE_AUDIENCE]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+    let mut result = Err(ErrorKind::InvalidSignature.into());
+    for key in config.keys() {
+        result = decode::<Claims>(token, &key.challenge.decoding, &validation).map(|data| data.claims);
+        if !matches!(&result, Err(e) if *e.kind() == ErrorKind::InvalidSignature) {
+            break;
+        }
+    }
+
+    let claims = result?;
+    if !claims.two_factor_pending {
+        return Err(ErrorKind::InvalidToken.into());
+    }
+
+    Ok(claims)
+}
+
+fn claims(user: &User, expiration: Duration, audience: &str, two_factor_pending: bool, config: &JwtConfig) -> Claims {
+    let now = Utc::now();
+
+    Claims {
+        sub: user.id.to_string(),
+        exp: (now + expiration).timestamp(),
+        iat: now.timestamp(),
+        iss: config.issuer.clone(),
+        aud: audience.to_string(),
+        email: user.email.clone(),
+        role: user.role,
+        two_factor_pending,
+    }
+}
+
+/// Verifies with the key the header names. Each key is bound to its
+/// algorithm, so a token cannot pick a weaker one.
+fn verify(token: &str, config: &JwtConfig) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let header = decode_header(token)?;
+    let key = config
+        .keys()
+        .find(|key| key.kid == header.kid && key.algorithm == header.alg)
+        .ok_or(ErrorKind::InvalidToken)?;
+
+    let mut validation = Validation::new(key.algorithm);
+    validation.set_issuer(&[&config.issuer]);
+    validation.set_audience(&[&config.audience]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+    decode::<Claims>(token, &key.decoding, &validation).map(|data| data.claims)
+}
@@ -1,12 +1,38 @@
 use actix_web::{
-    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error,
+    body::EitherBody,
+    dev::{forward_ready, ResourceDef, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    web, Error, HttpMessage,
 };
 use futures::future::{ready, LocalBoxFuture, Ready};
 use std::rc::Rc;
-use crate::auth::jwt::{JwtConfig, validate_token};
-use actix_web::error::ErrorUnauthorized;
+use crate::{
+    auth::jwt::{Claims, JwtConfig, validate_token},
+    error::AppError,
+    models::api_keys::{ApiKey, ApiKeyScope},
+    services::{api_keys::is_api_key, ApiKeyService, UserService},
+    telemetry,
+};
+
+/// The only endpoints an API key may call, and the scope each needs. Handlers
+/// behind these take the [`ApiKey`] from the request extensions and check it
+/// against the company they act on; every other handler expects a user.
+///
+/// Paths are relative to the API version's prefix, such as `/api/v1`.
+const API_KEY_ROUTES: &[(Method, &str, ApiKeyScope)] = &[
+    (Method::POST, "/jobs", ApiKeyScope::JobsWrite),
+    (Method::PUT, "/jobs/{job_id}", ApiKeyScope::JobsWrite),
+    (Method::GET, "/companies/{company_id}/applications", ApiKeyScope::ApplicationsRead),
+];
+
+/// Who a request authenticated as.
+enum Principal {
+    User(Claims),
+    ApiKey(ApiKey),
+}
 
+/// Accepts either a session JWT or a company API key as the bearer token,
+/// inserting its [`Claims`] or [`ApiKey`] into the request extensions.
 pub struct AuthMiddleware {
     config: JwtConfig,
 }
@@ -23,7 +49,7 @@ where
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Transform = AuthMiddlewareService<S>;
     type InitError = ();
@@ -48,7 +74,7 @@ where
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
 
@@ -59,24 +85,69 @@ where
         let config = self.config.clone();
 
         Box::pin(async move {
-            let auth_header = req
-                .headers()
-                .get("Authorization")
-                .and_then(|h| h.to_str().ok())
-                .and_then(|s| s.strip_prefix("Bearer "));
-
-            match auth_header {
-                Some(token) => {
-                    match validate_token(token, &config) {
-                        Ok(_claims) => {
-                            let res = service.call(req).await?;
-                            Ok(res)
-                        }
-                        Err(_) => Err(ErrorUnauthorized("Invalid token")),
-                    }
+            // Rejections are rendered here rather than returned as errors so they
+            // are still inside the request-id scope and pick up its headers.
+            match authenticate(&req, &config).await {
+                Ok(Principal::User(claims)) => {
+                    telemetry::record_user(&claims.sub);
+                    req.extensions_mut().insert(claims);
+                }
+                Ok(Principal::ApiKey(api_key)) => {
+                    telemetry::record_api_key(&api_key.id.to_string());
+                    req.extensions_mut().insert(api_key);
                 }
-                None => Err(ErrorUnauthorized("Missing token")),
+                Err(e) => return Ok(req.error_response(e).map_into_right_body()),
             }
+
+            let res = service.call(req).await?;
+            Ok(res.map_into_left_body())
         })
     }
-} 
\ No newline at end of file
+}
+
+async fn authenticate(req: &ServiceRequest, config: &JwtConfig) -> Result<Principal, AppError> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .ok_or_else(|| AppError::Unauthorized("Missing token".to_string()))?;
+
+    if is_api_key(token) {
+        return authenticate_api_key(req, token).await.map(Principal::ApiKey);
+    }
+
+    let claims = validate_token(token, config)
+        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+    // A valid signature is not enough: the account may have been suspended
+    // or had its tokens revoked since this one was issued.
+    let users = req
+        .app_data::<web::Data<UserService>>()
+        .ok_or_else(|| AppError::Internal("User service not configured".to_string()))?;
+    users.verify_session(&claims).await?;
+
+    Ok(Principal::User(claims))
+}
+
+async fn authenticate_api_key(req: &ServiceRequest, key: &str) -> Result<ApiKey, AppError> {
+    let api_keys = req
+        .app_data::<web::Data<ApiKeyService>>()
+        .ok_or_else(|| AppError::Internal("API key service not configured".to_string()))?;
+
+    let api_key = api_keys.authenticate(key).await?;
+
+    // The part of the path below the scope this middleware wraps.
+    let path = req.match_info().unprocessed();
+    let scope = API_KEY_ROUTES
+        .iter()
+        .find(|(method, pattern, _)| req.method() == method && ResourceDef::new(*pattern).is_match(path))
+        .map(|(_, _, scope)| *scope)
+        .ok_or_else(|| AppError::Forbidden("This endpoint does not accept API keys".to_string()))?;
+
+    if !api_key.has_scope(scope) {
+        return Err(AppError::Forbidden(format!("API key lacks the {} scope", scope)));
+    }
+
+    Ok(api_key)
+}
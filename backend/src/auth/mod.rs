@@ -1,2 +1,3 @@
 pub mod jwt;
-pub mod middleware; 
\ No newline at end of file
+pub mod middleware;
+pub mod oidc;
@@ -0,0 +1,236 @@
+//! The relying-party side of the OpenID Connect authorization code flow with
+//! PKCE: discovery, the authorization URL, the code exchange and ID token
+//! verification. What to do with a verified identity is up to
+//! [`OidcService`](crate::services::OidcService).
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{decode, decode_header, jwk::{Jwk, JwkSet}, Algorithm, DecodingKey, Validation};
+use rand_core::{OsRng, RngCore};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Deserializer};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+use tokio::sync::OnceCell;
+use crate::{config::OidcProviderConfig, error::AppError};
+
+/// Asymmetric algorithms only; a provider's keys are public.
+const ALLOWED_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+#[derive(Error, Debug)]
+pub enum OidcError {
+    #[error("identity provider request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("identity provider misbehaved: {0}")]
+    Provider(String),
+
+    /// The user's login could not be verified; not a fault of this server.
+    #[error("{0}")]
+    Rejected(String),
+}
+
+impl From<OidcError> for AppError {
+    fn from(e: OidcError) -> Self {
+        match e {
+            OidcError::Rejected(message) => AppError::Unauthorized(message),
+            e => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+/// The claims of a verified ID token that account linking uses.
+#[derive(Debug, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub email: Option<String>,
+    #[serde(default, deserialize_with = "lenient_bool")]
+    pub email_verified: bool,
+    pub name: Option<String>,
+    nonce: Option<String>,
+}
+
+/// The parts of the discovery document this client needs.
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// One configured provider. Discovery happens on first use and the signing
+/// keys are cached until a token names a key not among them.
+pub struct OidcProvider {
+    config: OidcProviderConfig,
+    http: Client,
+    metadata: OnceCell<ProviderMetadata>,
+    keys: RwLock<Arc<JwkSet>>,
+}
+
+impl OidcProvider {
+    pub fn new(config: OidcProviderConfig, http: Client) -> Self {
+        Self {
+            config,
+            http,
+            metadata: OnceCell::new(),
+            keys: RwLock::new(Arc::new(JwkSet { keys: Vec::new() })),
+        }
+    }
+
+    /// The URL to send the user to. `code_verifier` is kept secret until the
+    /// code exchange; the provider only sees its hash.
+    pub async fn authorization_url(&self, state: &str, nonce: &str, code_verifier: &str) -> Result<String, OidcError> {
+        let metadata = self.metadata().await?;
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            [
+                ("response_type", "code"),
+                ("client_id", &self.config.client_id),
+                ("redirect_uri", &self.config.redirect_uri),
+                ("scope", &self.config.scopes.join(" ")),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", &code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| OidcError::Provider(format!("invalid authorization_endpoint: {}", e)))?;
+
+        Ok(url.into())
+    }
+
+    /// Redeems an authorization code and verifies the ID token it yields,
+    /// which must carry `nonce`.
+    pub async fn exchange(&self, code: &str, code_verifier: &str, nonce: &str) -> Result<IdTokenClaims, OidcError> {
+        let metadata = self.metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_uri.as_str()),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = &self.config.client_secret {
+            form.push(("client_secret", secret));
+        }
+
+        let response = self.http.post(&metadata.token_endpoint).form(&form).send().await?;
+        if response.status().is_client_error() {
+            tracing::warn!(status = %response.status(), "Identity provider refused the authorization code");
+            return Err(OidcError::Rejected("The identity provider refused the login".to_string()));
+        }
+        let tokens: TokenResponse = response.error_for_status()?.json().await?;
+
+        let claims = self.verify(&tokens.id_token, &metadata.issuer).await?;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(OidcError::Rejected("ID token does not match the login".to_string()));
+        }
+
+        Ok(claims)
+    }
+
+    async fn verify(&self, id_token: &str, issuer: &str) -> Result<IdTokenClaims, OidcError> {
+        let invalid = |e: jsonwebtoken::errors::Error| OidcError::Rejected(format!("Invalid ID token: {}", e));
+
+        let header = decode_header(id_token).map_err(invalid)?;
+        if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+            return Err(OidcError::Rejected(format!("ID token is signed with {:?}", header.alg)));
+        }
+
+        let key = self.key(header.kid.as_deref()).await?;
+        let key = DecodingKey::from_jwk(&key).map_err(invalid)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(invalid)
+    }
+
+    /// The signing key named `kid`, refetching the key set once if it is
+    /// unknown, as happens when the provider rotates keys.
+    async fn key(&self, kid: Option<&str>) -> Result<Jwk, OidcError> {
+        let find = |keys: &JwkSet| match kid {
+            Some(kid) => keys.find(kid).cloned(),
+            None if keys.keys.len() == 1 => keys.keys.first().cloned(),
+            None => None,
+        };
+
+        let cached = self.keys.read().expect("key cache poisoned").clone();
+        if let Some(key) = find(&cached) {
+            return Ok(key);
+        }
+
+        let metadata = self.metadata().await?;
+        let fetched: JwkSet = self.http.get(&metadata.jwks_uri).send().await?.error_for_status()?.json().await?;
+        let key = find(&fetched);
+        *self.keys.write().expect("key cache poisoned") = Arc::new(fetched);
+
+        key.ok_or_else(|| OidcError::Rejected("ID token is signed with an unknown key".to_string()))
+    }
+
+    async fn metadata(&self) -> Result<&ProviderMetadata, OidcError> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                let metadata: ProviderMetadata = self.http.get(url).send().await?.error_for_status()?.json().await?;
+
+                if metadata.issuer.trim_end_matches('/') != self.config.issuer.trim_end_matches('/') {
+                    return Err(OidcError::Provider(format!(
+                        "discovery document is for issuer {}",
+                        metadata.issuer
+                    )));
+                }
+
+                Ok(metadata)
+            })
+            .await
+    }
+}
+
+/// 32 random bytes, URL-safe: for `state`, `nonce` and PKCE code verifiers.
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Some providers send `email_verified` as the string `"true"`.
+fn lenient_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Text(String),
+    }
+
+    Ok(match Flag::deserialize(deserializer)? {
+        Flag::Bool(flag) => flag,
+        Flag::Text(text) => text.eq_ignore_ascii_case("true"),
+    })
+}
@@ -1,20 +0,0 @@
-use sqlx::postgres::PgPoolOptions;
-use sqlx::PgPool;
-use crate::utils::Result;
-
-pub async fn init_pool(database_url: &str) -> Result<PgPool> {
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(database_url)
-        .await?;
-
-    Ok(pool)
-}
-
-pub async fn run_migrations(pool: &PgPool) -> Result<()> {
-    sqlx::migrate!("./migrations")
-        .run(pool)
-        .await?;
-
-    Ok(())
-} 
\ No newline at end of file
@@ -0,0 +1,161 @@
+use actix_web::{
+    http::{header, StatusCode},
+    HttpResponse, ResponseError,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use thiserror::Error;
+use validator::ValidationErrors;
+use utoipa::ToSchema;
+use crate::request_id;
+
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("Validation failed")]
+    Validation(#[from] ValidationErrors),
+
+    #[error("{0}")]
+    Unauthorized(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{message}")]
+    TooManyRequests { message: String, retry_after_secs: u64 },
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Internal server error: {0}")]
+    Internal(String),
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldError {
+    pub code: String,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorDetail {
+    /// Stable, machine-readable identifier such as `not_found` or `validation_failed`.
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<BTreeMap<String, Vec<FieldError>>>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: ErrorDetail,
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Validation(_) => "validation_failed",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::Database(sqlx::Error::RowNotFound) => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::TooManyRequests { .. } => "rate_limited",
+            AppError::Database(_) | AppError::Internal(_) => "internal_error",
+        }
+    }
+
+    /// The message shown to clients; internal failures are not described.
+    fn public_message(&self) -> String {
+        match self {
+            AppError::Database(sqlx::Error::RowNotFound) => "Not found".to_string(),
+            AppError::Database(_) | AppError::Internal(_) => "Internal server error".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    fn details(&self) -> Option<BTreeMap<String, Vec<FieldError>>> {
+        let AppError::Validation(errors) = self else {
+            return None;
+        };
+
+        let details = errors
+            .field_errors()
+            .into_iter()
+            .map(|(field, errors)| {
+                let errors = errors
+                    .iter()
+                    .map(|e| FieldError {
+                        code: e.code.to_string(),
+                        message: e.message.as_ref().map(|m| m.to_string()),
+                    })
+                    .collect();
+                (field.to_string(), errors)
+            })
+            .collect();
+
+        Some(details)
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) | AppError::Validation(_) => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) | AppError::Database(sqlx::Error::RowNotFound) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let request_id = request_id::current();
+
+        if self.status_code().is_server_error() {
+            tracing::error!(error = %self, "Request handler failed");
+        }
+
+        let mut response = HttpResponse::build(self.status_code());
+        if let AppError::TooManyRequests { retry_after_secs, .. } = self {
+            response.insert_header((header::RETRY_AFTER, retry_after_secs.to_string()));
+        }
+
+        response.json(ErrorBody {
+            error: ErrorDetail {
+                code: self.code(),
+                message: self.public_message(),
+                details: self.details(),
+                request_id,
+            },
+        })
+    }
+}
+
+/// Whether the error is a Postgres unique constraint violation.
+pub fn is_unique_violation(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .and_then(|e| e.code())
+        .map(|code| code == "23505")
+        .unwrap_or(false)
+}
+
+/// Whether the error is a Postgres foreign key violation.
+pub fn is_foreign_key_violation(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .and_then(|e| e.code())
+        .map(|code| code == "23503")
+        .unwrap_or(false)
+}
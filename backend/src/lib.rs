@@ -0,0 +1,171 @@
+use actix_web::{dev::HttpServiceFactory, web};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+pub mod audit;
+pub mod auth;
+pub mod config;
+pub mod error;
+pub mod metrics;
+pub mod migrate;
+pub mod models;
+pub mod openapi;
+pub mod queue;
+pub mod rate_limit;
+pub mod repositories;
+pub mod request_id;
+pub mod routes;
+pub mod services;
+pub mod telemetry;
+pub mod versioning;
+
+use crate::{
+    auth::jwt::JwtConfig,
+    config::{ApiConfig, OidcConfig, RateLimitConfig, WebhookConfig},
+    error::AppError,
+    metrics::Metrics,
+    queue::Queue,
+    rate_limit::{MemoryStore, RateLimiter},
+    repositories::{PgCompanyRepository, PgIdentityRepository, PgUserRepository, PgWebhookRepository},
+    routes::health::Readiness,
+    services::{OidcService, Services, WebhookService},
+    versioning::{deprecation_headers, ApiVersion, Deprecation},
+};
+
+/// Everything the HTTP layer needs, shared by every worker.
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: PgPool,
+    pub services: Services,
+    pub queue: Queue,
+    pub jwt_config: JwtConfig,
+    pub readiness: Arc<Readiness>,
+    pub metrics: Arc<Metrics>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub api: ApiConfig,
+}
+
+impl AppState {
+    pub fn new(pool: PgPool, jwt_config: JwtConfig) -> Self {
+        let services = Services::postgres(&pool);
+        let queue = Queue::new(pool.clone());
+        Self {
+            pool,
+            services,
+            queue,
+            jwt_config,
+            readiness: Arc::default(),
+            metrics: Arc::default(),
+            rate_limiter: Arc::new(RateLimiter::new(
+                RateLimitConfig::default(),
+                Arc::new(MemoryStore::default()),
+            )),
+            api: ApiConfig::default(),
+        }
+    }
+
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Arc::new(rate_limiter);
+        self
+    }
+
+    /// Enables login through the OpenID Connect providers in `config`.
+    pub fn with_oidc(mut self, config: &OidcConfig) -> Self {
+        self.services.oidc = Arc::new(OidcService::new(
+            config,
+            Arc::new(PgUserRepository::new(self.pool.clone())),
+            Arc::new(PgIdentityRepository::new(self.pool.clone())),
+        ));
+        self
+    }
+
+    /// Delivers webhooks with the timeouts and retry policy in `config`.
+    pub fn with_webhooks(mut self, config: &WebhookConfig) -> Self {
+        self.services.webhooks = Arc::new(WebhookService::new(
+            config,
+            Arc::new(PgWebhookRepository::new(self.pool.clone())),
+            Arc::new(PgCompanyRepository::new(self.pool.clone())),
+        ));
+        self
+    }
+
+    /// Announces the deprecation schedule in `config` on the old API versions.
+    pub fn with_api(mut self, config: &ApiConfig) -> Self {
+        self.api = config.clone();
+        self
+    }
+}
+
+/// Registers the shared state, extractor error handlers, the probe routes, the
+/// API docs and every API version under `/api`.
+///
+/// Outer middleware such as CORS and [`request_id::RequestIdMiddleware`] is left
+/// to the caller.
+pub fn configure(cfg: &mut web::ServiceConfig, state: &AppState) {
+    cfg.app_data(web::Data::new(state.pool.clone()))
+        .app_data(web::Data::new(state.jwt_config.clone()))
+        .app_data(web::Data::new(state.queue.clone()))
+        .app_data(web::Data::from(state.services.users.clone()))
+        .app_data(web::Data::from(state.services.jobs.clone()))
+        .app_data(web::Data::from(state.services.companies.clone()))
+        .app_data(web::Data::from(state.services.applications.clone()))
+        .app_data(web::Data::from(state.services.offers.clone()))
+        .app_data(web::Data::from(state.services.reviews.clone()))
+        .app_data(web::Data::from(state.services.admin.clone()))
+        .app_data(web::Data::from(state.services.two_factor.clone()))
+        .app_data(web::Data::from(state.services.oidc.clone()))
+        .app_data(web::Data::from(state.services.api_keys.clone()))
+        .app_data(web::Data::from(state.services.webhooks.clone()))
+        .app_data(web::Data::from(state.services.audit.clone()))
+        .app_data(web::Data::from(state.readiness.clone()))
+        .app_data(web::Data::from(state.metrics.clone()))
+        .app_data(web::Data::from(state.rate_limiter.clone()))
+        .app_data(web::JsonConfig::default().error_handler(|err, _| {
+            AppError::BadRequest(err.to_string()).into()
+        }))
+        .app_data(web::QueryConfig::default().error_handler(|err, _| {
+            AppError::BadRequest(err.to_string()).into()
+        }))
+        .app_data(web::PathConfig::default().error_handler(|_, _| {
+            AppError::NotFound("Not found".to_string()).into()
+        }))
+        .service(openapi::swagger_ui())
+        .configure(routes::health::health_routes)
+        .configure(routes::metrics::metrics_routes)
+        .configure(routes::well_known::well_known_routes);
+
+    for version in ApiVersion::ALL {
+        let deprecation = Deprecation::of_version(&state.api, version);
+        cfg.service(api_scope(&version.prefix(), version, deprecation.as_ref(), state));
+    }
+
+    // Registered last: `/api` would otherwise swallow the versioned prefixes.
+    let legacy = Deprecation::of_legacy(&state.api);
+    cfg.service(openapi::legacy_document(&legacy))
+        .service(api_scope("/api", ApiVersion::V1, Some(&legacy), state));
+}
+
+/// Every route of `version`, served under `path`.
+fn api_scope(
+    path: &str,
+    version: ApiVersion,
+    deprecation: Option<&Deprecation>,
+    state: &AppState,
+) -> impl HttpServiceFactory {
+    web::scope(path)
+        .wrap(deprecation_headers(deprecation))
+        .wrap(metrics::RequestMetrics::new(state.metrics.clone()))
+        .service(routes::auth::auth_scope())
+        .service(routes::companies::public_companies_scope())
+        .service(
+            web::scope("")
+                .wrap(auth::middleware::AuthMiddleware::new(state.jwt_config.clone()))
+                .service(routes::jobs::jobs_scope(version))
+                .service(routes::companies::companies_scope())
+                .service(routes::users::users_scope())
+                .service(routes::applications::applications_scope())
+                .service(routes::offers::offers_scope())
+                .service(routes::reviews::reviews_scope())
+                .service(routes::admin::admin_scope())
+        )
+}
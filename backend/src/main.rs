@@ -1,57 +1,233 @@
-use actix_web::{web, App, HttpServer};
-use actix_cors::Cors;
-use sqlx::postgres::PgPoolOptions;
+use actix_web::{dev::ServerHandle, App, HttpServer};
 use dotenv::dotenv;
-use std::env;
-use chrono::Duration;
+use std::time::Duration as StdDuration;
+use careerhub_backend::{
+    config::Config,
+    migrate::{self, MigrationState},
+    queue,
+    rate_limit::RateLimiter,
+    request_id,
+    routes::health::Readiness,
+    telemetry, AppState,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
 
-mod models;
-mod routes;
-mod auth;
+const USAGE: &str = "usage: careerhub-backend [worker | migrate <up|status|revert>]";
+
+enum Command {
+    Serve,
+    /// Runs background jobs only.
+    Worker,
+    Migrate(MigrateCommand),
+}
+
+enum MigrateCommand {
+    Up,
+    Status,
+    Revert,
+}
+
+impl Command {
+    fn parse(args: &[String]) -> Option<Self> {
+        match args {
+            [] => Some(Command::Serve),
+            [worker] if worker == "worker" => Some(Command::Worker),
+            [migrate, sub] if migrate == "migrate" => match sub.as_str() {
+                "up" => Some(Command::Migrate(MigrateCommand::Up)),
+                "status" => Some(Command::Migrate(MigrateCommand::Status)),
+                "revert" => Some(Command::Migrate(MigrateCommand::Revert)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
-    env_logger::init();
 
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(command) = Command::parse(&args) else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Configuration error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let telemetry = match telemetry::init(&config) {
+        Ok(telemetry) => telemetry,
+        Err(e) => {
+            eprintln!("Failed to initialise telemetry: {}", e);
+            std::process::exit(1);
+        }
+    };
+    tracing::info!("Starting in {:?} mode", config.environment);
 
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
+    let pool = config
+        .database
+        .pool_options()
+        .connect(&config.database.url)
         .await
         .expect("Failed to create pool");
 
-    let jwt_config = auth::jwt::JwtConfig {
-        secret: jwt_secret,
-        expiration: Duration::hours(24),
+    if let Command::Migrate(command) = command {
+        if let Err(e) = run_migrate(command, &pool).await {
+            eprintln!("Migration failed: {}", e);
+            std::process::exit(1);
+        }
+        telemetry.shutdown();
+        return Ok(());
+    }
+
+    if config.database.migrate_on_startup {
+        if let Err(e) = migrate::up(&pool).await {
+            tracing::error!(error = %e, "Failed to apply migrations");
+            std::process::exit(1);
+        }
+        tracing::info!("Database schema is up to date");
+    }
+
+    let jwt_config = match config.jwt.jwt_config() {
+        Ok(jwt_config) => jwt_config,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to load JWT keys");
+            std::process::exit(1);
+        }
     };
 
-    HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_method()
-            .allow_any_header()
-            .max_age(3600);
+    let rate_limiter = RateLimiter::from_config(&config.rate_limit, &pool);
+    let state = AppState::new(pool, jwt_config)
+        .with_rate_limiter(rate_limiter)
+        .with_oidc(&config.oidc)
+        .with_webhooks(&config.webhooks)
+        .with_api(&config.api);
+
+    if let Command::Worker = command {
+        queue::tasks::worker(&state, &config).run(shutdown_signal()).await;
+        telemetry.shutdown();
+        return Ok(());
+    }
+
+    // Drop lapsed rate-limit counters so the store does not grow without bound
+    let rate_limits = state.rate_limiter.clone();
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(StdDuration::from_secs(5 * 60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = rate_limits.purge_expired().await {
+                tracing::error!(error = %e, "Failed to purge rate-limit counters");
+            }
+        }
+    });
 
+    // Expire offers, purge deleted rows, deliver webhooks and run whatever
+    // else is queued, unless separate worker processes take care of it
+    if config.queue.run_with_server {
+        actix_rt::spawn(queue::tasks::worker(&state, &config).run(std::future::pending()));
+    }
+
+    let readiness = state.readiness.clone();
+    let cors = config.cors.clone();
+    let mut server = HttpServer::new(move || {
         App::new()
-            .wrap(cors)
-            .app_data(web::Data::new(pool.clone()))
-            .app_data(web::Data::new(jwt_config.clone()))
-            .service(
-                web::scope("/api")
-                    .service(routes::auth::auth_scope())
-                    .service(
-                        web::scope("")
-                            .wrap(auth::middleware::AuthMiddleware::new(jwt_config.clone()))
-                            .service(routes::jobs::jobs_scope())
-                            .service(routes::companies::companies_scope())
-                            .service(routes::users::users_scope())
-                    )
-            )
+            .wrap(cors.cors())
+            .wrap(telemetry::RequestTracing)
+            .wrap(request_id::RequestIdMiddleware)
+            .configure(|cfg| careerhub_backend::configure(cfg, &state))
     })
-    .bind(("127.0.0.1", 8080))?
-    .run()
-        .await
-}
\ No newline at end of file
+    .keep_alive(StdDuration::from_secs(config.server.keep_alive_secs))
+    .client_request_timeout(StdDuration::from_secs(config.server.client_request_timeout_secs))
+    .shutdown_timeout(config.server.shutdown_timeout_secs)
+    .disable_signals();
+
+    if let Some(workers) = config.server.workers {
+        server = server.workers(workers);
+    }
+
+    let server = server
+        .bind((config.server.host.as_str(), config.server.port))?
+        .run();
+
+    actix_rt::spawn(shutdown_on_signal(
+        server.handle(),
+        readiness,
+        StdDuration::from_secs(config.server.drain_secs),
+    ));
+
+    let result = server.await;
+    telemetry.shutdown();
+
+    result
+}
+
+/// On SIGTERM or Ctrl-C, fails readiness for `drain` so load balancers stop
+/// routing here, then stops the server once in-flight requests finish. A
+/// second signal stops it immediately.
+async fn shutdown_on_signal(handle: ServerHandle, readiness: Arc<Readiness>, drain: StdDuration) {
+    shutdown_signal().await;
+    tracing::info!("Shutdown requested, draining for {:?}", drain);
+    readiness.start_draining();
+
+    let graceful = tokio::select! {
+        _ = actix_rt::time::sleep(drain) => true,
+        _ = shutdown_signal() => false,
+    };
+
+    tracing::info!("Stopping server");
+    handle.stop(graceful).await;
+}
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+}
+
+async fn run_migrate(command: MigrateCommand, pool: &PgPool) -> Result<(), sqlx::migrate::MigrateError> {
+    match command {
+        MigrateCommand::Up => {
+            migrate::up(pool).await?;
+            println!("Database schema is up to date");
+        }
+        MigrateCommand::Status => {
+            for status in migrate::status(pool).await? {
+                let state = match status.state {
+                    MigrationState::Applied => "applied",
+                    MigrationState::Pending => "pending",
+                    MigrationState::Modified => "modified",
+                    MigrationState::Missing => "missing",
+                };
+                println!("{:<8} {} {}", state, status.version, status.description);
+            }
+        }
+        MigrateCommand::Revert => match migrate::revert(pool).await? {
+            Some(version) => println!("Reverted migration {}", version),
+            None => println!("No migrations to revert"),
+        },
+    }
+
+    Ok(())
+}
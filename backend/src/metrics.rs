@@ -0,0 +1,225 @@
+//! Prometheus metrics: per-route HTTP traffic, connection pool state and
+//! domain events, exposed in text format on `/metrics`.
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use prometheus::{
+    Encoder, Gauge, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+use sqlx::PgPool;
+use std::{rc::Rc, sync::Arc, time::Instant};
+use crate::routes::health::BUILD_INFO;
+
+/// Prefix of every metric name.
+const NAMESPACE: &str = "careerhub";
+
+/// Route label for requests that matched no route, so that probing random
+/// paths cannot create unbounded label values.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    pool_connections: IntGaugeVec,
+    pool_max_connections: IntGauge,
+    pool_acquire_wait: Gauge,
+    pub registrations: IntCounter,
+    pub logins: IntCounter,
+    pub failed_logins: IntCounter,
+    pub jobs_posted: IntCounter,
+    pub applications_submitted: IntCounter,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some(NAMESPACE.to_string()), None).expect("valid namespace");
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled, by route and status"),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency, by route"),
+            &["method", "route"],
+        )
+        .unwrap();
+        let pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database connections, by state"),
+            &["state"],
+        )
+        .unwrap();
+        let pool_max_connections =
+            IntGauge::new("db_pool_max_connections", "Configured size limit of the connection pool").unwrap();
+        let pool_acquire_wait = Gauge::new(
+            "db_pool_acquire_wait_seconds",
+            "Time the latest scrape waited to check a connection out of the pool",
+        )
+        .unwrap();
+        let build_info = IntGaugeVec::new(
+            Opts::new("build_info", "Always 1; labelled with the running build"),
+            &["version", "git_sha"],
+        )
+        .unwrap();
+
+        let registrations = IntCounter::new("registrations_total", "Accounts registered").unwrap();
+        let logins = IntCounter::new("logins_total", "Successful logins").unwrap();
+        let failed_logins = IntCounter::new("failed_logins_total", "Rejected login attempts").unwrap();
+        let jobs_posted = IntCounter::new("jobs_posted_total", "Jobs created").unwrap();
+        let applications_submitted =
+            IntCounter::new("applications_submitted_total", "Job applications submitted").unwrap();
+
+        registry.register(Box::new(http_requests.clone())).unwrap();
+        registry.register(Box::new(http_request_duration.clone())).unwrap();
+        registry.register(Box::new(pool_connections.clone())).unwrap();
+        registry.register(Box::new(pool_max_connections.clone())).unwrap();
+        registry.register(Box::new(pool_acquire_wait.clone())).unwrap();
+        registry.register(Box::new(build_info.clone())).unwrap();
+        registry.register(Box::new(registrations.clone())).unwrap();
+        registry.register(Box::new(logins.clone())).unwrap();
+        registry.register(Box::new(failed_logins.clone())).unwrap();
+        registry.register(Box::new(jobs_posted.clone())).unwrap();
+        registry.register(Box::new(applications_submitted.clone())).unwrap();
+
+        build_info
+            .with_label_values(&[BUILD_INFO.version, BUILD_INFO.git_sha])
+            .set(1);
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            pool_connections,
+            pool_max_connections,
+            pool_acquire_wait,
+            registrations,
+            logins,
+            failed_logins,
+            jobs_posted,
+            applications_submitted,
+        }
+    }
+
+    fn observe_request(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        self.http_requests
+            .with_label_values(&[method, route, &status.to_string()])
+            .inc();
+        self.http_request_duration
+            .with_label_values(&[method, route])
+            .observe(seconds);
+    }
+
+    /// Samples the pool and renders every metric in the Prometheus text format.
+    pub async fn render(&self, pool: &PgPool) -> String {
+        let size = i64::from(pool.size());
+        let idle = i64::try_from(pool.num_idle()).unwrap_or(i64::MAX);
+        self.pool_connections.with_label_values(&["in_use"]).set(size - idle);
+        self.pool_connections.with_label_values(&["idle"]).set(idle);
+        self.pool_max_connections
+            .set(i64::from(pool.options().get_max_connections()));
+
+        let started = Instant::now();
+        match pool.acquire().await {
+            Ok(_) => self.pool_acquire_wait.set(started.elapsed().as_secs_f64()),
+            Err(e) => tracing::warn!(error = %e, "Failed to sample connection pool"),
+        }
+
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding cannot fail");
+
+        String::from_utf8(buffer).expect("text format is UTF-8")
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Counts requests and measures their latency, labelled by the matched route
+/// pattern (`/api/v1/jobs/{job_id}`) rather than the raw path.
+pub struct RequestMetrics {
+    metrics: Arc<Metrics>,
+}
+
+impl RequestMetrics {
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestMetricsService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsService {
+            service: Rc::new(service),
+            metrics: self.metrics.clone(),
+        }))
+    }
+}
+
+pub struct RequestMetricsService<S> {
+    service: Rc<S>,
+    metrics: Arc<Metrics>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let metrics = self.metrics.clone();
+        let method = req.method().to_string();
+        let started = Instant::now();
+
+        Box::pin(async move {
+            let result = service.call(req).await;
+            let seconds = started.elapsed().as_secs_f64();
+
+            match &result {
+                Ok(res) => {
+                    let route = res.request().match_pattern();
+                    metrics.observe_request(
+                        &method,
+                        route.as_deref().unwrap_or(UNMATCHED_ROUTE),
+                        res.status().as_u16(),
+                        seconds,
+                    );
+                }
+                Err(e) => {
+                    let status = e.as_response_error().status_code().as_u16();
+                    metrics.observe_request(&method, UNMATCHED_ROUTE, status, seconds);
+                }
+            }
+
+            result
+        })
+    }
+}
@@ -0,0 +1,123 @@
+//! Schema migrations, embedded from `migrations/` at build time.
+
+use sqlx::{
+    migrate::{Migrate, MigrateError, Migrator},
+    PgPool,
+};
+use std::collections::HashMap;
+
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the file has been edited since.
+    Modified,
+    /// Recorded as applied but no longer present in `migrations/`.
+    Missing,
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+/// Applies every pending migration.
+pub async fn up(pool: &PgPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await
+}
+
+/// Every migration known to the binary or the database, oldest first.
+pub async fn status(pool: &PgPool) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+
+    let mut applied: HashMap<i64, _> = conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| (migration.version, migration))
+        .collect();
+
+    let mut statuses: Vec<MigrationStatus> = MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| {
+            let state = match applied.remove(&migration.version) {
+                Some(done) if done.checksum == migration.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::Modified,
+                None => MigrationState::Pending,
+            };
+
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                state,
+            }
+        })
+        .collect();
+
+    statuses.extend(applied.into_keys().map(|version| MigrationStatus {
+        version,
+        description: String::new(),
+        state: MigrationState::Missing,
+    }));
+    statuses.sort_by_key(|status| status.version);
+
+    Ok(statuses)
+}
+
+/// Reverts the most recently applied migration and returns its version, or
+/// `None` if no migration is applied.
+pub async fn revert(pool: &PgPool) -> Result<Option<i64>, MigrateError> {
+    let mut applied: Vec<i64> = {
+        let mut conn = pool.acquire().await?;
+        conn.ensure_migrations_table().await?;
+        conn.list_applied_migrations()
+            .await?
+            .into_iter()
+            .map(|migration| migration.version)
+            .collect()
+    };
+    applied.sort_unstable();
+
+    let Some(latest) = applied.pop() else {
+        return Ok(None);
+    };
+
+    let reversible = MIGRATOR
+        .iter()
+        .any(|migration| migration.version == latest && migration.migration_type.is_down_migration());
+    if !reversible {
+        return Err(MigrateError::VersionMissing(latest));
+    }
+
+    MIGRATOR.undo(pool, applied.last().copied().unwrap_or(0)).await?;
+
+    Ok(Some(latest))
+}
+
+/// Number of migrations the database has not applied yet. Unlike [`status`],
+/// this never creates the migrations table, so it is safe to poll.
+pub async fn pending(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let tracked = sqlx::query_scalar!(r#"SELECT to_regclass('_sqlx_migrations') IS NOT NULL as "tracked!""#)
+        .fetch_one(pool)
+        .await?;
+
+    let applied: Vec<i64> = if tracked {
+        sqlx::query_scalar!("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .filter(|migration| !applied.contains(&migration.version))
+        .count())
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+use crate::{models::users::UserRole, queue::JobStatus};
+
+/// A user as shown to admins: no credentials, plus moderation state.
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct AdminUserView {
+    pub id: Uuid,
+    pub email: String,
+    pub name: Option<String>,
+    pub role: UserRole,
+    pub company_id: Option<Uuid>,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub suspension_reason: Option<String>,
+    pub tokens_revoked_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UserSearchQuery {
+    /// Matched case-insensitively against email and name.
+    pub q: Option<String>,
+    pub role: Option<UserRole>,
+    pub suspended: Option<bool>,
+    /// Soft-deleted users are hidden unless this is `true`.
+    #[serde(default)]
+    pub deleted: bool,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct QueueQuery {
+    pub status: Option<JobStatus>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SuspendUserDto {
+    #[validate(length(min = 1, max = 500))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangeRoleDto {
+    pub role: UserRole,
+    /// Required when promoting to recruiter.
+    pub company_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TakedownDto {
+    #[validate(length(min = 1, max = 500))]
+    pub reason: String,
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use std::{fmt, str::FromStr};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// What an API key may do. Keys only ever act for their own company.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum ApiKeyScope {
+    /// Create and update the company's jobs.
+    #[serde(rename = "jobs:write")]
+    JobsWrite,
+    /// Read applications to the company's jobs.
+    #[serde(rename = "applications:read")]
+    ApplicationsRead,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::JobsWrite => "jobs:write",
+            ApiKeyScope::ApplicationsRead => "applications:read",
+        }
+    }
+}
+
+impl fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiKeyScope {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "jobs:write" => Ok(ApiKeyScope::JobsWrite),
+            "applications:read" => Ok(ApiKeyScope::ApplicationsRead),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A company's key for machine access. Inserted into the request extensions
+/// by the auth middleware when a request authenticates with one.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    /// `None` once the user who created the key has been purged.
+    pub created_by: Option<Uuid>,
+    pub name: String,
+    /// Public part of the key, shown in listings so a key can be recognised.
+    pub prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// Mirrors [`User::can_manage_company`](crate::models::users::User::can_manage_company).
+    pub fn can_manage_company(&self, company_id: Uuid) -> bool {
+        self.company_id == company_id
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateApiKeyDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<ApiKeyScope>,
+    /// Never expires when absent.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A newly created key. The full key is only ever shown here.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+/// A key about to be stored.
+#[derive(Debug)]
+pub struct NewApiKey {
+    pub company_id: Uuid,
+    pub created_by: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -3,18 +3,36 @@ use sqlx::{Type, FromRow};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use validator::Validate;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, Type)]
-#[sqlx(type_name = "application_status", rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "application_status", rename_all = "snake_case")]
 pub enum ApplicationStatus {
     Pending,
     UnderReview,
     Shortlisted,
     Rejected,
     Accepted,
+    Closed,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+impl ApplicationStatus {
+    /// Applications still in the hiring pipeline, i.e. ones that can receive an offer.
+    pub fn is_open(self) -> bool {
+        matches!(
+            self,
+            ApplicationStatus::Pending | ApplicationStatus::UnderReview | ApplicationStatus::Shortlisted
+        )
+    }
+
+    /// Whether an application may move from this status to `next`: open ones
+    /// move freely within the pipeline or out of it, closed ones stay closed.
+    pub fn can_move_to(self, next: ApplicationStatus) -> bool {
+        self == next || self.is_open()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Application {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -26,7 +44,7 @@ pub struct Application {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct ApplicationWithDetails {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -42,7 +60,7 @@ pub struct ApplicationWithDetails {
     pub user_name: String,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateApplicationDto {
     pub job_id: Uuid,
     #[validate(url)]
@@ -50,7 +68,7 @@ pub struct CreateApplicationDto {
     pub cover_letter: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateApplicationStatusDto {
     pub status: ApplicationStatus,
-} 
\ No newline at end of file
+}
@@ -1,37 +0,0 @@
-use serde::{Deserialize, Serialize};
-use sqlx::Type;
-use uuid::Uuid;
-
-#[derive(Debug, Serialize, Deserialize, Type, Clone)]
-#[sqlx(type_name = "user_role", rename_all = "lowercase")]
-pub enum UserRole {
-    User,
-    Admin,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AuthUser {
-    pub id: Uuid,
-    pub email: String,
-    pub role: UserRole,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct LoginRequest {
-    pub email: String,
-    pub password: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RegisterRequest {
-    pub email: String,
-    pub password: String,
-    pub first_name: String,
-    pub last_name: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AuthResponse {
-    pub token: String,
-    pub user: AuthUser,
-} 
\ No newline at end of file
@@ -1,21 +1,63 @@
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, Type};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use std::fmt;
 use validator::Validate;
+use utoipa::ToSchema;
+use crate::models::jobs::Job;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "company_size", rename_all = "lowercase")]
+pub enum CompanySize {
+    Small,
+    Medium,
+    Large,
+    Enterprise,
+}
+
+impl fmt::Display for CompanySize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompanySize::Small => write!(f, "small"),
+            CompanySize::Medium => write!(f, "medium"),
+            CompanySize::Large => write!(f, "large"),
+            CompanySize::Enterprise => write!(f, "enterprise"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Company {
     pub id: Uuid,
     pub name: String,
+    /// URL-safe identifier for the public company page, fixed at creation.
+    pub slug: String,
     pub description: Option<String>,
     pub location: Option<String>,
     pub website: Option<String>,
+    pub logo_url: Option<String>,
+    pub industry: Option<String>,
+    pub size: Option<CompanySize>,
+    /// Set by an admin once the company's identity has been checked.
+    pub verified: bool,
+    /// Average overall rating of approved reviews, cached on moderation.
+    pub rating_average: Option<f64>,
+    pub review_count: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+/// Public company page: the profile plus its currently open jobs.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CompanyPage {
+    #[serde(flatten)]
+    pub company: Company,
+    pub open_jobs: Vec<Job>,
+    pub job_count: i64,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateCompanyDto {
     #[validate(length(min = 1, max = 100))]
     pub name: String,
@@ -24,9 +66,14 @@ pub struct CreateCompanyDto {
     pub location: Option<String>,
     #[validate(url)]
     pub website: Option<String>,
+    #[validate(url)]
+    pub logo_url: Option<String>,
+    #[validate(length(min = 2, max = 100))]
+    pub industry: Option<String>,
+    pub size: Option<CompanySize>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateCompanyDto {
     #[validate(length(min = 1, max = 100))]
     pub name: Option<String>,
@@ -35,4 +82,40 @@ pub struct UpdateCompanyDto {
     pub location: Option<String>,
     #[validate(url)]
     pub website: Option<String>,
-} 
\ No newline at end of file
+    #[validate(url)]
+    pub logo_url: Option<String>,
+    #[validate(length(min = 2, max = 100))]
+    pub industry: Option<String>,
+    pub size: Option<CompanySize>,
+}
+
+impl UpdateCompanyDto {
+    /// Whether the payload sets no field at all.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.description.is_none()
+            && self.location.is_none()
+            && self.website.is_none()
+            && self.logo_url.is_none()
+            && self.industry.is_none()
+            && self.size.is_none()
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyCompanyDto {
+    pub verified: bool,
+}
+
+/// Lowercases `name` and collapses every run of non-alphanumerics into a single `-`.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
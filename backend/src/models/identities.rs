@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// An account at an OpenID Connect provider, linked to a local user.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Identity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: DateTime<Utc>,
+}
+
+/// What is remembered about an authorization request until the user comes back.
+#[derive(Debug, FromRow)]
+pub struct OidcLoginState {
+    pub state: String,
+    pub provider: String,
+    pub code_verifier: String,
+    pub nonce: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OidcProviders {
+    pub providers: Vec<String>,
+}
+
+/// Where to send the user to sign in at a provider.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OidcAuthorization {
+    pub authorization_url: String,
+    pub expires_in: i64,
+}
+
+/// The parameters the provider appended to the redirect URI.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct OidcCallbackDto {
+    #[validate(length(min = 1, max = 2048))]
+    pub code: String,
+    #[validate(length(min = 1, max = 64))]
+    pub state: String,
+}
@@ -1,11 +1,15 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::{Type, FromRow};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use std::fmt;
 use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Serialize, Deserialize, Type)]
+/// Largest page of jobs listed at once.
+pub const MAX_PER_PAGE: i64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
 #[sqlx(type_name = "job_type", rename_all = "lowercase")]
 pub enum JobType {
     Fulltime,
@@ -27,7 +31,7 @@ impl fmt::Display for JobType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
 #[sqlx(type_name = "experience_level", rename_all = "lowercase")]
 pub enum ExperienceLevel {
     Entry,
@@ -49,7 +53,7 @@ impl fmt::Display for ExperienceLevel {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Job {
     pub id: Uuid,
     pub title: String,
@@ -61,11 +65,14 @@ pub struct Job {
     pub salary_range: serde_json::Value,
     pub skills: Vec<String>,
     pub is_active: bool,
+    pub close_applications_on_accept: bool,
+    /// Mirrors the owning company's admin-controlled `verified` flag.
+    pub company_verified: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateJobDto {
     #[validate(length(min = 1, max = 100))]
     pub title: String,
@@ -78,9 +85,12 @@ pub struct CreateJobDto {
     pub experience_level: ExperienceLevel,
     pub salary_range: serde_json::Value,
     pub skills: Vec<String>,
+    /// Close every other open application on this job once an offer is accepted.
+    #[serde(default)]
+    pub close_applications_on_accept: bool,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateJobDto {
     #[validate(length(min = 1, max = 100))]
     pub title: Option<String>,
@@ -93,16 +103,57 @@ pub struct UpdateJobDto {
     pub salary_range: Option<serde_json::Value>,
     pub skills: Option<Vec<String>>,
     pub is_active: Option<bool>,
+    pub close_applications_on_accept: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct JobQuery {
     pub title: Option<String>,
     pub location: Option<String>,
     pub job_type: Option<JobType>,
     pub experience_level: Option<ExperienceLevel>,
+    /// Comma-separated; a job must list every one of them.
+    #[serde(default, deserialize_with = "comma_separated")]
+    #[param(value_type = Option<String>)]
     pub skills: Option<Vec<String>>,
     pub search: Option<String>,
     pub page: Option<i64>,
     pub per_page: Option<i64>,
-} 
\ No newline at end of file
+}
+
+impl JobQuery {
+    /// The requested page, counting from 1.
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> i64 {
+        self.per_page.unwrap_or(10).clamp(1, MAX_PER_PAGE)
+    }
+}
+
+/// A page of [`JobQuery`] results, as listed since API v2.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobPage {
+    pub items: Vec<Job>,
+    pub page: i64,
+    pub per_page: i64,
+    /// Jobs matching the query across all pages.
+    pub total: i64,
+}
+
+fn comma_separated<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    Ok(value.map(|value| {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|skill| !skill.is_empty())
+            .map(str::to_string)
+            .collect()
+    }))
+}
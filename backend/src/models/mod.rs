@@ -1,7 +1,13 @@
 pub mod users;
 pub mod jobs;
 pub mod companies;
-
-pub use users::*;
-// pub use jobs::*;
-// pub use companies::*;
\ No newline at end of file
+pub mod applications;
+pub mod offers;
+pub mod reviews;
+pub mod stats;
+pub mod admin;
+pub mod audit;
+pub mod two_factor;
+pub mod identities;
+pub mod api_keys;
+pub mod webhooks;
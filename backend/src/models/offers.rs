@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+use crate::models::applications::ApplicationStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "offer_status", rename_all = "lowercase")]
+pub enum OfferStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Expired,
+    Withdrawn,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Offer {
+    pub id: Uuid,
+    pub application_id: Uuid,
+    pub job_id: Uuid,
+    pub created_by: Uuid,
+    /// Annual salary, denominated in `salary_currency`.
+    pub salary_amount: i64,
+    /// Copied from the job's `salary_range.currency` when the offer is made.
+    pub salary_currency: String,
+    pub start_date: NaiveDate,
+    pub expires_at: DateTime<Utc>,
+    pub terms_url: Option<String>,
+    pub status: OfferStatus,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateOfferDto {
+    pub application_id: Uuid,
+    #[validate(range(min = 1))]
+    pub salary_amount: i64,
+    pub start_date: NaiveDate,
+    pub expires_at: DateTime<Utc>,
+    #[validate(url)]
+    pub terms_url: Option<String>,
+}
+
+/// The candidate an offer is addressed to and the company making it.
+#[derive(Debug, Clone, Copy)]
+pub struct OfferParties {
+    pub candidate_id: Uuid,
+    pub company_id: Uuid,
+}
+
+/// The application an offer is made for, with what the offer copies from its job.
+#[derive(Debug)]
+pub struct OfferTarget {
+    pub job_id: Uuid,
+    pub status: ApplicationStatus,
+    pub company_id: Uuid,
+    /// The currency of the job's salary range.
+    pub currency: Option<String>,
+}
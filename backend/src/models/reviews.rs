@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "review_status", rename_all = "lowercase")]
+pub enum ReviewStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "employment_status", rename_all = "lowercase")]
+pub enum EmploymentStatus {
+    Current,
+    Former,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CompanyReview {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+    pub overall_rating: i16,
+    pub work_life_balance_rating: Option<i16>,
+    pub compensation_rating: Option<i16>,
+    pub culture_rating: Option<i16>,
+    pub management_rating: Option<i16>,
+    pub career_growth_rating: Option<i16>,
+    pub pros: String,
+    pub cons: String,
+    pub employment_status: EmploymentStatus,
+    pub job_title: Option<String>,
+    pub status: ReviewStatus,
+    pub moderation_note: Option<String>,
+    pub moderated_by: Option<Uuid>,
+    pub moderated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateReviewDto {
+    #[validate(length(min = 1, max = 150))]
+    pub title: String,
+    #[validate(range(min = 1, max = 5))]
+    pub overall_rating: i16,
+    #[validate(range(min = 1, max = 5))]
+    pub work_life_balance_rating: Option<i16>,
+    #[validate(range(min = 1, max = 5))]
+    pub compensation_rating: Option<i16>,
+    #[validate(range(min = 1, max = 5))]
+    pub culture_rating: Option<i16>,
+    #[validate(range(min = 1, max = 5))]
+    pub management_rating: Option<i16>,
+    #[validate(range(min = 1, max = 5))]
+    pub career_growth_rating: Option<i16>,
+    #[validate(length(min = 1))]
+    pub pros: String,
+    #[validate(length(min = 1))]
+    pub cons: String,
+    pub employment_status: EmploymentStatus,
+    #[validate(length(max = 100))]
+    pub job_title: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ModerateReviewDto {
+    pub status: ReviewStatus,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewSort {
+    #[default]
+    Newest,
+    Oldest,
+    HighestRated,
+    LowestRated,
+}
+
+impl ReviewSort {
+    pub fn order_by(self) -> &'static str {
+        match self {
+            ReviewSort::Newest => "created_at DESC",
+            ReviewSort::Oldest => "created_at ASC",
+            ReviewSort::HighestRated => "overall_rating DESC, created_at DESC",
+            ReviewSort::LowestRated => "overall_rating ASC, created_at DESC",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReviewQuery {
+    pub sort: Option<ReviewSort>,
+    pub status: Option<ReviewStatus>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl ReviewQuery {
+    /// The requested page, counting from 1.
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> i64 {
+        self.per_page.unwrap_or(10).clamp(1, 100)
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
+
+/// Longest range, in days, accepted for the time-series part of the dashboard.
+pub const MAX_STATS_RANGE_DAYS: i64 = 366;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminStats {
+    pub users: UserStats,
+    pub companies: CompanyStats,
+    pub jobs: JobStats,
+    pub applications: ApplicationStats,
+    pub timeseries: TimeSeries,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserStats {
+    pub total: i64,
+    pub by_role: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CompanyStats {
+    pub total: i64,
+    pub verified: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobStats {
+    pub total: i64,
+    /// Keyed by `active` / `inactive`.
+    pub by_status: BTreeMap<String, i64>,
+    pub by_type: BTreeMap<String, i64>,
+    pub by_experience_level: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApplicationStats {
+    pub total: i64,
+    pub by_status: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimeSeries {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub daily: Vec<DailyCounts>,
+}
+
+/// Rows created on one UTC calendar day.
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct DailyCounts {
+    pub date: NaiveDate,
+    pub signups: i64,
+    pub job_postings: i64,
+    pub applications: i64,
+}
+
+/// One `GROUP BY` bucket, keyed by the enum's text value.
+#[derive(Debug, FromRow)]
+pub struct CountByKey {
+    pub key: String,
+    pub count: i64,
+}
+
+pub fn into_counts(rows: Vec<CountByKey>) -> BTreeMap<String, i64> {
+    rows.into_iter().map(|row| (row.key, row.count)).collect()
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// A user's TOTP secret; `enabled_at` is unset until enrollment is confirmed.
+#[derive(Debug, FromRow)]
+pub struct TotpSecret {
+    pub user_id: Uuid,
+    /// Base32, as shown to authenticator apps.
+    pub secret: String,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+}
+
+/// A TOTP code or, where accepted, a recovery code.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TwoFactorCodeDto {
+    #[validate(length(min = 6, max = 32))]
+    pub code: String,
+}
+
+/// The second step of a login to an account with two-factor authentication.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TwoFactorLoginDto {
+    pub challenge_token: String,
+    #[validate(length(min = 6, max = 32))]
+    pub code: String,
+}
+
+/// Returned by the first login step for accounts with two-factor
+/// authentication, to exchange at `/auth/login/2fa` with a code.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorChallenge {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+    pub expires_in: i64,
+}
+
+/// What an authenticator app needs to start generating codes.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecoveryCodes {
+    /// Shown once; only their hashes are stored.
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub recovery_codes_remaining: i64,
+}
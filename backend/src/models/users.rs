@@ -1,24 +1,36 @@
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool}; // Changed to PgPool for PostgreSQL
+use sqlx::{FromRow, Type};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use validator::Validate;
 use argon2::password_hash::SaltString;
 use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use rand_core::OsRng;
-use sqlx::Error;
+use utoipa::ToSchema;
+use crate::models::two_factor::TwoFactorChallenge;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "user_role", rename_all = "lowercase")]
+pub enum UserRole {
+    User,
+    Recruiter,
+    Admin,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
-    pub password_hash: String,
-    pub name: Option<String>,
+    /// `None` for accounts that only sign in through an OpenID Connect provider.
+    pub password_hash: Option<String>,
+    pub name: String,
+    pub role: UserRole,
+    pub company_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateUserDto {
     #[validate(email)]
     pub email: String,
@@ -28,7 +40,7 @@ pub struct CreateUserDto {
     pub name: String,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateUserDto {
     #[validate(email)]
     pub email: Option<String>,
@@ -38,13 +50,36 @@ pub struct UpdateUserDto {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct LoginDto {
     #[validate(email)]
     pub email: String,
     pub password: String,
 }
 
+/// A bearer token for a signed-in user.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Session {
+    pub token: String,
+    pub user: SessionUser,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionUser {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+}
+
+/// What a login step answers: a session, or a challenge when the account has
+/// two-factor authentication.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Session(Session),
+    TwoFactorRequired(TwoFactorChallenge),
+}
+
 impl User {
     pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
         let salt = SaltString::generate(&mut OsRng);
@@ -58,19 +93,12 @@ impl User {
         Ok(Argon2::default().verify_password(password.as_bytes(), &parsed_hash).is_ok())
     }
 
-    pub async fn get_by_email(db: &PgPool, email: &str) -> Result<Option<Self>, Error> {
-        let user = sqlx::query_as!(
-            Self,
-            r#"
-            SELECT id, email, password_hash, name, created_at, updated_at
-            FROM users
-            WHERE email = $1
-            "#,
-            email
-        )
-        .fetch_optional(db)
-        .await?;
-
-        Ok(user)
+    /// Whether this user may act on behalf of `company_id` (its recruiters, or any admin).
+    pub fn can_manage_company(&self, company_id: Uuid) -> bool {
+        match self.role {
+            UserRole::Admin => true,
+            UserRole::Recruiter => self.company_id == Some(company_id),
+            UserRole::User => false,
+        }
     }
 }
\ No newline at end of file
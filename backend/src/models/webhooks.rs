@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use std::{fmt, str::FromStr};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// Hiring events a webhook endpoint can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum WebhookEvent {
+    /// A candidate applied to one of the company's jobs.
+    #[serde(rename = "application.created")]
+    ApplicationCreated,
+    /// An application to one of the company's jobs moved to a new status.
+    #[serde(rename = "application.status_changed")]
+    ApplicationStatusChanged,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::ApplicationCreated => "application.created",
+            WebhookEvent::ApplicationStatusChanged => "application.status_changed",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "application.created" => Ok(WebhookEvent::ApplicationCreated),
+            "application.status_changed" => Ok(WebhookEvent::ApplicationStatusChanged),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookEndpoint {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateWebhookDto {
+    #[validate(url, length(max = 2048))]
+    pub url: String,
+    #[validate(length(min = 1, message = "At least one event is required"))]
+    pub events: Vec<WebhookEvent>,
+}
+
+/// A newly registered endpoint. The signing secret is only ever shown here.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedWebhookEndpoint {
+    #[serde(flatten)]
+    pub endpoint: WebhookEndpoint,
+    pub secret: String,
+}
+
+/// An endpoint about to be stored.
+#[derive(Debug)]
+pub struct NewWebhookEndpoint {
+    pub company_id: Uuid,
+    pub created_by: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Waiting for its first or next attempt.
+    Pending,
+    Delivered,
+    /// Gave up after the last attempt; can still be redelivered by hand.
+    Failed,
+}
+
+/// One event queued for one endpoint.
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub endpoint_id: Uuid,
+    pub event: String,
+    /// The exact body sent to the endpoint.
+    pub payload: serde_json::Value,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct WebhookAttempt {
+    pub id: Uuid,
+    pub delivery_id: Uuid,
+    /// `None` when no response arrived, e.g. on a timeout.
+    pub response_status: Option<i32>,
+    /// Why the attempt failed. Never the receiver's response body.
+    pub error: Option<String>,
+    pub duration_ms: i32,
+    pub attempted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookDeliveryDetails {
+    #[serde(flatten)]
+    pub delivery: WebhookDelivery,
+    pub attempt_log: Vec<WebhookAttempt>,
+}
+
+/// A delivery claimed for an attempt, with what is needed to send it.
+#[derive(Debug, FromRow)]
+pub struct DueDelivery {
+    pub id: Uuid,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// How an attempt went, and what happens to the delivery next.
+#[derive(Debug)]
+pub struct AttemptOutcome {
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub duration_ms: i32,
+    pub status: DeliveryStatus,
+    /// When to try again while the delivery stays pending.
+    pub next_attempt_at: DateTime<Utc>,
+}
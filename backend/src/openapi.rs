@@ -0,0 +1,197 @@
+//! The OpenAPI documents for the HTTP API, one per [`ApiVersion`], generated
+//! from the `#[utoipa::path]` annotations on the handlers and the schemas
+//! derived on the models.
+//!
+//! Each is served at `/api/<version>/openapi.json`, with Swagger UI for all of
+//! them at `/api/docs/`. The v1 document is also still served at
+//! [`LEGACY_OPENAPI_PATH`], deprecated along with the rest of `/api`. Every
+//! route must be listed in [`ApiDoc`], or in [`ApiDocV2`] when v2 replaced
+//! it; `tests/openapi.rs` fails on any that is not.
+
+use actix_web::{dev::HttpServiceFactory, web, HttpResponse};
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+use utoipa_swagger_ui::{SwaggerUi, Url};
+use crate::{
+    routes::{
+        admin, api_keys, applications, auth, companies, health, jobs, metrics, offers, reviews, users,
+        webhooks, well_known,
+    },
+    versioning::{deprecation_headers, ApiVersion, Deprecation},
+};
+
+/// Where Swagger UI is served.
+pub const DOCS_PATH: &str = "/api/docs";
+
+/// Where the v1 document was served before the API was versioned.
+pub const LEGACY_OPENAPI_PATH: &str = "/api/openapi.json";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "CareerHub API", description = "Jobs, companies and the hiring pipeline around them."),
+    paths(
+        auth::register,
+        auth::login,
+        auth::login_two_factor,
+        auth::oidc_providers,
+        auth::oidc_authorize,
+        auth::oidc_callback,
+        jobs::list_jobs,
+        jobs::get_job,
+        jobs::create_job,
+        jobs::update_job,
+        jobs::delete_job,
+        companies::list_companies,
+        companies::get_company,
+        companies::get_company_page,
+        companies::create_company,
+        companies::update_company,
+        companies::set_company_verified,
+        companies::delete_company,
+        applications::list_my_applications,
+        applications::list_company_applications,
+        applications::create_application,
+        applications::update_application_status,
+        offers::list_offers,
+        offers::get_offer,
+        offers::create_offer,
+        offers::accept_offer,
+        offers::decline_offer,
+        offers::withdraw_offer,
+        reviews::list_company_reviews,
+        reviews::create_review,
+        reviews::update_review,
+        reviews::delete_review,
+        reviews::list_reviews_for_moderation,
+        reviews::moderate_review,
+        users::get_profile,
+        users::update_profile,
+        users::two_factor_status,
+        users::enroll_two_factor,
+        users::confirm_two_factor,
+        users::disable_two_factor,
+        users::regenerate_recovery_codes,
+        api_keys::list_api_keys,
+        api_keys::create_api_key,
+        api_keys::revoke_api_key,
+        webhooks::list_webhooks,
+        webhooks::create_webhook,
+        webhooks::delete_webhook,
+        webhooks::list_deliveries,
+        webhooks::get_delivery,
+        webhooks::redeliver,
+        admin::get_stats,
+        admin::search_users,
+        admin::delete_user,
+        admin::restore_user,
+        admin::suspend_user,
+        admin::unsuspend_user,
+        admin::revoke_user_tokens,
+        admin::change_user_role,
+        admin::take_down_job,
+        admin::restore_job,
+        admin::take_down_company,
+        admin::restore_company,
+        admin::list_audit_events,
+        admin::list_queued_jobs,
+        admin::retry_queued_job,
+        health::healthz,
+        health::readyz,
+        health::version,
+        metrics::metrics,
+        well_known::jwks,
+    ),
+    modifiers(&SecuritySchemes),
+    security(("bearer" = [])),
+)]
+pub struct ApiDoc;
+
+/// The operations v2 changed. The rest of v2 is v1 under the v2 prefix.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "CareerHub API", description = "Jobs, companies and the hiring pipeline around them."),
+    paths(jobs::list_jobs_v2),
+)]
+pub struct ApiDocV2;
+
+/// Where the document for `version` is served.
+pub fn openapi_path(version: ApiVersion) -> &'static str {
+    match version {
+        ApiVersion::V1 => "/api/v1/openapi.json",
+        ApiVersion::V2 => "/api/v2/openapi.json",
+    }
+}
+
+/// The document for `version`.
+pub fn document(version: ApiVersion) -> utoipa::openapi::OpenApi {
+    match version {
+        ApiVersion::V1 => ApiDoc::openapi(),
+        ApiVersion::V2 => {
+            let mut v1 = ApiDoc::openapi();
+            v1.paths.paths = std::mem::take(&mut v1.paths.paths)
+                .into_iter()
+                .map(|(path, item)| match path.strip_prefix("/api/v1/") {
+                    Some(rest) => (format!("/api/v2/{}", rest), item),
+                    None => (path, item),
+                })
+                .collect();
+
+            // Merging keeps the operations already present, so v2's win.
+            let mut v2 = ApiDocV2::openapi();
+            v2.merge(v1);
+            v2
+        }
+    }
+}
+
+/// Swagger UI with a document per version, each also served as JSON.
+pub fn swagger_ui() -> SwaggerUi {
+    let urls = ApiVersion::ALL
+        .into_iter()
+        .map(|version| (Url::new(version.as_str(), openapi_path(version)), document(version)))
+        .collect();
+
+    SwaggerUi::new(format!("{}/{{_:.*}}", DOCS_PATH)).urls(urls)
+}
+
+/// The v1 document at [`LEGACY_OPENAPI_PATH`], with the same deprecation
+/// headers as the rest of `/api`.
+pub fn legacy_document(deprecation: &Deprecation) -> impl HttpServiceFactory {
+    web::resource(LEGACY_OPENAPI_PATH)
+        .app_data(web::Data::new(document(ApiVersion::V1)))
+        .wrap(deprecation_headers(Some(deprecation)))
+        .route(web::get().to(|document: web::Data<utoipa::openapi::OpenApi>| async move {
+            HttpResponse::Ok().json(document.get_ref())
+        }))
+}
+
+/// Both schemes are bearer tokens in the `Authorization` header; routes that
+/// accept API keys list the scope they need.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some("A session token from `/api/v1/auth/login`."))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("A company API key, prefixed `chk_`."))
+                    .build(),
+            ),
+        );
+    }
+}
@@ -0,0 +1,414 @@
+//! A durable background job queue in Postgres.
+//!
+//! Jobs are typed: each implements [`Job`], is stored as its JSON payload
+//! under [`Job::KIND`] and run by a [`Worker`] that has it registered. Workers
+//! claim due jobs with `FOR UPDATE SKIP LOCKED`, so any number of them can
+//! share the queue, whether inside `serve` or in `worker` processes. Failed
+//! jobs are retried with exponential backoff until they run out of attempts,
+//! then kept as dead for an admin to inspect and retry.
+
+pub mod tasks;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use futures::future::BoxFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Type};
+use std::{collections::HashMap, future::Future, rc::Rc, time::Duration as StdDuration};
+use tokio::sync::watch;
+use tracing::Instrument;
+use uuid::Uuid;
+use utoipa::ToSchema;
+use crate::{
+    config::QueueConfig,
+    error::{is_unique_violation, AppError},
+    services::Services,
+    AppState,
+};
+
+/// Work that can be queued and run later, possibly by another process.
+#[async_trait]
+pub trait Job: Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Names the job's handler in the queue, so it must stay stable across
+    /// releases, e.g. `offers.expire`.
+    const KIND: &'static str;
+    /// Runs before the job is given up on and kept as dead.
+    const MAX_ATTEMPTS: i32 = 5;
+
+    async fn run(self, ctx: &JobContext) -> Result<(), AppError>;
+}
+
+/// What a running job has access to.
+#[derive(Clone)]
+pub struct JobContext {
+    pub pool: PgPool,
+    pub services: Services,
+    pub queue: Queue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "background_job_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    /// Waiting for its first or next run.
+    Pending,
+    Running,
+    /// Out of attempts; stays until retried by hand.
+    Dead,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct QueuedJob {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub unique_key: Option<String>,
+    /// Why the last attempt failed.
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Adds jobs to the queue and manages dead ones.
+#[derive(Clone)]
+pub struct Queue {
+    pool: PgPool,
+}
+
+impl Queue {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queues `job` to run as soon as a worker is free.
+    pub async fn enqueue<J: Job>(&self, job: &J) -> Result<Uuid, AppError> {
+        self.enqueue_at(job, Utc::now()).await
+    }
+
+    /// Queues `job` to run once `run_at` has passed.
+    pub async fn enqueue_at<J: Job>(&self, job: &J, run_at: DateTime<Utc>) -> Result<Uuid, AppError> {
+        let id = self.insert(J::KIND, &payload(job)?, J::MAX_ATTEMPTS, run_at, None).await?;
+
+        Ok(id.expect("jobs without a unique key are always queued"))
+    }
+
+    /// Queues `job` unless a job with the same `key` is already waiting or
+    /// running, in which case nothing is queued and `None` returned.
+    pub async fn enqueue_unique<J: Job>(&self, job: &J, key: &str) -> Result<Option<Uuid>, AppError> {
+        Ok(self.insert(J::KIND, &payload(job)?, J::MAX_ATTEMPTS, Utc::now(), Some(key)).await?)
+    }
+
+    /// Jobs oldest first, optionally only those with `status`.
+    pub async fn list(&self, status: Option<JobStatus>, limit: i64, offset: i64) -> Result<Vec<QueuedJob>, AppError> {
+        let jobs = sqlx::query_as!(
+            QueuedJob,
+            r#"
+            SELECT id, kind, payload, status as "status: _", attempts, max_attempts, run_at, locked_until,
+                   unique_key, last_error, created_at, updated_at
+            FROM background_jobs
+            WHERE ($1::background_job_status IS NULL OR status = $1)
+            ORDER BY created_at
+            LIMIT $2 OFFSET $3
+            "#,
+            status as Option<JobStatus>,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    /// Queues a dead job to run again right away, with a fresh set of attempts.
+    pub async fn retry(&self, id: Uuid) -> Result<QueuedJob, AppError> {
+        sqlx::query_as!(
+            QueuedJob,
+            r#"
+            UPDATE background_jobs
+            SET status = 'pending', attempts = 0, run_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'dead'
+            RETURNING id, kind, payload, status as "status: _", attempts, max_attempts, run_at, locked_until,
+                      unique_key, last_error, created_at, updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                AppError::Conflict("A job with the same key is already queued".to_string())
+            } else {
+                e.into()
+            }
+        })?
+        .ok_or_else(|| AppError::NotFound("Dead job not found".to_string()))
+    }
+
+    async fn insert(
+        &self,
+        kind: &str,
+        payload: &serde_json::Value,
+        max_attempts: i32,
+        run_at: DateTime<Utc>,
+        unique_key: Option<&str>,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO background_jobs (kind, payload, max_attempts, run_at, unique_key)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (unique_key) WHERE status IN ('pending', 'running') DO NOTHING
+            RETURNING id
+            "#,
+            kind,
+            payload,
+            max_attempts,
+            run_at,
+            unique_key
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Claims the job of one of `kinds` that has been due the longest, also
+    /// taking back running jobs whose lease expired. The claim lasts until
+    /// `lease_until`.
+    async fn claim(&self, kinds: &[String], lease_until: DateTime<Utc>) -> Result<Option<QueuedJob>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedJob,
+            r#"
+            WITH next AS (
+                SELECT id
+                FROM background_jobs
+                WHERE kind = ANY($1)
+                  AND ((status = 'pending' AND run_at <= NOW())
+                       OR (status = 'running' AND locked_until <= NOW()))
+                ORDER BY run_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE background_jobs j
+            SET status = 'running', attempts = j.attempts + 1, locked_until = $2, updated_at = NOW()
+            FROM next
+            WHERE j.id = next.id
+            RETURNING j.id, j.kind, j.payload, j.status as "status: _", j.attempts, j.max_attempts, j.run_at,
+                      j.locked_until, j.unique_key, j.last_error, j.created_at, j.updated_at
+            "#,
+            kinds,
+            lease_until
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn complete(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM background_jobs WHERE id = $1", id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Records a failed attempt, queueing the job again at `retry_at` or, when
+    /// there is none, keeping it as dead.
+    async fn fail(&self, id: Uuid, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE background_jobs
+            SET status = CASE WHEN $3::timestamptz IS NULL THEN 'dead' ELSE 'pending' END::background_job_status,
+                run_at = COALESCE($3, run_at), locked_until = NULL, last_error = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+            id,
+            error,
+            retry_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn payload<J: Job>(job: &J) -> Result<serde_json::Value, AppError> {
+    serde_json::to_value(job).map_err(|e| AppError::Internal(format!("Failed to serialize {} job: {}", J::KIND, e)))
+}
+
+type Handler = Box<dyn Fn(serde_json::Value, JobContext) -> BoxFuture<'static, Result<(), AppError>> + Send + Sync>;
+
+/// A job queued again whenever its interval passes.
+struct Schedule {
+    kind: &'static str,
+    payload: serde_json::Value,
+    max_attempts: i32,
+    every: StdDuration,
+}
+
+/// A pool of workers running the jobs registered with it.
+pub struct Worker {
+    context: JobContext,
+    config: QueueConfig,
+    handlers: HashMap<&'static str, Handler>,
+    schedules: Vec<Schedule>,
+}
+
+impl Worker {
+    pub fn new(state: &AppState, config: &QueueConfig) -> Self {
+        Self {
+            context: JobContext {
+                pool: state.pool.clone(),
+                services: state.services.clone(),
+                queue: state.queue.clone(),
+            },
+            config: config.clone(),
+            handlers: HashMap::new(),
+            schedules: Vec::new(),
+        }
+    }
+
+    /// Runs jobs of type `J`. Jobs of kinds no worker has registered stay queued.
+    pub fn register<J: Job>(mut self) -> Self {
+        let handler: Handler = Box::new(|payload, ctx| {
+            Box::pin(async move {
+                let job: J = serde_json::from_value(payload)
+                    .map_err(|e| AppError::Internal(format!("Invalid {} payload: {}", J::KIND, e)))?;
+                job.run(&ctx).await
+            })
+        });
+        self.handlers.insert(J::KIND, handler);
+        self
+    }
+
+    /// Registers `J` and queues `job` every `interval`, starting when the pool
+    /// starts. The kind doubles as its unique key, so however many workers
+    /// schedule it, at most one is waiting or running at a time.
+    pub fn every<J: Job>(mut self, interval: StdDuration, job: J) -> Self {
+        let payload = payload(&job).expect("scheduled jobs serialize");
+        self.schedules.push(Schedule {
+            kind: J::KIND,
+            payload,
+            max_attempts: J::MAX_ATTEMPTS,
+            every: interval,
+        });
+        self.register::<J>()
+    }
+
+    /// Runs `concurrency` workers and the schedules until `shutdown`
+    /// completes, then waits for the jobs in progress to finish.
+    pub async fn run(self, shutdown: impl Future<Output = ()>) {
+        let worker = Rc::new(self);
+        let (stop, stopping) = watch::channel(false);
+
+        let mut tasks = Vec::new();
+        for index in 0..worker.schedules.len() {
+            tasks.push(actix_rt::spawn(worker.clone().schedule(index, stopping.clone())));
+        }
+        for _ in 0..worker.config.concurrency {
+            tasks.push(actix_rt::spawn(worker.clone().work(stopping.clone())));
+        }
+        tracing::info!("Started {} background workers", worker.config.concurrency);
+
+        shutdown.await;
+        tracing::info!("Stopping background workers once their jobs finish");
+        stop.send_replace(true);
+        futures::future::join_all(tasks).await;
+    }
+
+    /// Claims and runs one due job, if there is one, returning whether there was.
+    pub async fn work_one(&self) -> Result<bool, AppError> {
+        let queue = &self.context.queue;
+        let kinds: Vec<String> = self.handlers.keys().map(|kind| kind.to_string()).collect();
+        let lease = Duration::seconds(self.config.lease_secs);
+
+        let Some(job) = queue.claim(&kinds, Utc::now() + lease).await? else {
+            return Ok(false);
+        };
+
+        // Its lease ran out on the last attempt, e.g. because the job took its
+        // worker down with it; running it again could do the same.
+        if job.attempts > job.max_attempts {
+            tracing::error!("Background job {} ({}) was lost on its last attempt", job.id, job.kind);
+            queue.fail(job.id, "Lease expired on the last attempt", None).await?;
+            return Ok(true);
+        }
+
+        let span = tracing::info_span!("job.run", job.id = %job.id, job.kind = %job.kind, job.attempt = job.attempts);
+        let handler = &self.handlers[job.kind.as_str()];
+        let run = handler(job.payload, self.context.clone()).instrument(span);
+
+        let error = match actix_rt::time::timeout(lease.to_std().unwrap_or_default(), run).await {
+            Ok(Ok(())) => {
+                queue.complete(job.id).await?;
+                return Ok(true);
+            }
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => format!("Timed out after {} seconds", self.config.lease_secs),
+        };
+
+        if job.attempts >= job.max_attempts {
+            tracing::error!(error = %error, "Background job {} ({}) failed for the last time", job.id, job.kind);
+            queue.fail(job.id, &error, None).await?;
+        } else {
+            let retry_at = Utc::now() + self.backoff(job.attempts);
+            tracing::warn!(error = %error, "Background job {} ({}) failed, retrying at {}", job.id, job.kind, retry_at);
+            queue.fail(job.id, &error, Some(retry_at)).await?;
+        }
+
+        Ok(true)
+    }
+
+    async fn work(self: Rc<Self>, mut stopping: watch::Receiver<bool>) {
+        let poll_interval = StdDuration::from_millis(self.config.poll_interval_ms);
+
+        while !*stopping.borrow() {
+            match self.work_one().await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => tracing::error!(error = %e, "Failed to run background job"),
+            }
+
+            tokio::select! {
+                _ = actix_rt::time::sleep(poll_interval) => {}
+                _ = stopping.changed() => {}
+            }
+        }
+    }
+
+    async fn schedule(self: Rc<Self>, index: usize, mut stopping: watch::Receiver<bool>) {
+        let schedule = &self.schedules[index];
+        let mut interval = actix_rt::time::interval(schedule.every);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = stopping.changed() => return,
+            }
+
+            let queued = self
+                .context
+                .queue
+                .insert(schedule.kind, &schedule.payload, schedule.max_attempts, Utc::now(), Some(schedule.kind))
+                .await;
+            if let Err(e) = queued {
+                tracing::error!(error = %e, "Failed to schedule background job {}", schedule.kind);
+            }
+        }
+    }
+
+    /// Delay after the `attempts`-th failed attempt.
+    fn backoff(&self, attempts: i32) -> Duration {
+        let exponent = (attempts - 1).clamp(0, 30) as u32;
+        let secs = self
+            .config
+            .backoff_base_secs
+            .saturating_mul(1i64 << exponent)
+            .min(self.config.backoff_max_secs);
+
+        Duration::seconds(secs)
+    }
+}
@@ -0,0 +1,85 @@
+//! The application's own background jobs.
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration as StdDuration;
+use crate::{
+    config::Config,
+    error::AppError,
+    queue::{Job, JobContext, Worker},
+    AppState,
+};
+
+/// A worker pool with every job registered and the periodic ones scheduled.
+pub fn worker(state: &AppState, config: &Config) -> Worker {
+    Worker::new(state, &config.queue)
+        .every(StdDuration::from_secs(60), ExpireOffers)
+        .every(
+            StdDuration::from_secs(60 * 60),
+            PurgeDeleted { retention_days: config.retention.soft_delete_days },
+        )
+        .every(StdDuration::from_secs(config.webhooks.poll_interval_secs), DeliverWebhooks)
+}
+
+/// Expires offers whose deadline has passed without a response.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpireOffers;
+
+#[async_trait]
+impl Job for ExpireOffers {
+    const KIND: &'static str = "offers.expire";
+
+    async fn run(self, ctx: &JobContext) -> Result<(), AppError> {
+        let expired = ctx.services.offers.expire_overdue().await?;
+        if expired > 0 {
+            tracing::info!("Expired {} overdue offers", expired);
+        }
+
+        Ok(())
+    }
+}
+
+/// Purges soft-deleted rows once they are older than the retention period.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeDeleted {
+    pub retention_days: i64,
+}
+
+#[async_trait]
+impl Job for PurgeDeleted {
+    const KIND: &'static str = "retention.purge";
+
+    async fn run(self, ctx: &JobContext) -> Result<(), AppError> {
+        let cutoff = Utc::now() - Duration::days(self.retention_days);
+        let users = ctx.services.users.purge_deleted(cutoff).await?;
+        let jobs = ctx.services.jobs.purge_deleted(cutoff).await?;
+        let companies = ctx.services.companies.purge_deleted(cutoff).await?;
+
+        if users + jobs + companies > 0 {
+            tracing::info!(
+                "Purged {} users, {} jobs and {} companies deleted before {}",
+                users, jobs, companies, cutoff
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Delivers queued webhooks, retrying failed ones once their backoff has
+/// passed. Deliveries keep their own attempts, so this job only fails when
+/// the outbox cannot be read.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeliverWebhooks;
+
+#[async_trait]
+impl Job for DeliverWebhooks {
+    const KIND: &'static str = "webhooks.deliver";
+
+    async fn run(self, ctx: &JobContext) -> Result<(), AppError> {
+        ctx.services.webhooks.deliver_due().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+//! Request rate limits and progressive lockout of accounts after failed logins.
+//!
+//! Limits are fixed windows counted in a [`RateLimitStore`]: in memory for a
+//! single instance, or in Postgres when several instances must share them.
+//! Requests over a limit are refused with `429 Too Many Requests` and a
+//! `Retry-After` header.
+
+pub mod store;
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpMessage,
+};
+use chrono::{DateTime, Utc};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use sqlx::PgPool;
+use std::{rc::Rc, sync::Arc, time::Duration};
+use crate::{
+    auth::jwt::Claims,
+    config::{Quota, RateLimitConfig, RateLimitStoreKind},
+    error::AppError,
+};
+
+pub use store::{Counter, MemoryStore, PgRateLimitStore, RateLimitStore};
+
+/// The limit a route is held to, and what it is counted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Login attempts, per client address.
+    Login,
+    /// Registrations, per client address.
+    Register,
+    /// Job applications, per authenticated user.
+    Applications,
+}
+
+impl Policy {
+    fn name(self) -> &'static str {
+        match self {
+            Policy::Login => "login",
+            Policy::Register => "register",
+            Policy::Applications => "applications",
+        }
+    }
+}
+
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        Self { config, store }
+    }
+
+    /// The limiter `config` describes, keeping its counters in `pool` when so configured.
+    pub fn from_config(config: &RateLimitConfig, pool: &PgPool) -> Self {
+        let store: Arc<dyn RateLimitStore> = match config.store {
+            RateLimitStoreKind::Memory => Arc::new(MemoryStore::default()),
+            RateLimitStoreKind::Postgres => Arc::new(PgRateLimitStore::new(pool.clone())),
+        };
+
+        Self::new(config.clone(), store)
+    }
+
+    /// Counts a request against `key`, refusing it once `quota` is used up.
+    pub async fn check(&self, key: &str, quota: Quota) -> Result<(), AppError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let counter = self.store.increment(key, quota.window()).await?;
+        if counter.count > quota.limit {
+            return Err(too_many_requests("Too many requests", counter.expires_at));
+        }
+
+        Ok(())
+    }
+
+    /// Refuses a login to `email` while the account is locked out or over its
+    /// own quota, whichever address the attempts come from.
+    pub async fn check_login(&self, email: &str) -> Result<(), AppError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let account = account_key(email);
+        if let Some(lockout) = self.store.get(&format!("lockout:{}", account)).await? {
+            return Err(too_many_requests(
+                "Too many failed logins; try again later",
+                lockout.expires_at,
+            ));
+        }
+
+        self.check(&format!("login:account:{}", account), self.config.login_per_account)
+            .await
+    }
+
+    /// Records a failed login to `email`. From the threshold on, each failure
+    /// locks the account for twice as long as the one before, up to the maximum.
+    pub async fn login_failed(&self, email: &str) -> Result<(), AppError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let account = account_key(email);
+        let failures = self
+            .store
+            .increment(
+                &format!("login:failures:{}", account),
+                Duration::from_secs(self.config.failure_window_secs),
+            )
+            .await?;
+
+        if failures.count < self.config.lockout_threshold {
+            return Ok(());
+        }
+
+        let doublings = (failures.count - self.config.lockout_threshold).min(32);
+        let lockout_secs = self
+            .config
+            .lockout_base_secs
+            .saturating_mul(1 << doublings)
+            .min(self.config.lockout_max_secs);
+
+        self.store
+            .set(
+                &format!("lockout:{}", account),
+                Counter {
+                    count: failures.count,
+                    expires_at: Utc::now() + chrono::Duration::seconds(lockout_secs as i64),
+                },
+            )
+            .await?;
+
+        tracing::warn!(failures = failures.count, lockout_secs, "Account locked out after failed logins");
+
+        Ok(())
+    }
+
+    /// Forgets earlier failed logins to `email`.
+    pub async fn login_succeeded(&self, email: &str) -> Result<(), AppError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        self.store
+            .remove(&format!("login:failures:{}", account_key(email)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// The client's address: the peer, or the one forwarded by a trusted proxy
+    /// when `trust_forwarded_for` is set.
+    pub fn client_address(&self, info: &ConnectionInfo) -> Option<String> {
+        let address = if self.config.trust_forwarded_for {
+            info.realip_remote_addr()
+        } else {
+            info.peer_addr()
+        };
+
+        address.map(str::to_string)
+    }
+
+    pub async fn purge_expired(&self) -> Result<u64, AppError> {
+        Ok(self.store.purge_expired().await?)
+    }
+
+    fn quota(&self, policy: Policy) -> Quota {
+        match policy {
+            Policy::Login => self.config.login_per_ip,
+            Policy::Register => self.config.register_per_ip,
+            Policy::Applications => self.config.applications_per_user,
+        }
+    }
+
+    /// Who `req` is counted against under `policy`: the authenticated user
+    /// where the policy asks for one, the client address otherwise.
+    fn client_key(&self, req: &ServiceRequest, policy: Policy) -> String {
+        if policy == Policy::Applications {
+            if let Some(claims) = req.extensions().get::<Claims>() {
+                return format!("{}:user:{}", policy.name(), claims.sub);
+            }
+        }
+
+        let address = self.client_address(&req.connection_info());
+
+        format!("{}:ip:{}", policy.name(), address.as_deref().unwrap_or("unknown"))
+    }
+}
+
+/// Emails differing only in case or surrounding space share a counter.
+fn account_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+fn too_many_requests(message: &str, until: DateTime<Utc>) -> AppError {
+    let retry_after_secs = (until - Utc::now()).num_seconds().max(0) as u64 + 1;
+
+    AppError::TooManyRequests {
+        message: message.to_string(),
+        retry_after_secs,
+    }
+}
+
+/// Applies a [`Policy`] to the routes it wraps, using the [`RateLimiter`] in
+/// the app data.
+pub struct RateLimit {
+    policy: Policy,
+}
+
+impl RateLimit {
+    pub fn new(policy: Policy) -> Self {
+        Self { policy }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimitService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitService {
+            service: Rc::new(service),
+            policy: self.policy,
+        }))
+    }
+}
+
+pub struct RateLimitService<S> {
+    service: Rc<S>,
+    policy: Policy,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let policy = self.policy;
+
+        Box::pin(async move {
+            let allowed = match req.app_data::<web::Data<RateLimiter>>() {
+                Some(limiter) => {
+                    let key = limiter.client_key(&req, policy);
+                    limiter.check(&key, limiter.quota(policy)).await
+                }
+                None => Err(AppError::Internal("Rate limiter not configured".to_string())),
+            };
+
+            match allowed {
+                Ok(()) => {
+                    let res = service.call(req).await?;
+                    Ok(res.map_into_left_body())
+                }
+                Err(e) => {
+                    if matches!(e, AppError::TooManyRequests { .. }) {
+                        tracing::warn!(policy = policy.name(), "Rate limit exceeded");
+                    }
+                    Ok(req.error_response(e).map_into_right_body())
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,182 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+/// A count that lapses at `expires_at`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Counter {
+    pub count: u32,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Counter {
+    fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at > now
+    }
+}
+
+/// Where rate-limit counters are kept. Every operation is atomic per key, so
+/// concurrent requests cannot slip past a limit.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Adds one to `key`, first starting a fresh window of length `window` if
+    /// the counter has expired or does not exist.
+    async fn increment(&self, key: &str, window: Duration) -> Result<Counter, sqlx::Error>;
+
+    /// The counter for `key`, unless it has expired.
+    async fn get(&self, key: &str) -> Result<Option<Counter>, sqlx::Error>;
+
+    async fn set(&self, key: &str, counter: Counter) -> Result<(), sqlx::Error>;
+
+    async fn remove(&self, key: &str) -> Result<(), sqlx::Error>;
+
+    /// Drops expired counters and returns how many there were.
+    async fn purge_expired(&self) -> Result<u64, sqlx::Error>;
+}
+
+fn expiry(now: DateTime<Utc>, window: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(window)
+        .ok()
+        .and_then(|window| now.checked_add_signed(window))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Counters held by this process only.
+#[derive(Default)]
+pub struct MemoryStore {
+    counters: Mutex<HashMap<String, Counter>>,
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn increment(&self, key: &str, window: Duration) -> Result<Counter, sqlx::Error> {
+        let now = Utc::now();
+        let mut counters = self.counters.lock().unwrap();
+
+        let counter = counters
+            .entry(key.to_string())
+            .and_modify(|counter| {
+                if counter.is_live(now) {
+                    counter.count = counter.count.saturating_add(1);
+                } else {
+                    *counter = Counter { count: 1, expires_at: expiry(now, window) };
+                }
+            })
+            .or_insert_with(|| Counter { count: 1, expires_at: expiry(now, window) });
+
+        Ok(*counter)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Counter>, sqlx::Error> {
+        let counters = self.counters.lock().unwrap();
+
+        Ok(counters.get(key).copied().filter(|counter| counter.is_live(Utc::now())))
+    }
+
+    async fn set(&self, key: &str, counter: Counter) -> Result<(), sqlx::Error> {
+        self.counters.lock().unwrap().insert(key.to_string(), counter);
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), sqlx::Error> {
+        self.counters.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let mut counters = self.counters.lock().unwrap();
+
+        let before = counters.len();
+        counters.retain(|_, counter| counter.is_live(now));
+
+        Ok((before - counters.len()) as u64)
+    }
+}
+
+/// Counters in the `rate_limits` table, shared by every instance.
+pub struct PgRateLimitStore {
+    pool: PgPool,
+}
+
+impl PgRateLimitStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for PgRateLimitStore {
+    async fn increment(&self, key: &str, window: Duration) -> Result<Counter, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO rate_limits (key, count, expires_at)
+            VALUES ($1, 1, NOW() + make_interval(secs => $2))
+            ON CONFLICT (key) DO UPDATE SET
+                count = CASE WHEN rate_limits.expires_at > NOW() THEN rate_limits.count + 1 ELSE 1 END,
+                expires_at = CASE
+                    WHEN rate_limits.expires_at > NOW() THEN rate_limits.expires_at
+                    ELSE EXCLUDED.expires_at
+                END
+            RETURNING count, expires_at
+            "#,
+            key,
+            window.as_secs_f64()
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Counter {
+            count: u32::try_from(row.count).unwrap_or(0),
+            expires_at: row.expires_at,
+        })
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Counter>, sqlx::Error> {
+        let row = sqlx::query!(
+            "SELECT count, expires_at FROM rate_limits WHERE key = $1 AND expires_at > NOW()",
+            key
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| Counter {
+            count: u32::try_from(row.count).unwrap_or(0),
+            expires_at: row.expires_at,
+        }))
+    }
+
+    async fn set(&self, key: &str, counter: Counter) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO rate_limits (key, count, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (key) DO UPDATE SET count = EXCLUDED.count, expires_at = EXCLUDED.expires_at
+            "#,
+            key,
+            i32::try_from(counter.count).unwrap_or(i32::MAX),
+            counter.expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM rate_limits WHERE key = $1", key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM rate_limits WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
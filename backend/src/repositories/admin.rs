@@ -0,0 +1,528 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+use crate::models::{
+    admin::{AdminUserView, UserSearchQuery},
+    audit::{AuditEvent, AuditQuery},
+    stats::{
+        into_counts, AdminStats, ApplicationStats, CompanyStats, CountByKey, DailyCounts, JobStats,
+        TimeSeries, UserStats,
+    },
+    users::UserRole,
+};
+
+/// Whether a job, and the company it belongs to, are soft-deleted or taken down.
+#[derive(Debug, Clone, Copy)]
+pub struct JobDeletion {
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub taken_down_at: Option<DateTime<Utc>>,
+    pub company_deleted_at: Option<DateTime<Utc>>,
+    pub company_taken_down_at: Option<DateTime<Utc>>,
+}
+
+/// Moderation and reporting across users, jobs and companies. Unlike the
+/// other repositories, these also see soft-deleted and taken-down rows.
+#[async_trait]
+pub trait AdminRepository: Send + Sync {
+    /// Totals as of now, with daily counts from `from` to `to` inclusive.
+    async fn stats(&self, from: NaiveDate, to: NaiveDate) -> Result<AdminStats, sqlx::Error>;
+
+    async fn search_users(&self, query: &UserSearchQuery) -> Result<Vec<AdminUserView>, sqlx::Error>;
+
+    /// Unlike [`UserRepository::find_by_id`](crate::repositories::UserRepository::find_by_id), this
+    /// also finds soft-deleted users.
+    async fn find_user(&self, id: Uuid) -> Result<Option<AdminUserView>, sqlx::Error>;
+
+    async fn suspend_user(&self, id: Uuid, reason: &str) -> Result<Option<AdminUserView>, sqlx::Error>;
+
+    async fn unsuspend_user(&self, id: Uuid) -> Result<Option<AdminUserView>, sqlx::Error>;
+
+    /// Invalidates every token issued to the user so far.
+    async fn revoke_user_tokens(&self, id: Uuid) -> Result<Option<AdminUserView>, sqlx::Error>;
+
+    async fn change_user_role(
+        &self,
+        id: Uuid,
+        role: UserRole,
+        company_id: Option<Uuid>,
+    ) -> Result<Option<AdminUserView>, sqlx::Error>;
+
+    /// Returns whether a user that was not already deleted was found.
+    async fn soft_delete_user(&self, id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// Brings back a soft-deleted user; `None` if there is none with `id`.
+    async fn restore_user(&self, id: Uuid) -> Result<Option<AdminUserView>, sqlx::Error>;
+
+    /// Deactivates the job and hides it from the public. Returns whether it exists.
+    async fn take_down_job(&self, id: Uuid, reason: &str) -> Result<bool, sqlx::Error>;
+
+    async fn find_job_deletion(&self, id: Uuid) -> Result<Option<JobDeletion>, sqlx::Error>;
+
+    /// Undeletes the job and lifts its takedown. A job that was taken down
+    /// stays inactive until the company reopens it.
+    async fn restore_job(&self, id: Uuid) -> Result<(), sqlx::Error>;
+
+    /// Takes the company down together with all of its jobs. Returns whether it exists.
+    async fn take_down_company(&self, id: Uuid, reason: &str) -> Result<bool, sqlx::Error>;
+
+    /// Restores a soft-deleted or taken-down company and the jobs that were
+    /// deleted or taken down along with it. Returns whether there was such a
+    /// company.
+    async fn restore_company(&self, id: Uuid) -> Result<bool, sqlx::Error>;
+
+    async fn list_audit_events(&self, query: &AuditQuery) -> Result<Vec<AuditEvent>, sqlx::Error>;
+}
+
+pub struct PgAdminRepository {
+    pool: PgPool,
+}
+
+impl PgAdminRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AdminRepository for PgAdminRepository {
+    #[instrument(name = "db.admin.stats", skip_all, fields(db.system = "postgresql"))]
+    async fn stats(&self, from: NaiveDate, to: NaiveDate) -> Result<AdminStats, sqlx::Error> {
+        let users_by_role = sqlx::query_as!(
+            CountByKey,
+            r#"
+            SELECT r::text as "key!", COUNT(u.id) as "count!"
+            FROM unnest(enum_range(NULL::user_role)) r
+            LEFT JOIN users u ON u.role = r AND u.deleted_at IS NULL
+            GROUP BY r
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let companies = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "total!", COUNT(*) FILTER (WHERE verified) as "verified!"
+            FROM companies
+            WHERE deleted_at IS NULL
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let jobs_by_status = sqlx::query_as!(
+            CountByKey,
+            r#"
+            SELECT CASE WHEN is_active THEN 'active' ELSE 'inactive' END as "key!", COUNT(*) as "count!"
+            FROM jobs
+            WHERE deleted_at IS NULL
+            GROUP BY 1
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let jobs_by_type = sqlx::query_as!(
+            CountByKey,
+            r#"
+            SELECT t::text as "key!", COUNT(j.id) as "count!"
+            FROM unnest(enum_range(NULL::job_type)) t
+            LEFT JOIN jobs j ON j.job_type = t AND j.deleted_at IS NULL
+            GROUP BY t
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let jobs_by_experience_level = sqlx::query_as!(
+            CountByKey,
+            r#"
+            SELECT l::text as "key!", COUNT(j.id) as "count!"
+            FROM unnest(enum_range(NULL::experience_level)) l
+            LEFT JOIN jobs j ON j.experience_level = l AND j.deleted_at IS NULL
+            GROUP BY l
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let applications_by_status = sqlx::query_as!(
+            CountByKey,
+            r#"
+            SELECT s::text as "key!", COUNT(a.id) as "count!"
+            FROM unnest(enum_range(NULL::application_status)) s
+            LEFT JOIN applications a ON a.status = s
+            GROUP BY s
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let daily = sqlx::query_as!(
+            DailyCounts,
+            r#"
+            SELECT d.day::date as "date!",
+                   COALESCE(u.count, 0) as "signups!",
+                   COALESCE(j.count, 0) as "job_postings!",
+                   COALESCE(a.count, 0) as "applications!"
+            FROM generate_series($1::date, $2::date, INTERVAL '1 day') AS d(day)
+            LEFT JOIN (
+                SELECT (created_at AT TIME ZONE 'UTC')::date as day, COUNT(*) as count
+                FROM users
+                WHERE (created_at AT TIME ZONE 'UTC')::date BETWEEN $1 AND $2
+                GROUP BY 1
+            ) u ON u.day = d.day::date
+            LEFT JOIN (
+                SELECT (created_at AT TIME ZONE 'UTC')::date as day, COUNT(*) as count
+                FROM jobs
+                WHERE (created_at AT TIME ZONE 'UTC')::date BETWEEN $1 AND $2
+                GROUP BY 1
+            ) j ON j.day = d.day::date
+            LEFT JOIN (
+                SELECT (created_at AT TIME ZONE 'UTC')::date as day, COUNT(*) as count
+                FROM applications
+                WHERE (created_at AT TIME ZONE 'UTC')::date BETWEEN $1 AND $2
+                GROUP BY 1
+            ) a ON a.day = d.day::date
+            ORDER BY 1
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let users_by_role = into_counts(users_by_role);
+        let mut jobs_by_status = into_counts(jobs_by_status);
+        for status in ["active", "inactive"] {
+            jobs_by_status.entry(status.to_string()).or_insert(0);
+        }
+        let applications_by_status = into_counts(applications_by_status);
+
+        Ok(AdminStats {
+            users: UserStats {
+                total: users_by_role.values().sum(),
+                by_role: users_by_role,
+            },
+            companies: CompanyStats {
+                total: companies.total,
+                verified: companies.verified,
+            },
+            jobs: JobStats {
+                total: jobs_by_status.values().sum(),
+                by_status: jobs_by_status,
+                by_type: into_counts(jobs_by_type),
+                by_experience_level: into_counts(jobs_by_experience_level),
+            },
+            applications: ApplicationStats {
+                total: applications_by_status.values().sum(),
+                by_status: applications_by_status,
+            },
+            timeseries: TimeSeries { from, to, daily },
+        })
+    }
+
+    #[instrument(name = "db.admin.search_users", skip_all, fields(db.system = "postgresql"))]
+    async fn search_users(&self, query: &UserSearchQuery) -> Result<Vec<AdminUserView>, sqlx::Error> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+        let pattern = query.q.as_ref().map(|q| format!("%{}%", q));
+
+        sqlx::query_as!(
+            AdminUserView,
+            r#"
+            SELECT id, email, name, role as "role: _", company_id, suspended_at, suspension_reason,
+                   tokens_revoked_at, deleted_at, created_at, updated_at
+            FROM users
+            WHERE ($1::text IS NULL OR email ILIKE $1 OR name ILIKE $1)
+              AND ($2::user_role IS NULL OR role = $2)
+              AND ($3::bool IS NULL OR (suspended_at IS NOT NULL) = $3)
+              AND (deleted_at IS NOT NULL) = $4
+            ORDER BY created_at DESC
+            LIMIT $5 OFFSET $6
+            "#,
+            pattern,
+            query.role as _,
+            query.suspended,
+            query.deleted,
+            per_page,
+            (page - 1) * per_page
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.admin.find_user", skip_all, fields(db.system = "postgresql"))]
+    async fn find_user(&self, id: Uuid) -> Result<Option<AdminUserView>, sqlx::Error> {
+        sqlx::query_as!(
+            AdminUserView,
+            r#"
+            SELECT id, email, name, role as "role: _", company_id, suspended_at, suspension_reason,
+                   tokens_revoked_at, deleted_at, created_at, updated_at
+            FROM users
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.admin.suspend_user", skip_all, fields(db.system = "postgresql"))]
+    async fn suspend_user(&self, id: Uuid, reason: &str) -> Result<Option<AdminUserView>, sqlx::Error> {
+        sqlx::query_as!(
+            AdminUserView,
+            r#"
+            UPDATE users
+            SET suspended_at = NOW(), suspension_reason = $2
+            WHERE id = $1
+            RETURNING id, email, name, role as "role: _", company_id, suspended_at, suspension_reason,
+                      tokens_revoked_at, deleted_at, created_at, updated_at
+            "#,
+            id,
+            reason
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.admin.unsuspend_user", skip_all, fields(db.system = "postgresql"))]
+    async fn unsuspend_user(&self, id: Uuid) -> Result<Option<AdminUserView>, sqlx::Error> {
+        sqlx::query_as!(
+            AdminUserView,
+            r#"
+            UPDATE users
+            SET suspended_at = NULL, suspension_reason = NULL
+            WHERE id = $1
+            RETURNING id, email, name, role as "role: _", company_id, suspended_at, suspension_reason,
+                      tokens_revoked_at, deleted_at, created_at, updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.admin.revoke_user_tokens", skip_all, fields(db.system = "postgresql"))]
+    async fn revoke_user_tokens(&self, id: Uuid) -> Result<Option<AdminUserView>, sqlx::Error> {
+        sqlx::query_as!(
+            AdminUserView,
+            r#"
+            UPDATE users
+            SET tokens_revoked_at = NOW()
+            WHERE id = $1
+            RETURNING id, email, name, role as "role: _", company_id, suspended_at, suspension_reason,
+                      tokens_revoked_at, deleted_at, created_at, updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.admin.change_user_role", skip_all, fields(db.system = "postgresql"))]
+    async fn change_user_role(
+        &self,
+        id: Uuid,
+        role: UserRole,
+        company_id: Option<Uuid>,
+    ) -> Result<Option<AdminUserView>, sqlx::Error> {
+        sqlx::query_as!(
+            AdminUserView,
+            r#"
+            UPDATE users
+            SET role = $2, company_id = $3
+            WHERE id = $1
+            RETURNING id, email, name, role as "role: _", company_id, suspended_at, suspension_reason,
+                      tokens_revoked_at, deleted_at, created_at, updated_at
+            "#,
+            id,
+            role as _,
+            company_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.admin.soft_delete_user", skip_all, fields(db.system = "postgresql"))]
+    async fn soft_delete_user(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE users SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(name = "db.admin.restore_user", skip_all, fields(db.system = "postgresql"))]
+    async fn restore_user(&self, id: Uuid) -> Result<Option<AdminUserView>, sqlx::Error> {
+        sqlx::query_as!(
+            AdminUserView,
+            r#"
+            UPDATE users
+            SET deleted_at = NULL
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            RETURNING id, email, name, role as "role: _", company_id, suspended_at, suspension_reason,
+                      tokens_revoked_at, deleted_at, created_at, updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.admin.take_down_job", skip_all, fields(db.system = "postgresql"))]
+    async fn take_down_job(&self, id: Uuid, reason: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE jobs
+            SET is_active = false, taken_down_at = NOW(), takedown_reason = $2
+            WHERE id = $1
+            "#,
+            id,
+            reason
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(name = "db.admin.find_job_deletion", skip_all, fields(db.system = "postgresql"))]
+    async fn find_job_deletion(&self, id: Uuid) -> Result<Option<JobDeletion>, sqlx::Error> {
+        sqlx::query_as!(
+            JobDeletion,
+            r#"
+            SELECT j.deleted_at, j.taken_down_at, c.deleted_at as company_deleted_at,
+                   c.taken_down_at as company_taken_down_at
+            FROM jobs j
+            JOIN companies c ON c.id = j.company_id
+            WHERE j.id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.admin.restore_job", skip_all, fields(db.system = "postgresql"))]
+    async fn restore_job(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE jobs SET deleted_at = NULL, taken_down_at = NULL, takedown_reason = NULL WHERE id = $1",
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "db.admin.take_down_company", skip_all, fields(db.system = "postgresql"))]
+    async fn take_down_company(&self, id: Uuid, reason: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let taken_down = sqlx::query!(
+            r#"
+            UPDATE companies
+            SET taken_down_at = NOW(), takedown_reason = $2
+            WHERE id = $1
+            "#,
+            id,
+            reason
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query!(
+            r#"
+            UPDATE jobs
+            SET is_active = false, taken_down_at = NOW(), takedown_reason = $2
+            WHERE company_id = $1 AND taken_down_at IS NULL
+            "#,
+            id,
+            reason
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(taken_down > 0)
+    }
+
+    #[instrument(name = "db.admin.restore_company", skip_all, fields(db.system = "postgresql"))]
+    async fn restore_company(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Jobs went with the company if they were removed in the same transaction.
+        sqlx::query!(
+            r#"
+            UPDATE jobs j
+            SET deleted_at = CASE WHEN j.deleted_at = c.deleted_at THEN NULL ELSE j.deleted_at END,
+                taken_down_at = CASE WHEN j.taken_down_at = c.taken_down_at THEN NULL ELSE j.taken_down_at END,
+                takedown_reason = CASE WHEN j.taken_down_at = c.taken_down_at THEN NULL ELSE j.takedown_reason END
+            FROM companies c
+            WHERE c.id = $1 AND j.company_id = c.id
+              AND (j.deleted_at = c.deleted_at OR j.taken_down_at = c.taken_down_at)
+            "#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let restored = sqlx::query!(
+            r#"
+            UPDATE companies
+            SET deleted_at = NULL, taken_down_at = NULL, takedown_reason = NULL
+            WHERE id = $1 AND (deleted_at IS NOT NULL OR taken_down_at IS NOT NULL)
+            "#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        Ok(restored > 0)
+    }
+
+    #[instrument(name = "db.admin.list_audit_events", skip_all, fields(db.system = "postgresql"))]
+    async fn list_audit_events(&self, query: &AuditQuery) -> Result<Vec<AuditEvent>, sqlx::Error> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+
+        sqlx::query_as!(
+            AuditEvent,
+            r#"
+            SELECT id, actor_id, api_key_id, action, entity_type, entity_id, before, after, changes,
+                   request_id, ip_address, user_agent, method, path, created_at
+            FROM audit_events
+            WHERE ($1::uuid IS NULL OR actor_id = $1)
+              AND ($2::text IS NULL OR entity_type = $2)
+              AND ($3::uuid IS NULL OR entity_id = $3)
+              AND ($4::text IS NULL OR action = $4)
+              AND ($5::timestamptz IS NULL OR created_at >= $5)
+              AND ($6::timestamptz IS NULL OR created_at < $6)
+              AND ($9::uuid IS NULL OR api_key_id = $9)
+            ORDER BY created_at DESC
+            LIMIT $7 OFFSET $8
+            "#,
+            query.actor_id,
+            query.entity_type,
+            query.entity_id,
+            query.action,
+            query.from,
+            query.to,
+            per_page,
+            (page - 1) * per_page,
+            query.api_key_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
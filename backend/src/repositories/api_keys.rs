@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+use crate::models::api_keys::{ApiKey, NewApiKey};
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn create(&self, key: &NewApiKey) -> Result<ApiKey, sqlx::Error>;
+
+    /// A company's keys, including revoked and expired ones, newest first.
+    async fn list_for_company(&self, company_id: Uuid) -> Result<Vec<ApiKey>, sqlx::Error>;
+
+    /// The key with `prefix`, unless it is revoked or expired or its company
+    /// has been deleted.
+    async fn find_active(&self, prefix: &str) -> Result<Option<ApiKey>, sqlx::Error>;
+
+    async fn record_use(&self, id: Uuid) -> Result<(), sqlx::Error>;
+
+    /// Revokes one of a company's keys, returning it, or `None` if the
+    /// company has no such key. Revoking a revoked key keeps the first time.
+    async fn revoke(&self, company_id: Uuid, id: Uuid) -> Result<Option<ApiKey>, sqlx::Error>;
+}
+
+pub struct PgApiKeyRepository {
+    pool: PgPool,
+}
+
+impl PgApiKeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Scopes are stored as text, so the set can grow without a migration.
+struct ApiKeyRow {
+    id: Uuid,
+    company_id: Uuid,
+    created_by: Option<Uuid>,
+    name: String,
+    prefix: String,
+    key_hash: String,
+    scopes: Vec<String>,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ApiKeyRow> for ApiKey {
+    fn from(row: ApiKeyRow) -> Self {
+        Self {
+            id: row.id,
+            company_id: row.company_id,
+            created_by: row.created_by,
+            name: row.name,
+            prefix: row.prefix,
+            key_hash: row.key_hash,
+            // A scope this build does not know grants nothing.
+            scopes: row.scopes.iter().filter_map(|scope| scope.parse().ok()).collect(),
+            expires_at: row.expires_at,
+            last_used_at: row.last_used_at,
+            revoked_at: row.revoked_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PgApiKeyRepository {
+    #[instrument(name = "db.api_keys.create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, key: &NewApiKey) -> Result<ApiKey, sqlx::Error> {
+        let scopes: Vec<String> = key.scopes.iter().map(|scope| scope.to_string()).collect();
+
+        sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            INSERT INTO api_keys (company_id, created_by, name, prefix, key_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, company_id, created_by, name, prefix, key_hash, scopes,
+                      expires_at, last_used_at, revoked_at, created_at
+            "#,
+            key.company_id,
+            key.created_by,
+            key.name,
+            key.prefix,
+            key.key_hash,
+            &scopes,
+            key.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map(ApiKey::from)
+    }
+
+    #[instrument(name = "db.api_keys.list_for_company", skip_all, fields(db.system = "postgresql"))]
+    async fn list_for_company(&self, company_id: Uuid) -> Result<Vec<ApiKey>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            SELECT id, company_id, created_by, name, prefix, key_hash, scopes,
+                   expires_at, last_used_at, revoked_at, created_at
+            FROM api_keys
+            WHERE company_id = $1
+            ORDER BY created_at DESC
+            "#,
+            company_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(ApiKey::from).collect())
+    }
+
+    #[instrument(name = "db.api_keys.find_active", skip_all, fields(db.system = "postgresql"))]
+    async fn find_active(&self, prefix: &str) -> Result<Option<ApiKey>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            SELECT k.id, k.company_id, k.created_by, k.name, k.prefix, k.key_hash, k.scopes,
+                   k.expires_at, k.last_used_at, k.revoked_at, k.created_at
+            FROM api_keys k
+            JOIN companies c ON c.id = k.company_id
+            WHERE k.prefix = $1
+              AND k.revoked_at IS NULL
+              AND (k.expires_at IS NULL OR k.expires_at > NOW())
+              AND c.deleted_at IS NULL
+            "#,
+            prefix
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map(|row| row.map(ApiKey::from))
+    }
+
+    #[instrument(name = "db.api_keys.record_use", skip_all, fields(db.system = "postgresql"))]
+    async fn record_use(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1", id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "db.api_keys.revoke", skip_all, fields(db.system = "postgresql"))]
+    async fn revoke(&self, company_id: Uuid, id: Uuid) -> Result<Option<ApiKey>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            UPDATE api_keys
+            SET revoked_at = COALESCE(revoked_at, NOW())
+            WHERE id = $1 AND company_id = $2
+            RETURNING id, company_id, created_by, name, prefix, key_hash, scopes,
+                      expires_at, last_used_at, revoked_at, created_at
+            "#,
+            id,
+            company_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map(|row| row.map(ApiKey::from))
+    }
+}
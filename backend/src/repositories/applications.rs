@@ -0,0 +1,212 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+use crate::{
+    models::applications::{Application, ApplicationStatus, CreateApplicationDto},
+    models::webhooks::WebhookEvent,
+    repositories::webhooks,
+};
+
+#[async_trait]
+pub trait ApplicationRepository: Send + Sync {
+    /// A user's applications, newest first.
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<Application>, sqlx::Error>;
+
+    /// Applications to a company's jobs, newest first.
+    async fn list_for_company(&self, company_id: Uuid) -> Result<Vec<Application>, sqlx::Error>;
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Application>, sqlx::Error>;
+
+    /// Whether `user_id` has already applied for `job_id`.
+    async fn exists(&self, user_id: Uuid, job_id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// Adds the application and queues `application.created` for the company's webhooks.
+    async fn create(&self, user_id: Uuid, dto: &CreateApplicationDto) -> Result<Application, sqlx::Error>;
+
+    /// The company owning the job an application was made for.
+    async fn find_company_id(&self, id: Uuid) -> Result<Option<Uuid>, sqlx::Error>;
+
+    /// Sets the status and, if it changed, queues `application.status_changed`
+    /// for the company's webhooks. Rejecting an application withdraws its
+    /// pending offers. `None` if the application does not exist or its status
+    /// cannot move to `status`.
+    async fn update_status(&self, id: Uuid, status: ApplicationStatus) -> Result<Option<Application>, sqlx::Error>;
+}
+
+pub struct PgApplicationRepository {
+    pool: PgPool,
+}
+
+impl PgApplicationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ApplicationRepository for PgApplicationRepository {
+    #[instrument(name = "db.applications.list_for_user", skip_all, fields(db.system = "postgresql"))]
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<Application>, sqlx::Error> {
+        sqlx::query_as!(
+            Application,
+            r#"
+            SELECT id, user_id, job_id, status as "status: _", resume_url, cover_letter,
+                   created_at, updated_at
+            FROM applications
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.applications.list_for_company", skip_all, fields(db.system = "postgresql"))]
+    async fn list_for_company(&self, company_id: Uuid) -> Result<Vec<Application>, sqlx::Error> {
+        sqlx::query_as!(
+            Application,
+            r#"
+            SELECT a.id, a.user_id, a.job_id, a.status as "status: _", a.resume_url, a.cover_letter,
+                   a.created_at, a.updated_at
+            FROM applications a
+            JOIN jobs j ON j.id = a.job_id
+            WHERE j.company_id = $1 AND j.deleted_at IS NULL
+            ORDER BY a.created_at DESC
+            "#,
+            company_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.applications.find_by_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Application>, sqlx::Error> {
+        sqlx::query_as!(
+            Application,
+            r#"
+            SELECT id, user_id, job_id, status as "status: _", resume_url, cover_letter,
+                   created_at, updated_at
+            FROM applications
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.applications.exists", skip_all, fields(db.system = "postgresql"))]
+    async fn exists(&self, user_id: Uuid, job_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM applications WHERE user_id = $1 AND job_id = $2) as "exists!""#,
+            user_id,
+            job_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.applications.create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, user_id: Uuid, dto: &CreateApplicationDto) -> Result<Application, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let application = sqlx::query_as!(
+            Application,
+            r#"
+            INSERT INTO applications (user_id, job_id, resume_url, cover_letter)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, user_id, job_id, status as "status: _", resume_url, cover_letter,
+                      created_at, updated_at
+            "#,
+            user_id,
+            dto.job_id,
+            dto.resume_url,
+            dto.cover_letter
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let company_id = sqlx::query_scalar!("SELECT company_id FROM jobs WHERE id = $1", application.job_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        webhooks::enqueue(&mut tx, company_id, WebhookEvent::ApplicationCreated, &application).await?;
+
+        tx.commit().await?;
+
+        Ok(application)
+    }
+
+    #[instrument(name = "db.applications.find_company_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_company_id(&self, id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT j.company_id
+            FROM applications a
+            JOIN jobs j ON j.id = a.job_id
+            WHERE a.id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.applications.update_status", skip_all, fields(db.system = "postgresql"))]
+    async fn update_status(&self, id: Uuid, status: ApplicationStatus) -> Result<Option<Application>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous = sqlx::query!(
+            r#"
+            SELECT a.status as "status: ApplicationStatus", j.company_id
+            FROM applications a
+            JOIN jobs j ON j.id = a.job_id
+            WHERE a.id = $1
+            FOR UPDATE OF a
+            "#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(previous) = previous else {
+            return Ok(None);
+        };
+        if !previous.status.can_move_to(status) {
+            return Ok(None);
+        }
+
+        let application = sqlx::query_as!(
+            Application,
+            r#"
+            UPDATE applications
+            SET status = $1
+            WHERE id = $2
+            RETURNING id, user_id, job_id, status as "status: _", resume_url, cover_letter,
+                      created_at, updated_at
+            "#,
+            status as _,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if status == ApplicationStatus::Rejected {
+            sqlx::query!(
+                "UPDATE offers SET status = 'withdrawn' WHERE application_id = $1 AND status = 'pending'",
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if application.status != previous.status {
+            webhooks::enqueue(&mut tx, previous.company_id, WebhookEvent::ApplicationStatusChanged, &application).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(Some(application))
+    }
+}
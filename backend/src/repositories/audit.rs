@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::instrument;
+use crate::models::audit::NewAuditEvent;
+
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    async fn record(&self, event: &NewAuditEvent) -> Result<(), sqlx::Error>;
+}
+
+pub struct PgAuditRepository {
+    pool: PgPool,
+}
+
+impl PgAuditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditRepository for PgAuditRepository {
+    #[instrument(name = "db.audit_events.record", skip_all, fields(db.system = "postgresql"))]
+    async fn record(&self, event: &NewAuditEvent) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_events (actor_id, api_key_id, action, entity_type, entity_id, before, after,
+                                      changes, request_id, ip_address, user_agent, method, path)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            "#,
+            event.actor_id,
+            event.api_key_id,
+            event.action,
+            event.entity_type,
+            event.entity_id,
+            event.before,
+            event.after,
+            event.changes,
+            event.request_id,
+            event.ip_address,
+            event.user_agent,
+            event.method,
+            event.path
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use tracing::instrument;
+use uuid::Uuid;
+use crate::models::companies::{Company, CreateCompanyDto, UpdateCompanyDto};
+
+/// Columns of [`Company`].
+const COMPANY_COLUMNS: &str = "id, name, slug, description, location, website, logo_url, industry, \
+    size, verified, rating_average, review_count, created_at, updated_at";
+
+#[async_trait]
+pub trait CompanyRepository: Send + Sync {
+    /// Companies that are neither taken down nor deleted, newest first.
+    async fn list(&self) -> Result<Vec<Company>, sqlx::Error>;
+
+    /// A company that has not been deleted, including one taken down by moderation.
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Company>, sqlx::Error>;
+
+    /// A company the public may see: neither taken down nor deleted.
+    async fn find_visible(&self, id: Uuid) -> Result<Option<Company>, sqlx::Error>;
+
+    async fn find_visible_by_slug(&self, slug: &str) -> Result<Option<Company>, sqlx::Error>;
+
+    /// Whether any company, deleted ones included, already uses `slug`.
+    async fn slug_exists(&self, slug: &str) -> Result<bool, sqlx::Error>;
+
+    async fn create(&self, dto: &CreateCompanyDto, slug: &str) -> Result<Company, sqlx::Error>;
+
+    /// Applies the fields set in `dto`; `None` if the company does not exist.
+    async fn update(&self, id: Uuid, dto: &UpdateCompanyDto) -> Result<Option<Company>, sqlx::Error>;
+
+    async fn set_verified(&self, id: Uuid, verified: bool) -> Result<Option<Company>, sqlx::Error>;
+
+    /// Soft-deletes the company together with its jobs. Both share one `deleted_at`
+    /// so that restoring the company brings back exactly the jobs deleted with it.
+    async fn soft_delete(&self, id: Uuid) -> Result<(), sqlx::Error>;
+
+    /// Permanently removes companies soft-deleted before `cutoff`. Companies that
+    /// still have jobs are kept until those jobs have been purged.
+    async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, sqlx::Error>;
+}
+
+pub struct PgCompanyRepository {
+    pool: PgPool,
+}
+
+impl PgCompanyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CompanyRepository for PgCompanyRepository {
+    #[instrument(name = "db.companies.list", skip_all, fields(db.system = "postgresql"))]
+    async fn list(&self) -> Result<Vec<Company>, sqlx::Error> {
+        sqlx::query_as!(
+            Company,
+            r#"
+            SELECT id, name, slug, description, location, website, logo_url, industry,
+                   size as "size: _", verified, rating_average, review_count, created_at, updated_at
+            FROM companies
+            WHERE taken_down_at IS NULL AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.companies.find_by_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Company>, sqlx::Error> {
+        sqlx::query_as!(
+            Company,
+            r#"
+            SELECT id, name, slug, description, location, website, logo_url, industry,
+                   size as "size: _", verified, rating_average, review_count, created_at, updated_at
+            FROM companies
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.companies.find_visible", skip_all, fields(db.system = "postgresql"))]
+    async fn find_visible(&self, id: Uuid) -> Result<Option<Company>, sqlx::Error> {
+        sqlx::query_as!(
+            Company,
+            r#"
+            SELECT id, name, slug, description, location, website, logo_url, industry,
+                   size as "size: _", verified, rating_average, review_count, created_at, updated_at
+            FROM companies
+            WHERE id = $1 AND taken_down_at IS NULL AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.companies.find_visible_by_slug", skip_all, fields(db.system = "postgresql"))]
+    async fn find_visible_by_slug(&self, slug: &str) -> Result<Option<Company>, sqlx::Error> {
+        sqlx::query_as!(
+            Company,
+            r#"
+            SELECT id, name, slug, description, location, website, logo_url, industry,
+                   size as "size: _", verified, rating_average, review_count, created_at, updated_at
+            FROM companies
+            WHERE slug = $1 AND taken_down_at IS NULL AND deleted_at IS NULL
+            "#,
+            slug
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.companies.slug_exists", skip_all, fields(db.system = "postgresql"))]
+    async fn slug_exists(&self, slug: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM companies WHERE slug = $1) as "taken!""#,
+            slug
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.companies.create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, dto: &CreateCompanyDto, slug: &str) -> Result<Company, sqlx::Error> {
+        sqlx::query_as!(
+            Company,
+            r#"
+            INSERT INTO companies (name, slug, description, location, website, logo_url, industry, size)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, name, slug, description, location, website, logo_url, industry,
+                      size as "size: _", verified, rating_average, review_count, created_at, updated_at
+            "#,
+            dto.name,
+            slug,
+            dto.description,
+            dto.location,
+            dto.website,
+            dto.logo_url,
+            dto.industry,
+            dto.size as _
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.companies.update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(&self, id: Uuid, dto: &UpdateCompanyDto) -> Result<Option<Company>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Postgres>::new("UPDATE companies SET updated_at = CURRENT_TIMESTAMP");
+
+        if let Some(name) = &dto.name {
+            builder.push(", name = ").push_bind(name);
+        }
+
+        if let Some(description) = &dto.description {
+            builder.push(", description = ").push_bind(description);
+        }
+
+        if let Some(location) = &dto.location {
+            builder.push(", location = ").push_bind(location);
+        }
+
+        if let Some(website) = &dto.website {
+            builder.push(", website = ").push_bind(website);
+        }
+
+        if let Some(logo_url) = &dto.logo_url {
+            builder.push(", logo_url = ").push_bind(logo_url);
+        }
+
+        if let Some(industry) = &dto.industry {
+            builder.push(", industry = ").push_bind(industry);
+        }
+
+        if let Some(size) = dto.size {
+            builder.push(", size = ").push_bind(size);
+        }
+
+        builder
+            .push(" WHERE id = ")
+            .push_bind(id)
+            .push(" AND deleted_at IS NULL RETURNING ")
+            .push(COMPANY_COLUMNS);
+
+        builder.build_query_as::<Company>().fetch_optional(&self.pool).await
+    }
+
+    #[instrument(name = "db.companies.set_verified", skip_all, fields(db.system = "postgresql"))]
+    async fn set_verified(&self, id: Uuid, verified: bool) -> Result<Option<Company>, sqlx::Error> {
+        sqlx::query_as!(
+            Company,
+            r#"
+            UPDATE companies
+            SET verified = $1
+            WHERE id = $2 AND deleted_at IS NULL
+            RETURNING id, name, slug, description, location, website, logo_url, industry,
+                      size as "size: _", verified, rating_average, review_count, created_at, updated_at
+            "#,
+            verified,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.companies.soft_delete", skip_all, fields(db.system = "postgresql"))]
+    async fn soft_delete(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "UPDATE companies SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE jobs SET deleted_at = NOW() WHERE company_id = $1 AND deleted_at IS NULL",
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "db.companies.purge_deleted", skip_all, fields(db.system = "postgresql"))]
+    async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM companies
+            WHERE deleted_at < $1
+              AND NOT EXISTS (SELECT 1 FROM jobs WHERE jobs.company_id = companies.id)
+            "#,
+            cutoff
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+use crate::models::identities::{Identity, OidcLoginState};
+
+#[async_trait]
+pub trait IdentityRepository: Send + Sync {
+    async fn find(&self, provider: &str, subject: &str) -> Result<Option<Identity>, sqlx::Error>;
+
+    /// Links an existing user to the provider account `subject`.
+    async fn link(&self, user_id: Uuid, provider: &str, subject: &str, email: &str) -> Result<Identity, sqlx::Error>;
+
+    /// Creates a user without a password, linked to the provider account
+    /// `subject`, and returns its id.
+    async fn create_user(
+        &self,
+        email: &str,
+        name: &str,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Uuid, sqlx::Error>;
+
+    /// Records a login, keeping the email the provider last reported.
+    async fn record_login(&self, id: Uuid, email: &str) -> Result<(), sqlx::Error>;
+
+    /// Stores an authorization request, dropping any that have expired.
+    async fn save_state(&self, state: &OidcLoginState) -> Result<(), sqlx::Error>;
+
+    /// Removes and returns the unexpired authorization request for `state`,
+    /// so each can be completed only once.
+    async fn take_state(&self, state: &str) -> Result<Option<OidcLoginState>, sqlx::Error>;
+}
+
+pub struct PgIdentityRepository {
+    pool: PgPool,
+}
+
+impl PgIdentityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl IdentityRepository for PgIdentityRepository {
+    #[instrument(name = "db.user_identities.find", skip_all, fields(db.system = "postgresql"))]
+    async fn find(&self, provider: &str, subject: &str) -> Result<Option<Identity>, sqlx::Error> {
+        sqlx::query_as!(
+            Identity,
+            r#"
+            SELECT id, user_id, provider, subject, email, created_at, last_login_at
+            FROM user_identities
+            WHERE provider = $1 AND subject = $2
+            "#,
+            provider,
+            subject
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.user_identities.link", skip_all, fields(db.system = "postgresql"))]
+    async fn link(&self, user_id: Uuid, provider: &str, subject: &str, email: &str) -> Result<Identity, sqlx::Error> {
+        sqlx::query_as!(
+            Identity,
+            r#"
+            INSERT INTO user_identities (user_id, provider, subject, email)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, user_id, provider, subject, email, created_at, last_login_at
+            "#,
+            user_id,
+            provider,
+            subject,
+            email
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.user_identities.create_user", skip_all, fields(db.system = "postgresql"))]
+    async fn create_user(
+        &self,
+        email: &str,
+        name: &str,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Uuid, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let user_id = sqlx::query_scalar!(
+            "INSERT INTO users (email, name) VALUES ($1, $2) RETURNING id",
+            email,
+            name
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO user_identities (user_id, provider, subject, email) VALUES ($1, $2, $3, $4)",
+            user_id,
+            provider,
+            subject,
+            email
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(user_id)
+    }
+
+    #[instrument(name = "db.user_identities.record_login", skip_all, fields(db.system = "postgresql"))]
+    async fn record_login(&self, id: Uuid, email: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE user_identities SET email = $2, last_login_at = NOW() WHERE id = $1",
+            id,
+            email
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "db.oidc_login_states.save", skip_all, fields(db.system = "postgresql"))]
+    async fn save_state(&self, state: &OidcLoginState) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("DELETE FROM oidc_login_states WHERE expires_at <= NOW()")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO oidc_login_states (state, provider, code_verifier, nonce, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            state.state,
+            state.provider,
+            state.code_verifier,
+            state.nonce,
+            state.expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    #[instrument(name = "db.oidc_login_states.take", skip_all, fields(db.system = "postgresql"))]
+    async fn take_state(&self, state: &str) -> Result<Option<OidcLoginState>, sqlx::Error> {
+        sqlx::query_as!(
+            OidcLoginState,
+            r#"
+            DELETE FROM oidc_login_states
+            WHERE state = $1 AND expires_at > NOW()
+            RETURNING state, provider, code_verifier, nonce, expires_at
+            "#,
+            state
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
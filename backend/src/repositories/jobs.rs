@@ -0,0 +1,307 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use tracing::instrument;
+use uuid::Uuid;
+use crate::models::jobs::{CreateJobDto, Job, JobQuery, UpdateJobDto};
+
+/// Columns of [`Job`], selected from `jobs j JOIN companies c`.
+const JOB_COLUMNS: &str = "j.id, j.title, j.description, j.company_id, j.location, j.job_type, \
+    j.experience_level, j.salary_range, j.skills, j.is_active, j.close_applications_on_accept, \
+    c.verified AS company_verified, j.created_at, j.updated_at";
+
+/// The listings [`JobRepository::list`] pages through and [`JobRepository::count`]
+/// counts, before [`push_filters`] narrows them.
+const LISTINGS: &str = "FROM jobs j JOIN companies c ON c.id = j.company_id \
+    WHERE j.taken_down_at IS NULL AND j.deleted_at IS NULL";
+
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    /// Listings matching `query` that are neither taken down nor deleted, newest first.
+    async fn list(&self, query: &JobQuery) -> Result<Vec<Job>, sqlx::Error>;
+
+    /// How many listings match `query`, ignoring its paging.
+    async fn count(&self, query: &JobQuery) -> Result<i64, sqlx::Error>;
+
+    /// A job that has not been deleted, including one taken down by moderation.
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Job>, sqlx::Error>;
+
+    /// A job the public may see: neither taken down nor deleted.
+    async fn find_visible(&self, id: Uuid) -> Result<Option<Job>, sqlx::Error>;
+
+    /// Up to `limit` active jobs of a company, newest first.
+    async fn list_open_for_company(&self, company_id: Uuid, limit: i64) -> Result<Vec<Job>, sqlx::Error>;
+
+    async fn count_open_for_company(&self, company_id: Uuid) -> Result<i64, sqlx::Error>;
+
+    async fn create(&self, dto: &CreateJobDto) -> Result<Job, sqlx::Error>;
+
+    /// Applies the fields set in `dto`; `None` if the job does not exist or is
+    /// taken down.
+    async fn update(&self, id: Uuid, dto: &UpdateJobDto) -> Result<Option<Job>, sqlx::Error>;
+
+    async fn soft_delete(&self, id: Uuid) -> Result<(), sqlx::Error>;
+
+    /// Permanently removes jobs soft-deleted before `cutoff`, together with
+    /// their applications and offers.
+    async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, sqlx::Error>;
+}
+
+pub struct PgJobRepository {
+    pool: PgPool,
+}
+
+impl PgJobRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl JobRepository for PgJobRepository {
+    #[instrument(name = "db.jobs.list", skip_all, fields(db.system = "postgresql"))]
+    async fn list(&self, query: &JobQuery) -> Result<Vec<Job>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Postgres>::new(format!("SELECT {} {}", JOB_COLUMNS, LISTINGS));
+        push_filters(&mut builder, query);
+
+        let per_page = query.per_page();
+        builder
+            .push(" ORDER BY j.created_at DESC LIMIT ")
+            .push_bind(per_page)
+            .push(" OFFSET ")
+            .push_bind((query.page() - 1) * per_page);
+
+        builder.build_query_as::<Job>().fetch_all(&self.pool).await
+    }
+
+    #[instrument(name = "db.jobs.count", skip_all, fields(db.system = "postgresql"))]
+    async fn count(&self, query: &JobQuery) -> Result<i64, sqlx::Error> {
+        let mut builder = QueryBuilder::<Postgres>::new(format!("SELECT COUNT(*) {}", LISTINGS));
+        push_filters(&mut builder, query);
+
+        builder.build_query_scalar::<i64>().fetch_one(&self.pool).await
+    }
+
+    #[instrument(name = "db.jobs.find_by_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Job>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"
+            SELECT j.id, j.title, j.description, j.company_id, j.location, j.job_type as "job_type: _",
+                   j.experience_level as "experience_level: _", j.salary_range, j.skills, j.is_active,
+                   j.close_applications_on_accept, c.verified as company_verified,
+                   j.created_at, j.updated_at
+            FROM jobs j
+            JOIN companies c ON c.id = j.company_id
+            WHERE j.id = $1 AND j.deleted_at IS NULL
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.jobs.find_visible", skip_all, fields(db.system = "postgresql"))]
+    async fn find_visible(&self, id: Uuid) -> Result<Option<Job>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"
+            SELECT j.id, j.title, j.description, j.company_id, j.location, j.job_type as "job_type: _",
+                   j.experience_level as "experience_level: _", j.salary_range, j.skills, j.is_active,
+                   j.close_applications_on_accept, c.verified as company_verified,
+                   j.created_at, j.updated_at
+            FROM jobs j
+            JOIN companies c ON c.id = j.company_id
+            WHERE j.id = $1 AND j.taken_down_at IS NULL AND j.deleted_at IS NULL
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.jobs.list_open_for_company", skip_all, fields(db.system = "postgresql"))]
+    async fn list_open_for_company(&self, company_id: Uuid, limit: i64) -> Result<Vec<Job>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"
+            SELECT j.id, j.title, j.description, j.company_id, j.location, j.job_type as "job_type: _",
+                   j.experience_level as "experience_level: _", j.salary_range, j.skills, j.is_active,
+                   j.close_applications_on_accept, c.verified as company_verified,
+                   j.created_at, j.updated_at
+            FROM jobs j
+            JOIN companies c ON c.id = j.company_id
+            WHERE j.company_id = $1 AND j.is_active = true AND j.taken_down_at IS NULL AND j.deleted_at IS NULL
+            ORDER BY j.created_at DESC
+            LIMIT $2
+            "#,
+            company_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.jobs.count_open_for_company", skip_all, fields(db.system = "postgresql"))]
+    async fn count_open_for_company(&self, company_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM jobs
+            WHERE company_id = $1 AND is_active = true AND taken_down_at IS NULL AND deleted_at IS NULL
+            "#,
+            company_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.jobs.create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, dto: &CreateJobDto) -> Result<Job, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"
+            INSERT INTO jobs (title, description, company_id, location, job_type, experience_level,
+                             salary_range, skills, close_applications_on_accept)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, title, description, company_id, location, job_type as "job_type: _",
+                      experience_level as "experience_level: _", salary_range, skills, is_active,
+                      close_applications_on_accept,
+                      (SELECT verified FROM companies WHERE companies.id = jobs.company_id) as "company_verified!",
+                      created_at, updated_at
+            "#,
+            dto.title,
+            dto.description,
+            dto.company_id,
+            dto.location,
+            dto.job_type as _,
+            dto.experience_level as _,
+            dto.salary_range,
+            &dto.skills,
+            dto.close_applications_on_accept
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.jobs.update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(&self, id: Uuid, dto: &UpdateJobDto) -> Result<Option<Job>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Postgres>::new("UPDATE jobs j SET updated_at = CURRENT_TIMESTAMP");
+
+        if let Some(title) = &dto.title {
+            builder.push(", title = ").push_bind(title);
+        }
+
+        if let Some(description) = &dto.description {
+            builder.push(", description = ").push_bind(description);
+        }
+
+        if let Some(location) = &dto.location {
+            builder.push(", location = ").push_bind(location);
+        }
+
+        if let Some(job_type) = dto.job_type {
+            builder.push(", job_type = ").push_bind(job_type);
+        }
+
+        if let Some(experience_level) = dto.experience_level {
+            builder.push(", experience_level = ").push_bind(experience_level);
+        }
+
+        if let Some(salary_range) = &dto.salary_range {
+            builder.push(", salary_range = ").push_bind(salary_range);
+        }
+
+        if let Some(skills) = &dto.skills {
+            builder.push(", skills = ").push_bind(skills);
+        }
+
+        if let Some(is_active) = dto.is_active {
+            builder.push(", is_active = ").push_bind(is_active);
+        }
+
+        if let Some(close_applications_on_accept) = dto.close_applications_on_accept {
+            builder
+                .push(", close_applications_on_accept = ")
+                .push_bind(close_applications_on_accept);
+        }
+
+        builder
+            .push(" FROM companies c WHERE c.id = j.company_id AND j.id = ")
+            .push_bind(id)
+            .push(" AND j.taken_down_at IS NULL AND j.deleted_at IS NULL RETURNING ")
+            .push(JOB_COLUMNS);
+
+        builder.build_query_as::<Job>().fetch_optional(&self.pool).await
+    }
+
+    #[instrument(name = "db.jobs.soft_delete", skip_all, fields(db.system = "postgresql"))]
+    async fn soft_delete(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE jobs
+            SET deleted_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "db.jobs.purge_deleted", skip_all, fields(db.system = "postgresql"))]
+    async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM applications WHERE job_id IN (SELECT id FROM jobs WHERE deleted_at < $1)",
+            cutoff
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let purged = sqlx::query!("DELETE FROM jobs WHERE deleted_at < $1", cutoff)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+
+        Ok(purged)
+    }
+}
+
+/// Narrows a query over [`LISTINGS`] to those matching `query`.
+fn push_filters(builder: &mut QueryBuilder<'_, Postgres>, query: &JobQuery) {
+    if let Some(title) = &query.title {
+        builder.push(" AND j.title ILIKE ").push_bind(format!("%{}%", title));
+    }
+
+    if let Some(location) = &query.location {
+        builder.push(" AND j.location ILIKE ").push_bind(format!("%{}%", location));
+    }
+
+    if let Some(job_type) = query.job_type {
+        builder.push(" AND j.job_type = ").push_bind(job_type);
+    }
+
+    if let Some(experience_level) = query.experience_level {
+        builder.push(" AND j.experience_level = ").push_bind(experience_level);
+    }
+
+    if let Some(skills) = &query.skills {
+        builder.push(" AND j.skills @> ").push_bind(skills.clone());
+    }
+
+    if let Some(search) = &query.search {
+        let pattern = format!("%{}%", search);
+        builder
+            .push(" AND (j.title ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR j.description ILIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+}
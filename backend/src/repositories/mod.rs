@@ -0,0 +1,29 @@
+//! Data access behind traits, so services run the same against Postgres or
+//! in-memory fakes. The Postgres implementations open a `db.<table>.<method>`
+//! span per call.
+
+pub mod admin;
+pub mod api_keys;
+pub mod audit;
+pub mod applications;
+pub mod companies;
+pub mod identities;
+pub mod jobs;
+pub mod offers;
+pub mod reviews;
+pub mod two_factor;
+pub mod users;
+pub mod webhooks;
+
+pub use admin::{AdminRepository, JobDeletion, PgAdminRepository};
+pub use api_keys::{ApiKeyRepository, PgApiKeyRepository};
+pub use audit::{AuditRepository, PgAuditRepository};
+pub use applications::{ApplicationRepository, PgApplicationRepository};
+pub use companies::{CompanyRepository, PgCompanyRepository};
+pub use identities::{IdentityRepository, PgIdentityRepository};
+pub use jobs::{JobRepository, PgJobRepository};
+pub use offers::{OfferRepository, PgOfferRepository};
+pub use reviews::{PgReviewRepository, ReviewRepository};
+pub use two_factor::{PgTwoFactorRepository, TwoFactorRepository};
+pub use users::{PgUserRepository, SessionState, UserChanges, UserRepository};
+pub use webhooks::{PgWebhookRepository, WebhookRepository};
@@ -0,0 +1,290 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+use crate::{
+    models::{
+        applications::Application,
+        offers::{CreateOfferDto, Offer, OfferParties, OfferStatus, OfferTarget},
+        webhooks::WebhookEvent,
+    },
+    repositories::webhooks,
+};
+
+#[async_trait]
+pub trait OfferRepository: Send + Sync {
+    /// Offers made to `user_id` or by `company_id`, or every offer if `all`,
+    /// newest first.
+    async fn list(&self, user_id: Uuid, company_id: Option<Uuid>, all: bool) -> Result<Vec<Offer>, sqlx::Error>;
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Offer>, sqlx::Error>;
+
+    /// Who an offer for `application_id` concerns.
+    async fn find_parties(&self, application_id: Uuid) -> Result<Option<OfferParties>, sqlx::Error>;
+
+    async fn find_target(&self, application_id: Uuid) -> Result<Option<OfferTarget>, sqlx::Error>;
+
+    async fn create(
+        &self,
+        created_by: Uuid,
+        job_id: Uuid,
+        currency: &str,
+        dto: &CreateOfferDto,
+    ) -> Result<Offer, sqlx::Error>;
+
+    /// Accepts a pending, unexpired offer on a still open application and
+    /// marks the application accepted.
+    /// If the job is configured to, also closes every other open application
+    /// on the job and withdraws their pending offers, and queues
+    /// `application.status_changed` for each application, all in one
+    /// transaction. Returns the offer with every application whose status changed, or
+    /// `None` if the offer could not be accepted.
+    async fn accept(&self, id: Uuid) -> Result<Option<(Offer, Vec<Application>)>, sqlx::Error>;
+
+    /// Answers a pending offer with `status`; `None` if it is no longer pending.
+    async fn respond(&self, id: Uuid, status: OfferStatus) -> Result<Option<Offer>, sqlx::Error>;
+
+    /// Marks every pending offer whose `expires_at` has passed as expired.
+    async fn expire_overdue(&self) -> Result<u64, sqlx::Error>;
+}
+
+pub struct PgOfferRepository {
+    pool: PgPool,
+}
+
+impl PgOfferRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OfferRepository for PgOfferRepository {
+    #[instrument(name = "db.offers.list", skip_all, fields(db.system = "postgresql"))]
+    async fn list(&self, user_id: Uuid, company_id: Option<Uuid>, all: bool) -> Result<Vec<Offer>, sqlx::Error> {
+        sqlx::query_as!(
+            Offer,
+            r#"
+            SELECT o.id, o.application_id, o.job_id, o.created_by, o.salary_amount, o.salary_currency,
+                   o.start_date, o.expires_at, o.terms_url, o.status as "status: _", o.responded_at,
+                   o.created_at, o.updated_at
+            FROM offers o
+            JOIN applications a ON a.id = o.application_id
+            JOIN jobs j ON j.id = o.job_id
+            WHERE a.user_id = $1 OR j.company_id = $2 OR $3
+            ORDER BY o.created_at DESC
+            "#,
+            user_id,
+            company_id,
+            all
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.offers.find_by_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Offer>, sqlx::Error> {
+        sqlx::query_as!(
+            Offer,
+            r#"
+            SELECT id, application_id, job_id, created_by, salary_amount, salary_currency,
+                   start_date, expires_at, terms_url, status as "status: _", responded_at,
+                   created_at, updated_at
+            FROM offers
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.offers.find_parties", skip_all, fields(db.system = "postgresql"))]
+    async fn find_parties(&self, application_id: Uuid) -> Result<Option<OfferParties>, sqlx::Error> {
+        sqlx::query_as!(
+            OfferParties,
+            r#"
+            SELECT a.user_id as candidate_id, j.company_id
+            FROM applications a
+            JOIN jobs j ON j.id = a.job_id
+            WHERE a.id = $1
+            "#,
+            application_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.offers.find_target", skip_all, fields(db.system = "postgresql"))]
+    async fn find_target(&self, application_id: Uuid) -> Result<Option<OfferTarget>, sqlx::Error> {
+        sqlx::query_as!(
+            OfferTarget,
+            r#"
+            SELECT a.job_id, a.status as "status: _", j.company_id,
+                   j.salary_range->>'currency' as currency
+            FROM applications a
+            JOIN jobs j ON j.id = a.job_id
+            WHERE a.id = $1
+            "#,
+            application_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.offers.create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(
+        &self,
+        created_by: Uuid,
+        job_id: Uuid,
+        currency: &str,
+        dto: &CreateOfferDto,
+    ) -> Result<Offer, sqlx::Error> {
+        sqlx::query_as!(
+            Offer,
+            r#"
+            INSERT INTO offers (application_id, job_id, created_by, salary_amount, salary_currency,
+                                start_date, expires_at, terms_url)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, application_id, job_id, created_by, salary_amount, salary_currency,
+                      start_date, expires_at, terms_url, status as "status: _", responded_at,
+                      created_at, updated_at
+            "#,
+            dto.application_id,
+            job_id,
+            created_by,
+            dto.salary_amount,
+            currency,
+            dto.start_date,
+            dto.expires_at,
+            dto.terms_url
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.offers.accept", skip_all, fields(db.system = "postgresql"))]
+    async fn accept(&self, id: Uuid) -> Result<Option<(Offer, Vec<Application>)>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let offer = sqlx::query_as!(
+            Offer,
+            r#"
+            UPDATE offers
+            SET status = 'accepted', responded_at = NOW()
+            WHERE id = $1 AND status = 'pending' AND expires_at > NOW()
+            RETURNING id, application_id, job_id, created_by, salary_amount, salary_currency,
+                      start_date, expires_at, terms_url, status as "status: _", responded_at,
+                      created_at, updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(offer) = offer else {
+            return Ok(None);
+        };
+
+        let accepted = sqlx::query_as!(
+            Application,
+            r#"
+            UPDATE applications
+            SET status = 'accepted'
+            WHERE id = $1 AND status IN ('pending', 'under_review', 'shortlisted')
+            RETURNING id, user_id, job_id, status as "status: _", resume_url, cover_letter,
+                      created_at, updated_at
+            "#,
+            offer.application_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // The application was rejected or closed after the offer was made;
+        // dropping the transaction leaves the offer pending.
+        let Some(accepted) = accepted else {
+            return Ok(None);
+        };
+        let mut changed = vec![accepted];
+
+        let job = sqlx::query!(
+            "SELECT company_id, close_applications_on_accept FROM jobs WHERE id = $1",
+            offer.job_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if job.close_applications_on_accept {
+            let closed = sqlx::query_as!(
+                Application,
+                r#"
+                UPDATE applications
+                SET status = 'closed'
+                WHERE job_id = $1 AND id <> $2
+                  AND status IN ('pending', 'under_review', 'shortlisted')
+                RETURNING id, user_id, job_id, status as "status: _", resume_url, cover_letter,
+                          created_at, updated_at
+                "#,
+                offer.job_id,
+                offer.application_id
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+            changed.extend(closed);
+
+            sqlx::query!(
+                r#"
+                UPDATE offers
+                SET status = 'withdrawn'
+                WHERE job_id = $1 AND id <> $2 AND status = 'pending'
+                "#,
+                offer.job_id,
+                offer.id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for application in &changed {
+            webhooks::enqueue(&mut tx, job.company_id, WebhookEvent::ApplicationStatusChanged, application).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(Some((offer, changed)))
+    }
+
+    #[instrument(name = "db.offers.respond", skip_all, fields(db.system = "postgresql"))]
+    async fn respond(&self, id: Uuid, status: OfferStatus) -> Result<Option<Offer>, sqlx::Error> {
+        sqlx::query_as!(
+            Offer,
+            r#"
+            UPDATE offers
+            SET status = $2, responded_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING id, application_id, job_id, created_by, salary_amount, salary_currency,
+                      start_date, expires_at, terms_url, status as "status: _", responded_at,
+                      created_at, updated_at
+            "#,
+            id,
+            status as _
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.offers.expire_overdue", skip_all, fields(db.system = "postgresql"))]
+    async fn expire_overdue(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE offers
+            SET status = 'expired'
+            WHERE status = 'pending' AND expires_at <= NOW()
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
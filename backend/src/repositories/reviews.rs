@@ -0,0 +1,251 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+use crate::models::reviews::{CompanyReview, CreateReviewDto, ReviewQuery, ReviewStatus};
+
+/// Columns of [`CompanyReview`].
+const REVIEW_COLUMNS: &str = "id, company_id, user_id, title, overall_rating, work_life_balance_rating, \
+    compensation_rating, culture_rating, management_rating, career_growth_rating, pros, cons, \
+    employment_status, job_title, status, moderation_note, moderated_by, moderated_at, \
+    created_at, updated_at";
+
+#[async_trait]
+pub trait ReviewRepository: Send + Sync {
+    /// A page of a company's approved reviews, in the requested order.
+    async fn list_for_company(&self, company_id: Uuid, query: &ReviewQuery) -> Result<Vec<CompanyReview>, sqlx::Error>;
+
+    /// A page of reviews with `status`, in the requested order.
+    async fn list_by_status(&self, status: ReviewStatus, query: &ReviewQuery) -> Result<Vec<CompanyReview>, sqlx::Error>;
+
+    /// Adds a pending review; `None` if the company is taken down or deleted.
+    async fn create(
+        &self,
+        company_id: Uuid,
+        user_id: Uuid,
+        dto: &CreateReviewDto,
+    ) -> Result<Option<CompanyReview>, sqlx::Error>;
+
+    /// Replaces the author's review and sends it back to moderation; `None` if
+    /// `user_id` has no such review.
+    async fn update(
+        &self,
+        id: Uuid,
+        company_id: Uuid,
+        user_id: Uuid,
+        dto: &CreateReviewDto,
+    ) -> Result<Option<CompanyReview>, sqlx::Error>;
+
+    /// Deletes the review if `author_id` wrote it, or whoever did if `None`.
+    /// Returns whether it existed.
+    async fn delete(&self, id: Uuid, company_id: Uuid, author_id: Option<Uuid>) -> Result<bool, sqlx::Error>;
+
+    async fn moderate(
+        &self,
+        id: Uuid,
+        status: ReviewStatus,
+        note: Option<&str>,
+        moderated_by: Uuid,
+    ) -> Result<Option<CompanyReview>, sqlx::Error>;
+
+    /// Recomputes the company's cached rating from its approved reviews.
+    async fn refresh_company_rating(&self, company_id: Uuid) -> Result<(), sqlx::Error>;
+}
+
+pub struct PgReviewRepository {
+    pool: PgPool,
+}
+
+impl PgReviewRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ReviewRepository for PgReviewRepository {
+    #[instrument(name = "db.company_reviews.list_for_company", skip_all, fields(db.system = "postgresql"))]
+    async fn list_for_company(&self, company_id: Uuid, query: &ReviewQuery) -> Result<Vec<CompanyReview>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {} FROM company_reviews WHERE company_id = $1 AND status = 'approved' \
+             ORDER BY {} LIMIT $2 OFFSET $3",
+            REVIEW_COLUMNS,
+            query.sort.unwrap_or_default().order_by()
+        );
+
+        sqlx::query_as::<_, CompanyReview>(&sql)
+            .bind(company_id)
+            .bind(query.per_page())
+            .bind((query.page() - 1) * query.per_page())
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    #[instrument(name = "db.company_reviews.list_by_status", skip_all, fields(db.system = "postgresql"))]
+    async fn list_by_status(&self, status: ReviewStatus, query: &ReviewQuery) -> Result<Vec<CompanyReview>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {} FROM company_reviews WHERE status = $1 ORDER BY {} LIMIT $2 OFFSET $3",
+            REVIEW_COLUMNS,
+            query.sort.unwrap_or_default().order_by()
+        );
+
+        sqlx::query_as::<_, CompanyReview>(&sql)
+            .bind(status)
+            .bind(query.per_page())
+            .bind((query.page() - 1) * query.per_page())
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    #[instrument(name = "db.company_reviews.create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(
+        &self,
+        company_id: Uuid,
+        user_id: Uuid,
+        dto: &CreateReviewDto,
+    ) -> Result<Option<CompanyReview>, sqlx::Error> {
+        sqlx::query_as!(
+            CompanyReview,
+            r#"
+            INSERT INTO company_reviews (company_id, user_id, title, overall_rating,
+                                         work_life_balance_rating, compensation_rating, culture_rating,
+                                         management_rating, career_growth_rating, pros, cons,
+                                         employment_status, job_title)
+            SELECT id, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13
+            FROM companies
+            WHERE id = $1 AND taken_down_at IS NULL AND deleted_at IS NULL
+            RETURNING id, company_id, user_id, title, overall_rating, work_life_balance_rating,
+                      compensation_rating, culture_rating, management_rating, career_growth_rating,
+                      pros, cons, employment_status as "employment_status: _", job_title,
+                      status as "status: _", moderation_note, moderated_by, moderated_at,
+                      created_at, updated_at
+            "#,
+            company_id,
+            user_id,
+            dto.title,
+            dto.overall_rating,
+            dto.work_life_balance_rating,
+            dto.compensation_rating,
+            dto.culture_rating,
+            dto.management_rating,
+            dto.career_growth_rating,
+            dto.pros,
+            dto.cons,
+            dto.employment_status as _,
+            dto.job_title
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.company_reviews.update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(
+        &self,
+        id: Uuid,
+        company_id: Uuid,
+        user_id: Uuid,
+        dto: &CreateReviewDto,
+    ) -> Result<Option<CompanyReview>, sqlx::Error> {
+        sqlx::query_as!(
+            CompanyReview,
+            r#"
+            UPDATE company_reviews
+            SET title = $4, overall_rating = $5, work_life_balance_rating = $6,
+                compensation_rating = $7, culture_rating = $8, management_rating = $9,
+                career_growth_rating = $10, pros = $11, cons = $12, employment_status = $13,
+                job_title = $14, status = 'pending', moderation_note = NULL,
+                moderated_by = NULL, moderated_at = NULL
+            WHERE id = $1 AND company_id = $2 AND user_id = $3
+            RETURNING id, company_id, user_id, title, overall_rating, work_life_balance_rating,
+                      compensation_rating, culture_rating, management_rating, career_growth_rating,
+                      pros, cons, employment_status as "employment_status: _", job_title,
+                      status as "status: _", moderation_note, moderated_by, moderated_at,
+                      created_at, updated_at
+            "#,
+            id,
+            company_id,
+            user_id,
+            dto.title,
+            dto.overall_rating,
+            dto.work_life_balance_rating,
+            dto.compensation_rating,
+            dto.culture_rating,
+            dto.management_rating,
+            dto.career_growth_rating,
+            dto.pros,
+            dto.cons,
+            dto.employment_status as _,
+            dto.job_title
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.company_reviews.delete", skip_all, fields(db.system = "postgresql"))]
+    async fn delete(&self, id: Uuid, company_id: Uuid, author_id: Option<Uuid>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM company_reviews
+            WHERE id = $1 AND company_id = $2 AND ($3::uuid IS NULL OR user_id = $3)
+            "#,
+            id,
+            company_id,
+            author_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(name = "db.company_reviews.moderate", skip_all, fields(db.system = "postgresql"))]
+    async fn moderate(
+        &self,
+        id: Uuid,
+        status: ReviewStatus,
+        note: Option<&str>,
+        moderated_by: Uuid,
+    ) -> Result<Option<CompanyReview>, sqlx::Error> {
+        sqlx::query_as!(
+            CompanyReview,
+            r#"
+            UPDATE company_reviews
+            SET status = $2, moderation_note = $3, moderated_by = $4, moderated_at = NOW()
+            WHERE id = $1
+            RETURNING id, company_id, user_id, title, overall_rating, work_life_balance_rating,
+                      compensation_rating, culture_rating, management_rating, career_growth_rating,
+                      pros, cons, employment_status as "employment_status: _", job_title,
+                      status as "status: _", moderation_note, moderated_by, moderated_at,
+                      created_at, updated_at
+            "#,
+            id,
+            status as _,
+            note,
+            moderated_by
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.company_reviews.refresh_company_rating", skip_all, fields(db.system = "postgresql"))]
+    async fn refresh_company_rating(&self, company_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE companies
+            SET rating_average = stats.average,
+                review_count = stats.count
+            FROM (
+                SELECT AVG(overall_rating)::DOUBLE PRECISION as average, COUNT(*)::INTEGER as count
+                FROM company_reviews
+                WHERE company_id = $1 AND status = 'approved'
+            ) stats
+            WHERE id = $1
+            "#,
+            company_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+use crate::models::two_factor::TotpSecret;
+
+#[async_trait]
+pub trait TwoFactorRepository: Send + Sync {
+    async fn find(&self, user_id: Uuid) -> Result<Option<TotpSecret>, sqlx::Error>;
+
+    /// Stores `secret` as an unconfirmed enrollment, replacing any earlier one.
+    /// Returns `false`, changing nothing, if two-factor authentication is
+    /// already enabled.
+    async fn save_pending(&self, user_id: Uuid, secret: &str) -> Result<bool, sqlx::Error>;
+
+    /// Confirms the enrollment, accepting `step`, and replaces the recovery codes.
+    async fn enable(&self, user_id: Uuid, step: i64, code_hashes: &[String]) -> Result<(), sqlx::Error>;
+
+    /// Marks `step` as used. `false` if it, or a later step, was used already.
+    async fn use_step(&self, user_id: Uuid, step: i64) -> Result<bool, sqlx::Error>;
+
+    /// Marks the recovery code as used. `false` if there is no such unused code.
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool, sqlx::Error>;
+
+    async fn replace_recovery_codes(&self, user_id: Uuid, code_hashes: &[String]) -> Result<(), sqlx::Error>;
+
+    async fn count_recovery_codes(&self, user_id: Uuid) -> Result<i64, sqlx::Error>;
+
+    /// Removes the secret and every recovery code.
+    async fn delete(&self, user_id: Uuid) -> Result<(), sqlx::Error>;
+}
+
+pub struct PgTwoFactorRepository {
+    pool: PgPool,
+}
+
+impl PgTwoFactorRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TwoFactorRepository for PgTwoFactorRepository {
+    #[instrument(name = "db.two_factor.find", skip_all, fields(db.system = "postgresql"))]
+    async fn find(&self, user_id: Uuid) -> Result<Option<TotpSecret>, sqlx::Error> {
+        sqlx::query_as!(
+            TotpSecret,
+            "SELECT user_id, secret, enabled_at, last_used_step FROM user_totp WHERE user_id = $1",
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.two_factor.save_pending", skip_all, fields(db.system = "postgresql"))]
+    async fn save_pending(&self, user_id: Uuid, secret: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO user_totp (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, last_used_step = NULL, created_at = NOW()
+            WHERE user_totp.enabled_at IS NULL
+            "#,
+            user_id,
+            secret
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[instrument(name = "db.two_factor.enable", skip_all, fields(db.system = "postgresql"))]
+    async fn enable(&self, user_id: Uuid, step: i64, code_hashes: &[String]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "UPDATE user_totp SET enabled_at = NOW(), last_used_step = $2 WHERE user_id = $1",
+            user_id,
+            step
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO recovery_codes (user_id, code_hash) SELECT $1, * FROM UNNEST($2::text[])",
+            user_id,
+            code_hashes
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    #[instrument(name = "db.two_factor.use_step", skip_all, fields(db.system = "postgresql"))]
+    async fn use_step(&self, user_id: Uuid, step: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE user_totp SET last_used_step = $2
+            WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+            user_id,
+            step
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[instrument(name = "db.two_factor.use_recovery_code", skip_all, fields(db.system = "postgresql"))]
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE recovery_codes SET used_at = NOW()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+            user_id,
+            code_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[instrument(name = "db.two_factor.replace_recovery_codes", skip_all, fields(db.system = "postgresql"))]
+    async fn replace_recovery_codes(&self, user_id: Uuid, code_hashes: &[String]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("DELETE FROM recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO recovery_codes (user_id, code_hash) SELECT $1, * FROM UNNEST($2::text[])",
+            user_id,
+            code_hashes
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    #[instrument(name = "db.two_factor.count_recovery_codes", skip_all, fields(db.system = "postgresql"))]
+    async fn count_recovery_codes(&self, user_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM recovery_codes WHERE user_id = $1 AND used_at IS NULL"#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.two_factor.delete", skip_all, fields(db.system = "postgresql"))]
+    async fn delete(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("DELETE FROM recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM user_totp WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await
+    }
+}
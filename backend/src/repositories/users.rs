@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use tracing::instrument;
+use uuid::Uuid;
+use crate::models::users::User;
+
+/// Columns written by a profile update; `None` leaves a column unchanged.
+#[derive(Debug, Default)]
+pub struct UserChanges {
+    pub email: Option<String>,
+    pub password_hash: Option<String>,
+    pub name: Option<String>,
+}
+
+/// What decides whether a user's validly signed tokens are still accepted.
+#[derive(Debug)]
+pub struct SessionState {
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub tokens_revoked_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, sqlx::Error>;
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error>;
+
+    /// Inserts a user and returns its id.
+    async fn create(&self, email: &str, password_hash: &str, name: &str) -> Result<Uuid, sqlx::Error>;
+
+    /// `None` if the user does not exist.
+    async fn update(&self, id: Uuid, changes: &UserChanges) -> Result<Option<User>, sqlx::Error>;
+
+    async fn is_suspended(&self, id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// `None` if the user does not exist or has been deleted.
+    async fn session_state(&self, id: Uuid) -> Result<Option<SessionState>, sqlx::Error>;
+
+    /// Permanently removes users soft-deleted before `cutoff`, together with their
+    /// applications. Users who made offers are kept so those offers stay attributable.
+    async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, sqlx::Error>;
+}
+
+pub struct PgUserRepository {
+    pool: PgPool,
+}
+
+impl PgUserRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UserRepository for PgUserRepository {
+    #[instrument(name = "db.users.find_by_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, email, password_hash, name, role as "role: _", company_id,
+                   created_at, updated_at
+            FROM users
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.users.find_by_email", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, email, password_hash, name, role as "role: _", company_id,
+                   created_at, updated_at
+            FROM users
+            WHERE email = $1 AND deleted_at IS NULL
+            "#,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.users.create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, email: &str, password_hash: &str, name: &str) -> Result<Uuid, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO users (email, password_hash, name)
+            VALUES ($1, $2, $3)
+            RETURNING id
+            "#,
+            email,
+            password_hash,
+            name
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.users.update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(&self, id: Uuid, changes: &UserChanges) -> Result<Option<User>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Postgres>::new("UPDATE users SET updated_at = CURRENT_TIMESTAMP");
+
+        if let Some(email) = &changes.email {
+            builder.push(", email = ").push_bind(email);
+        }
+
+        if let Some(password_hash) = &changes.password_hash {
+            builder.push(", password_hash = ").push_bind(password_hash);
+        }
+
+        if let Some(name) = &changes.name {
+            builder.push(", name = ").push_bind(name);
+        }
+
+        builder
+            .push(" WHERE id = ")
+            .push_bind(id)
+            .push(" AND deleted_at IS NULL RETURNING id, email, password_hash, name, role, company_id, created_at, updated_at");
+
+        builder.build_query_as::<User>().fetch_optional(&self.pool).await
+    }
+
+    #[instrument(name = "db.users.is_suspended", skip_all, fields(db.system = "postgresql"))]
+    async fn is_suspended(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT suspended_at IS NOT NULL as "suspended!" FROM users WHERE id = $1"#,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.users.session_state", skip_all, fields(db.system = "postgresql"))]
+    async fn session_state(&self, id: Uuid) -> Result<Option<SessionState>, sqlx::Error> {
+        sqlx::query_as!(
+            SessionState,
+            "SELECT suspended_at, tokens_revoked_at FROM users WHERE id = $1 AND deleted_at IS NULL",
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.users.purge_deleted", skip_all, fields(db.system = "postgresql"))]
+    async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM applications
+            WHERE user_id IN (
+                SELECT id FROM users u
+                WHERE u.deleted_at < $1
+                  AND NOT EXISTS (SELECT 1 FROM offers o WHERE o.created_by = u.id)
+            )
+            "#,
+            cutoff
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let purged = sqlx::query!(
+            r#"
+            DELETE FROM users u
+            WHERE u.deleted_at < $1
+              AND NOT EXISTS (SELECT 1 FROM offers o WHERE o.created_by = u.id)
+            "#,
+            cutoff
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        Ok(purged)
+    }
+}
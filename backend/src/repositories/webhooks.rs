@@ -0,0 +1,315 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{PgPool, Postgres, Transaction};
+use tracing::instrument;
+use uuid::Uuid;
+use crate::models::webhooks::{
+    AttemptOutcome, DueDelivery, NewWebhookEndpoint, WebhookAttempt, WebhookDelivery, WebhookEndpoint,
+    WebhookEvent,
+};
+
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn create_endpoint(&self, endpoint: &NewWebhookEndpoint) -> Result<WebhookEndpoint, sqlx::Error>;
+
+    async fn list_endpoints(&self, company_id: Uuid) -> Result<Vec<WebhookEndpoint>, sqlx::Error>;
+
+    async fn find_endpoint(&self, company_id: Uuid, id: Uuid) -> Result<Option<WebhookEndpoint>, sqlx::Error>;
+
+    /// Deletes an endpoint with its deliveries. Returns whether it existed.
+    async fn delete_endpoint(&self, company_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// Claims up to `limit` pending deliveries that are due, pushing their
+    /// next attempt to `lease_until` so no other worker picks them up and
+    /// they come back if this one dies mid-attempt.
+    async fn claim_due(&self, limit: i64, lease_until: DateTime<Utc>) -> Result<Vec<DueDelivery>, sqlx::Error>;
+
+    /// Logs an attempt and moves the delivery on accordingly.
+    async fn record_attempt(&self, delivery_id: Uuid, outcome: &AttemptOutcome) -> Result<(), sqlx::Error>;
+
+    /// An endpoint's deliveries, newest first.
+    async fn list_deliveries(&self, endpoint_id: Uuid, limit: i64) -> Result<Vec<WebhookDelivery>, sqlx::Error>;
+
+    async fn find_delivery(&self, endpoint_id: Uuid, id: Uuid) -> Result<Option<WebhookDelivery>, sqlx::Error>;
+
+    /// A delivery's attempts, oldest first.
+    async fn list_attempts(&self, delivery_id: Uuid) -> Result<Vec<WebhookAttempt>, sqlx::Error>;
+
+    /// Queues a delivery again from scratch, whatever its status.
+    async fn redeliver(&self, endpoint_id: Uuid, id: Uuid) -> Result<Option<WebhookDelivery>, sqlx::Error>;
+}
+
+/// Queues `event` about `data` for every endpoint of the company subscribed
+/// to it, returning how many deliveries were queued. Called by the
+/// repositories making the change the event announces, inside their
+/// transaction, so the deliveries are committed exactly when the change is.
+#[instrument(name = "db.webhook_deliveries.enqueue", skip_all, fields(db.system = "postgresql"))]
+pub async fn enqueue<T: Serialize>(
+    tx: &mut Transaction<'_, Postgres>,
+    company_id: Uuid,
+    event: WebhookEvent,
+    data: &T,
+) -> Result<u64, sqlx::Error> {
+    let payload = json!({
+        "id": Uuid::new_v4(),
+        "event": event,
+        "created_at": Utc::now(),
+        "data": data,
+    });
+
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO webhook_deliveries (endpoint_id, event, payload)
+        SELECT id, $2::TEXT, $3
+        FROM webhook_endpoints
+        WHERE company_id = $1 AND $2::TEXT = ANY(events)
+        "#,
+        company_id,
+        event.as_str(),
+        payload
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub struct PgWebhookRepository {
+    pool: PgPool,
+}
+
+impl PgWebhookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Events are stored as text, so the set can grow without a migration.
+struct WebhookEndpointRow {
+    id: Uuid,
+    company_id: Uuid,
+    created_by: Option<Uuid>,
+    url: String,
+    secret: String,
+    events: Vec<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<WebhookEndpointRow> for WebhookEndpoint {
+    fn from(row: WebhookEndpointRow) -> Self {
+        Self {
+            id: row.id,
+            company_id: row.company_id,
+            created_by: row.created_by,
+            url: row.url,
+            secret: row.secret,
+            events: row.events.iter().filter_map(|event| event.parse().ok()).collect(),
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for PgWebhookRepository {
+    #[instrument(name = "db.webhook_endpoints.create", skip_all, fields(db.system = "postgresql"))]
+    async fn create_endpoint(&self, endpoint: &NewWebhookEndpoint) -> Result<WebhookEndpoint, sqlx::Error> {
+        let events: Vec<String> = endpoint.events.iter().map(|event| event.to_string()).collect();
+
+        sqlx::query_as!(
+            WebhookEndpointRow,
+            r#"
+            INSERT INTO webhook_endpoints (company_id, created_by, url, secret, events)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, company_id, created_by, url, secret, events, created_at
+            "#,
+            endpoint.company_id,
+            endpoint.created_by,
+            endpoint.url,
+            endpoint.secret,
+            &events
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map(WebhookEndpoint::from)
+    }
+
+    #[instrument(name = "db.webhook_endpoints.list", skip_all, fields(db.system = "postgresql"))]
+    async fn list_endpoints(&self, company_id: Uuid) -> Result<Vec<WebhookEndpoint>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            WebhookEndpointRow,
+            r#"
+            SELECT id, company_id, created_by, url, secret, events, created_at
+            FROM webhook_endpoints
+            WHERE company_id = $1
+            ORDER BY created_at
+            "#,
+            company_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(WebhookEndpoint::from).collect())
+    }
+
+    #[instrument(name = "db.webhook_endpoints.find", skip_all, fields(db.system = "postgresql"))]
+    async fn find_endpoint(&self, company_id: Uuid, id: Uuid) -> Result<Option<WebhookEndpoint>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookEndpointRow,
+            r#"
+            SELECT id, company_id, created_by, url, secret, events, created_at
+            FROM webhook_endpoints
+            WHERE id = $1 AND company_id = $2
+            "#,
+            id,
+            company_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map(|row| row.map(WebhookEndpoint::from))
+    }
+
+    #[instrument(name = "db.webhook_endpoints.delete", skip_all, fields(db.system = "postgresql"))]
+    async fn delete_endpoint(&self, company_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM webhook_endpoints WHERE id = $1 AND company_id = $2",
+            id,
+            company_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(name = "db.webhook_deliveries.claim_due", skip_all, fields(db.system = "postgresql"))]
+    async fn claim_due(&self, limit: i64, lease_until: DateTime<Utc>) -> Result<Vec<DueDelivery>, sqlx::Error> {
+        sqlx::query_as!(
+            DueDelivery,
+            r#"
+            WITH due AS (
+                SELECT id
+                FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = $2
+            FROM due, webhook_endpoints e
+            WHERE d.id = due.id AND e.id = d.endpoint_id
+            RETURNING d.id, d.event, d.payload, d.attempts, e.url, e.secret
+            "#,
+            limit,
+            lease_until
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.webhook_deliveries.record_attempt", skip_all, fields(db.system = "postgresql"))]
+    async fn record_attempt(&self, delivery_id: Uuid, outcome: &AttemptOutcome) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_attempts (delivery_id, response_status, error, duration_ms)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            delivery_id,
+            outcome.response_status,
+            outcome.error,
+            outcome.duration_ms
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = attempts + 1, last_attempt_at = NOW(), next_attempt_at = $3,
+                delivered_at = CASE WHEN $2 = 'delivered'::webhook_delivery_status THEN NOW() END
+            WHERE id = $1
+            "#,
+            delivery_id,
+            outcome.status as _,
+            outcome.next_attempt_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    #[instrument(name = "db.webhook_deliveries.list", skip_all, fields(db.system = "postgresql"))]
+    async fn list_deliveries(&self, endpoint_id: Uuid, limit: i64) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            SELECT id, endpoint_id, event, payload, status as "status: _", attempts, next_attempt_at,
+                   last_attempt_at, delivered_at, created_at
+            FROM webhook_deliveries
+            WHERE endpoint_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+            endpoint_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.webhook_deliveries.find", skip_all, fields(db.system = "postgresql"))]
+    async fn find_delivery(&self, endpoint_id: Uuid, id: Uuid) -> Result<Option<WebhookDelivery>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            SELECT id, endpoint_id, event, payload, status as "status: _", attempts, next_attempt_at,
+                   last_attempt_at, delivered_at, created_at
+            FROM webhook_deliveries
+            WHERE id = $1 AND endpoint_id = $2
+            "#,
+            id,
+            endpoint_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.webhook_attempts.list", skip_all, fields(db.system = "postgresql"))]
+    async fn list_attempts(&self, delivery_id: Uuid) -> Result<Vec<WebhookAttempt>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookAttempt,
+            r#"
+            SELECT id, delivery_id, response_status, error, duration_ms, attempted_at
+            FROM webhook_attempts
+            WHERE delivery_id = $1
+            ORDER BY attempted_at
+            "#,
+            delivery_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[instrument(name = "db.webhook_deliveries.redeliver", skip_all, fields(db.system = "postgresql"))]
+    async fn redeliver(&self, endpoint_id: Uuid, id: Uuid) -> Result<Option<WebhookDelivery>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'pending', attempts = 0, next_attempt_at = NOW(), delivered_at = NULL
+            WHERE id = $1 AND endpoint_id = $2
+            RETURNING id, endpoint_id, event, payload, status as "status: _", attempts, next_attempt_at,
+                      last_attempt_at, delivered_at, created_at
+            "#,
+            id,
+            endpoint_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
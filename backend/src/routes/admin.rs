@@ -0,0 +1,445 @@
+use actix_web::{web, HttpResponse, Scope};
+use serde_json::json;
+use uuid::Uuid;
+use crate::{
+    models::admin::{AdminUserView, ChangeRoleDto, QueueQuery, SuspendUserDto, TakedownDto, UserSearchQuery},
+    models::audit::{AuditEvent, AuditQuery},
+    models::companies::Company,
+    models::jobs::Job,
+    models::stats::{AdminStats, StatsQuery},
+    auth::jwt::Claims,
+    queue::{Queue, QueuedJob},
+    services::{AdminService, AuditService, UserService},
+    audit::AuditContext,
+    error::{AppError, ErrorBody},
+};
+
+pub fn admin_scope() -> Scope {
+    web::scope("/admin")
+        .route("/stats", web::get().to(get_stats))
+        .route("/users", web::get().to(search_users))
+        .route("/users/{user_id}", web::delete().to(delete_user))
+        .route("/users/{user_id}/restore", web::post().to(restore_user))
+        .route("/users/{user_id}/suspend", web::post().to(suspend_user))
+        .route("/users/{user_id}/unsuspend", web::post().to(unsuspend_user))
+        .route("/users/{user_id}/revoke-tokens", web::post().to(revoke_user_tokens))
+        .route("/users/{user_id}/role", web::put().to(change_user_role))
+        .route("/jobs/{job_id}/takedown", web::post().to(take_down_job))
+        .route("/jobs/{job_id}/restore", web::post().to(restore_job))
+        .route("/companies/{company_id}/takedown", web::post().to(take_down_company))
+        .route("/companies/{company_id}/restore", web::post().to(restore_company))
+        .route("/audit-events", web::get().to(list_audit_events))
+        .route("/queue", web::get().to(list_queued_jobs))
+        .route("/queue/{job_id}/retry", web::post().to(retry_queued_job))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/stats",
+    tag = "admin",
+    params(StatsQuery),
+    responses(
+        (status = 200, body = AdminStats),
+        (status = 400, description = "Invalid date range", body = ErrorBody),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+    )
+)]
+pub async fn get_stats(
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<StatsQuery>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let stats = admin.stats(&query).await?;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users",
+    tag = "admin",
+    params(UserSearchQuery),
+    responses(
+        (status = 200, body = Vec<AdminUserView>),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+    )
+)]
+pub async fn search_users(
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<UserSearchQuery>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let users = admin.search_users(&query).await?;
+
+    Ok(HttpResponse::Ok().json(users))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{user_id}/suspend",
+    tag = "admin",
+    request_body = SuspendUserDto,
+    responses(
+        (status = 200, body = AdminUserView),
+        (status = 400, description = "Admins cannot suspend themselves", body = ErrorBody),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn suspend_user(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    user_id: web::Path<Uuid>,
+    suspend_dto: web::Json<SuspendUserDto>,
+) -> Result<HttpResponse, AppError> {
+    let actor = users.require_admin(&claims).await?;
+
+    let change = admin.suspend_user(&actor, *user_id, &suspend_dto).await?;
+
+    audit.record(&audit_ctx, "suspend", "user", change.after.id, Some(&change.before), Some(&change.after)).await;
+
+    Ok(HttpResponse::Ok().json(change.after))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{user_id}/unsuspend",
+    tag = "admin",
+    responses(
+        (status = 200, body = AdminUserView),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn unsuspend_user(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let change = admin.unsuspend_user(*user_id).await?;
+
+    audit.record(&audit_ctx, "unsuspend", "user", change.after.id, Some(&change.before), Some(&change.after)).await;
+
+    Ok(HttpResponse::Ok().json(change.after))
+}
+
+/// Invalidates every token issued to the user so far, forcing a fresh login.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{user_id}/revoke-tokens",
+    tag = "admin",
+    responses(
+        (status = 204),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn revoke_user_tokens(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let change = admin.revoke_user_tokens(*user_id).await?;
+
+    audit.record(&audit_ctx, "revoke_tokens", "user", change.after.id, Some(&change.before), Some(&change.after)).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/users/{user_id}/role",
+    tag = "admin",
+    request_body = ChangeRoleDto,
+    responses(
+        (status = 200, body = AdminUserView),
+        (status = 400, description = "Self-demotion, or a recruiter without a valid company", body = ErrorBody),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn change_user_role(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    user_id: web::Path<Uuid>,
+    role_dto: web::Json<ChangeRoleDto>,
+) -> Result<HttpResponse, AppError> {
+    let actor = users.require_admin(&claims).await?;
+
+    let change = admin.change_user_role(&actor, *user_id, &role_dto).await?;
+
+    audit.record(&audit_ctx, "change_role", "user", change.after.id, Some(&change.before), Some(&change.after)).await;
+
+    Ok(HttpResponse::Ok().json(change.after))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{user_id}",
+    tag = "admin",
+    responses(
+        (status = 204),
+        (status = 400, description = "Admins cannot delete themselves", body = ErrorBody),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+    )
+)]
+pub async fn delete_user(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let actor = users.require_admin(&claims).await?;
+
+    let before = admin.delete_user(&actor, *user_id).await?;
+
+    audit.record(&audit_ctx, "delete", "user", before.id, Some(&before), None).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{user_id}/restore",
+    tag = "admin",
+    responses(
+        (status = 200, body = AdminUserView),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, description = "Deleted user not found", body = ErrorBody),
+    )
+)]
+pub async fn restore_user(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let user = admin.restore_user(*user_id).await?;
+
+    audit.record(&audit_ctx, "restore", "user", user.id, None, Some(&user)).await;
+
+    Ok(HttpResponse::Ok().json(user))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/jobs/{job_id}/takedown",
+    tag = "admin",
+    request_body = TakedownDto,
+    responses(
+        (status = 204),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn take_down_job(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    job_id: web::Path<Uuid>,
+    takedown_dto: web::Json<TakedownDto>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    admin.take_down_job(*job_id, &takedown_dto).await?;
+
+    let after = json!({ "takedown_reason": takedown_dto.reason });
+    audit.record(&audit_ctx, "takedown", "job", *job_id, None, Some(&after)).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/jobs/{job_id}/restore",
+    tag = "admin",
+    responses(
+        (status = 200, body = Job),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, description = "Deleted or taken-down job not found", body = ErrorBody),
+        (status = 409, description = "The job's company is deleted or taken down", body = ErrorBody),
+    )
+)]
+pub async fn restore_job(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    job_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let job = admin.restore_job(*job_id).await?;
+
+    audit.record(&audit_ctx, "restore", "job", job.id, None, Some(&job)).await;
+
+    Ok(HttpResponse::Ok().json(job))
+}
+
+/// Takes the company down together with all of its jobs.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/companies/{company_id}/takedown",
+    tag = "admin",
+    request_body = TakedownDto,
+    responses(
+        (status = 204),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn take_down_company(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    company_id: web::Path<Uuid>,
+    takedown_dto: web::Json<TakedownDto>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    admin.take_down_company(*company_id, &takedown_dto).await?;
+
+    let after = json!({ "takedown_reason": takedown_dto.reason });
+    audit.record(&audit_ctx, "takedown", "company", *company_id, None, Some(&after)).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit-events",
+    tag = "admin",
+    params(AuditQuery),
+    responses(
+        (status = 200, body = Vec<AuditEvent>),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+    )
+)]
+pub async fn list_audit_events(
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<AuditQuery>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let events = admin.audit_events(&query).await?;
+
+    Ok(HttpResponse::Ok().json(events))
+}
+
+/// Background jobs waiting, running or dead, oldest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/queue",
+    tag = "admin",
+    params(QueueQuery),
+    responses(
+        (status = 200, body = Vec<QueuedJob>),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+    )
+)]
+pub async fn list_queued_jobs(
+    users: web::Data<UserService>,
+    queue: web::Data<Queue>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<QueueQuery>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+
+    let jobs = queue.list(query.status, per_page, (page - 1) * per_page).await?;
+
+    Ok(HttpResponse::Ok().json(jobs))
+}
+
+/// Gives a dead job a fresh set of attempts.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/queue/{job_id}/retry",
+    tag = "admin",
+    responses(
+        (status = 200, body = QueuedJob),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, description = "Dead job not found", body = ErrorBody),
+        (status = 409, description = "A job with the same key is already queued", body = ErrorBody),
+    )
+)]
+pub async fn retry_queued_job(
+    audit: web::Data<AuditService>,
+    users: web::Data<UserService>,
+    queue: web::Data<Queue>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    job_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let job = queue.retry(*job_id).await?;
+
+    audit.record(&audit_ctx, "retry", "background_job", job.id, None, Some(&job)).await;
+
+    Ok(HttpResponse::Ok().json(job))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/companies/{company_id}/restore",
+    tag = "admin",
+    responses(
+        (status = 200, body = Company),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, description = "Deleted or taken-down company not found", body = ErrorBody),
+    )
+)]
+pub async fn restore_company(
+    audit: web::Data<AuditService>,
+    admin: web::Data<AdminService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    company_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let company = admin.restore_company(*company_id).await?;
+
+    audit.record(&audit_ctx, "restore", "company", company.id, None, Some(&company)).await;
+
+    Ok(HttpResponse::Ok().json(company))
+}
@@ -0,0 +1,93 @@
+//! A company's API keys, managed by its recruiters. The routes are served
+//! under `/companies/{company_id}/api-keys`.
+
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+use crate::{
+    models::api_keys::{ApiKey, CreateApiKeyDto, CreatedApiKey},
+    auth::jwt::Claims,
+    services::{ApiKeyService, AuditService, UserService},
+    audit::AuditContext,
+    error::{AppError, ErrorBody},
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{company_id}/api-keys",
+    tag = "api-keys",
+    responses(
+        (status = 200, body = Vec<ApiKey>),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+    )
+)]
+pub async fn list_api_keys(
+    users: web::Data<UserService>,
+    api_keys: web::Data<ApiKeyService>,
+    claims: web::ReqData<Claims>,
+    company_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user = users.current_user(&claims).await?;
+
+    let keys = api_keys.list(&user, *company_id).await?;
+
+    Ok(HttpResponse::Ok().json(keys))
+}
+
+/// Responds with the full key, which cannot be retrieved again.
+#[utoipa::path(
+    post,
+    path = "/api/v1/companies/{company_id}/api-keys",
+    tag = "api-keys",
+    request_body = CreateApiKeyDto,
+    responses(
+        (status = 201, body = CreatedApiKey),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+    )
+)]
+pub async fn create_api_key(
+    audit: web::Data<AuditService>,
+    users: web::Data<UserService>,
+    api_keys: web::Data<ApiKeyService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    company_id: web::Path<Uuid>,
+    key_dto: web::Json<CreateApiKeyDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = users.current_user(&claims).await?;
+
+    let created = api_keys.create(&user, *company_id, &key_dto).await?;
+
+    audit.record(&audit_ctx, "create", "api_key", created.api_key.id, None, Some(&created.api_key)).await;
+
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/companies/{company_id}/api-keys/{key_id}",
+    tag = "api-keys",
+    params(("company_id" = Uuid, Path), ("key_id" = Uuid, Path)),
+    responses(
+        (status = 204),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn revoke_api_key(
+    audit: web::Data<AuditService>,
+    users: web::Data<UserService>,
+    api_keys: web::Data<ApiKeyService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (company_id, key_id) = path.into_inner();
+    let user = users.current_user(&claims).await?;
+
+    let revoked = api_keys.revoke(&user, company_id, key_id).await?;
+
+    audit.record(&audit_ctx, "revoke", "api_key", revoked.id, None, Some(&revoked)).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,135 @@
+use actix_web::{web, HttpResponse, Scope};
+use uuid::Uuid;
+use crate::{
+    models::{
+        api_keys::ApiKey,
+        applications::{Application, CreateApplicationDto, UpdateApplicationStatusDto},
+    },
+    auth::jwt::Claims,
+    services::{ApplicationService, AuditService, UserService},
+    audit::AuditContext,
+    error::{AppError, ErrorBody},
+    metrics::Metrics,
+    rate_limit::{Policy, RateLimit},
+};
+
+pub fn applications_scope() -> Scope {
+    web::scope("/applications")
+        .route("", web::get().to(list_my_applications))
+        .route("", web::post().to(create_application).wrap(RateLimit::new(Policy::Applications)))
+        .route("/{application_id}/status", web::put().to(update_application_status))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/applications",
+    tag = "applications",
+    responses((status = 200, description = "The caller's applications", body = Vec<Application>))
+)]
+pub async fn list_my_applications(
+    applications: web::Data<ApplicationService>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    let applications = applications.list_for_user(user_id).await?;
+
+    Ok(HttpResponse::Ok().json(applications))
+}
+
+/// Served under `/companies/{company_id}/applications`, to the company's
+/// recruiters and to its API keys.
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{company_id}/applications",
+    tag = "applications",
+    responses(
+        (status = 200, body = Vec<Application>),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = ["applications:read"]))
+)]
+pub async fn list_company_applications(
+    users: web::Data<UserService>,
+    applications: web::Data<ApplicationService>,
+    claims: Option<web::ReqData<Claims>>,
+    api_key: Option<web::ReqData<ApiKey>>,
+    company_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let allowed = match (api_key, claims) {
+        (Some(api_key), _) => api_key.can_manage_company(*company_id),
+        (None, Some(claims)) => users.current_user(&claims).await?.can_manage_company(*company_id),
+        (None, None) => false,
+    };
+    if !allowed {
+        return Err(AppError::Forbidden("Not a recruiter for this company".to_string()));
+    }
+
+    let applications = applications.list_for_company(*company_id).await?;
+
+    Ok(HttpResponse::Ok().json(applications))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/applications",
+    tag = "applications",
+    request_body = CreateApplicationDto,
+    responses(
+        (status = 201, body = Application),
+        (status = 404, description = "Job not found or closed", body = ErrorBody),
+        (status = 409, description = "Already applied", body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    )
+)]
+pub async fn create_application(
+    audit: web::Data<AuditService>,
+    applications: web::Data<ApplicationService>,
+    metrics: web::Data<Metrics>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    application_dto: web::Json<CreateApplicationDto>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    let application = applications.apply(user_id, &application_dto).await?;
+
+    metrics.applications_submitted.inc();
+
+    audit.record(&audit_ctx, "create", "application", application.id, None, Some(&application)).await;
+
+    Ok(HttpResponse::Created().json(application))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/applications/{application_id}/status",
+    tag = "applications",
+    request_body = UpdateApplicationStatusDto,
+    responses(
+        (status = 200, body = Application),
+        (status = 400, description = "Accepted and closed are only set through offers", body = ErrorBody),
+        (status = 403, description = "Not a recruiter for the job's company", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The application is rejected, accepted or closed", body = ErrorBody),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_application_status(
+    audit: web::Data<AuditService>,
+    users: web::Data<UserService>,
+    applications: web::Data<ApplicationService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    application_id: web::Path<Uuid>,
+    status_dto: web::Json<UpdateApplicationStatusDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = users.current_user(&claims).await?;
+
+    let change = applications.update_status(&user, *application_id, &status_dto).await?;
+
+    audit.record(&audit_ctx, "update_status", "application", change.after.id, Some(&change.before), Some(&change.after)).await;
+
+    Ok(HttpResponse::Ok().json(change.after))
+}
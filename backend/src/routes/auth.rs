@@ -1,99 +1,240 @@
-use actix_web::{web, HttpResponse, Responder, Scope};
-use sqlx::PgPool;
+use actix_web::{web, HttpResponse, Scope};
+use uuid::Uuid;
 use validator::Validate;
 use crate::{
-    models::users::{User, CreateUserDto, LoginDto},
-    auth::jwt::{JwtConfig, generate_token},
+    models::{
+        identities::{OidcAuthorization, OidcCallbackDto, OidcProviders},
+        two_factor::{TwoFactorChallenge, TwoFactorLoginDto},
+        users::{CreateUserDto, LoginDto, LoginResponse, Session, SessionUser, User},
+    },
+    auth::jwt::{
+        generate_challenge_token, generate_token, validate_challenge_token, JwtConfig, CHALLENGE_EXPIRATION_SECS,
+    },
+    services::{OidcService, TwoFactorService, UserService},
+    error::{AppError, ErrorBody},
+    metrics::Metrics,
+    rate_limit::{Policy, RateLimit, RateLimiter},
 };
 
 pub fn auth_scope() -> Scope {
     web::scope("/auth")
-        .route("/register", web::post().to(register))
-        .route("/login", web::post().to(login))
+        .route("/register", web::post().to(register).wrap(RateLimit::new(Policy::Register)))
+        .route("/login", web::post().to(login).wrap(RateLimit::new(Policy::Login)))
+        .route("/login/2fa", web::post().to(login_two_factor).wrap(RateLimit::new(Policy::Login)))
+        .route("/oidc", web::get().to(oidc_providers))
+        .route("/oidc/{provider}", web::get().to(oidc_authorize))
+        .route("/oidc/{provider}/callback", web::post().to(oidc_callback).wrap(RateLimit::new(Policy::Login)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    security(()),
+    request_body = CreateUserDto,
+    responses(
+        (status = 201, description = "The new user's id", body = Uuid),
+        (status = 409, description = "Email already registered", body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    )
+)]
 pub async fn register(
-    pool: web::Data<PgPool>,
+    users: web::Data<UserService>,
+    metrics: web::Data<Metrics>,
     user_dto: web::Json<CreateUserDto>,
-) -> impl Responder {
-    if let Err(e) = user_dto.validate() {
-        return HttpResponse::BadRequest().json(e);
-    }
+) -> Result<HttpResponse, AppError> {
+    let user_id = users.register(&user_dto).await?;
 
-    let password_hash = match User::hash_password(&user_dto.password) {
-        Ok(hash) => hash,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
-    };
+    metrics.registrations.inc();
 
-    let result = sqlx::query!(
-        r#"
-        INSERT INTO users (email, password_hash, name)
-        VALUES ($1, $2, $3)
-        RETURNING id
-        "#,
-        user_dto.email,
-        password_hash,
-        user_dto.name
+    Ok(HttpResponse::Created().json(user_id))
+}
+
+/// First login step. Accounts with two-factor authentication get a challenge
+/// token to exchange at `/auth/login/2fa` instead of a session.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    security(()),
+    request_body = LoginDto,
+    responses(
+        (status = 200, description = "A session, or a challenge for accounts with two-factor authentication", body = LoginResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorBody),
+        (status = 429, description = "Rate limited or locked out", body = ErrorBody),
     )
-    .fetch_one(&**pool)
-    .await;
+)]
+pub async fn login(
+    users: web::Data<UserService>,
+    two_factor: web::Data<TwoFactorService>,
+    jwt_config: web::Data<JwtConfig>,
+    metrics: web::Data<Metrics>,
+    limiter: web::Data<RateLimiter>,
+    login_dto: web::Json<LoginDto>,
+) -> Result<HttpResponse, AppError> {
+    limiter.check_login(&login_dto.email).await?;
 
-    match result {
-        Ok(record) => HttpResponse::Created().json(record.id),
+    let user = match users.authenticate(&login_dto).await {
+        Ok(user) => user,
         Err(e) => {
-            if e.as_database_error()
-                .and_then(|e| e.code())
-                .map(|code| code == "23505")
-                .unwrap_or(false)
-            {
-                HttpResponse::Conflict().json("Email already exists")
-            } else {
-                HttpResponse::InternalServerError().finish()
+            metrics.failed_logins.inc();
+            if matches!(e, AppError::Unauthorized(_)) {
+                limiter.login_failed(&login_dto.email).await?;
             }
+            return Err(e);
         }
+    };
+
+    // Failures still count towards a lockout until the second factor is in.
+    if two_factor.is_enabled(user.id).await? {
+        return challenge(&user, &jwt_config);
     }
+
+    limiter.login_succeeded(&login_dto.email).await?;
+
+    metrics.logins.inc();
+
+    session(&user, &jwt_config)
 }
 
-pub async fn login(
-    pool: web::Data<PgPool>,
+/// Second login step: a challenge token from [`login`] and a TOTP or recovery code.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login/2fa",
+    tag = "auth",
+    security(()),
+    request_body = TwoFactorLoginDto,
+    responses(
+        (status = 200, body = Session),
+        (status = 401, description = "Invalid challenge or code", body = ErrorBody),
+        (status = 429, description = "Rate limited or locked out", body = ErrorBody),
+    )
+)]
+pub async fn login_two_factor(
+    users: web::Data<UserService>,
+    two_factor: web::Data<TwoFactorService>,
     jwt_config: web::Data<JwtConfig>,
-    login_dto: web::Json<LoginDto>,
-) -> impl Responder {
-    if let Err(e) = login_dto.validate() {
-        return HttpResponse::BadRequest().json(e);
+    metrics: web::Data<Metrics>,
+    limiter: web::Data<RateLimiter>,
+    dto: web::Json<TwoFactorLoginDto>,
+) -> Result<HttpResponse, AppError> {
+    dto.validate()?;
+
+    let invalid_challenge = || AppError::Unauthorized("Invalid or expired challenge".to_string());
+    let claims = validate_challenge_token(&dto.challenge_token, &jwt_config).map_err(|_| invalid_challenge())?;
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| invalid_challenge())?;
+    let user = users.get(user_id).await.map_err(|_| invalid_challenge())?;
+
+    limiter.check_login(&user.email).await?;
+
+    if let Err(e) = two_factor.verify(user.id, &dto.code).await {
+        metrics.failed_logins.inc();
+        if matches!(e, AppError::Unauthorized(_)) {
+            limiter.login_failed(&user.email).await?;
+        }
+        return Err(e);
     }
 
-    let user = sqlx::query_as!(
-        User,
-        r#"
-        SELECT id, email, password_hash, name, created_at, updated_at
-        FROM users
-        WHERE email = $1
-        "#,
-        login_dto.email
+    limiter.login_succeeded(&user.email).await?;
+
+    metrics.logins.inc();
+
+    session(&user, &jwt_config)
+}
+
+/// Names of the OpenID Connect providers users can log in with.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oidc",
+    tag = "auth",
+    security(()),
+    responses((status = 200, body = OidcProviders))
+)]
+pub async fn oidc_providers(oidc: web::Data<OidcService>) -> HttpResponse {
+    let providers = oidc.providers().into_iter().map(str::to_string).collect();
+
+    HttpResponse::Ok().json(OidcProviders { providers })
+}
+
+/// Starts a login at an OpenID Connect provider. The client sends the user to
+/// the returned URL; the provider sends them back to the configured redirect
+/// URI with the `code` and `state` that [`oidc_callback`] takes.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oidc/{provider}",
+    tag = "auth",
+    security(()),
+    params(("provider" = String, Path)),
+    responses(
+        (status = 200, body = OidcAuthorization),
+        (status = 404, description = "Unknown provider", body = ErrorBody),
     )
-    .fetch_optional(&**pool)
-    .await;
-
-    match user {
-        Ok(Some(user)) => {
-            if User::verify_password(&login_dto.password, &user.password_hash).unwrap_or(false) {
-                match generate_token(user.id, &jwt_config) {
-                    Ok(token) => HttpResponse::Ok().json(serde_json::json!({
-                        "token": token,
-                        "user": {
-                            "id": user.id,
-                            "email": user.email,
-                            "name": user.name
-                        }
-                    })),
-                    Err(_) => HttpResponse::InternalServerError().finish(),
-                }
-            } else {
-                HttpResponse::Unauthorized().json("Invalid credentials")
-            }
+)]
+pub async fn oidc_authorize(
+    oidc: web::Data<OidcService>,
+    provider: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let authorization = oidc.authorize(&provider).await?;
+
+    Ok(HttpResponse::Ok().json(authorization))
+}
+
+/// Finishes a login at an OpenID Connect provider. Like [`login`], accounts
+/// with two-factor authentication get a challenge token instead of a session.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/oidc/{provider}/callback",
+    tag = "auth",
+    security(()),
+    params(("provider" = String, Path)),
+    request_body = OidcCallbackDto,
+    responses(
+        (status = 200, body = LoginResponse),
+        (status = 401, description = "Invalid state or code", body = ErrorBody),
+        (status = 404, description = "Unknown provider", body = ErrorBody),
+    )
+)]
+pub async fn oidc_callback(
+    oidc: web::Data<OidcService>,
+    two_factor: web::Data<TwoFactorService>,
+    jwt_config: web::Data<JwtConfig>,
+    metrics: web::Data<Metrics>,
+    provider: web::Path<String>,
+    dto: web::Json<OidcCallbackDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = match oidc.complete(&provider, &dto).await {
+        Ok(user) => user,
+        Err(e) => {
+            metrics.failed_logins.inc();
+            return Err(e);
         }
-        Ok(None) => HttpResponse::Unauthorized().json("Invalid credentials"),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+    };
+
+    if two_factor.is_enabled(user.id).await? {
+        return challenge(&user, &jwt_config);
     }
-} 
\ No newline at end of file
+
+    metrics.logins.inc();
+
+    session(&user, &jwt_config)
+}
+
+fn challenge(user: &User, jwt_config: &JwtConfig) -> Result<HttpResponse, AppError> {
+    let token = generate_challenge_token(user, jwt_config).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(TwoFactorChallenge {
+        two_factor_required: true,
+        challenge_token: token,
+        expires_in: CHALLENGE_EXPIRATION_SECS,
+    }))
+}
+
+fn session(user: &User, jwt_config: &JwtConfig) -> Result<HttpResponse, AppError> {
+    let token = generate_token(user, jwt_config).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(Session {
+        token,
+        user: SessionUser { id: user.id, email: user.email.clone(), name: user.name.clone() },
+    }))
+}
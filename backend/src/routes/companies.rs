@@ -1,7 +1,13 @@
-use actix_web::{web, HttpResponse, Responder, Scope};
-use sqlx::PgPool;
-use validator::Validate;
-use crate::models::companies::{Company, CreateCompanyDto, UpdateCompanyDto};
+use actix_web::{web, HttpResponse, Scope};
+use uuid::Uuid;
+use crate::{
+    models::companies::{Company, CompanyPage, CreateCompanyDto, UpdateCompanyDto, VerifyCompanyDto},
+    auth::jwt::Claims,
+    routes::{api_keys, applications, reviews, webhooks},
+    services::{AuditService, CompanyService, UserService},
+    audit::AuditContext,
+    error::{AppError, ErrorBody},
+};
 
 pub fn companies_scope() -> Scope {
     web::scope("/companies")
@@ -10,171 +16,177 @@ pub fn companies_scope() -> Scope {
         .route("/{company_id}", web::get().to(get_company))
         .route("/{company_id}", web::put().to(update_company))
         .route("/{company_id}", web::delete().to(delete_company))
+        .route("/{company_id}/verification", web::put().to(set_company_verified))
+        .route("/{company_id}/reviews", web::get().to(reviews::list_company_reviews))
+        .route("/{company_id}/reviews", web::post().to(reviews::create_review))
+        .route("/{company_id}/reviews/{review_id}", web::put().to(reviews::update_review))
+        .route("/{company_id}/reviews/{review_id}", web::delete().to(reviews::delete_review))
+        .route("/{company_id}/applications", web::get().to(applications::list_company_applications))
+        .route("/{company_id}/api-keys", web::get().to(api_keys::list_api_keys))
+        .route("/{company_id}/api-keys", web::post().to(api_keys::create_api_key))
+        .route("/{company_id}/api-keys/{key_id}", web::delete().to(api_keys::revoke_api_key))
+        .route("/{company_id}/webhooks", web::get().to(webhooks::list_webhooks))
+        .route("/{company_id}/webhooks", web::post().to(webhooks::create_webhook))
+        .route("/{company_id}/webhooks/{webhook_id}", web::delete().to(webhooks::delete_webhook))
+        .route("/{company_id}/webhooks/{webhook_id}/deliveries", web::get().to(webhooks::list_deliveries))
+        .route("/{company_id}/webhooks/{webhook_id}/deliveries/{delivery_id}", web::get().to(webhooks::get_delivery))
+        .route(
+            "/{company_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver",
+            web::post().to(webhooks::redeliver),
+        )
 }
 
+/// Routes served without authentication.
+pub fn public_companies_scope() -> Scope {
+    web::scope("/public/companies")
+        .route("/{slug}", web::get().to(get_company_page))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies",
+    tag = "companies",
+    responses((status = 200, body = Vec<Company>))
+)]
 pub async fn list_companies(
-    pool: web::Data<PgPool>,
-) -> impl Responder {
-    let companies = sqlx::query_as!(
-        Company,
-        r#"
-        SELECT id, name, description, location, website, created_at, updated_at
-        FROM companies
-        ORDER BY created_at DESC
-        "#
-    )
-    .fetch_all(&**pool)
-    .await;
+    companies: web::Data<CompanyService>,
+) -> Result<HttpResponse, AppError> {
+    let companies = companies.list().await?;
 
-    match companies {
-        Ok(companies) => HttpResponse::Ok().json(companies),
-        Err(_) => HttpResponse::InternalServerError().finish(),
-    }
+    Ok(HttpResponse::Ok().json(companies))
 }
 
-pub async fn get_company(
-    pool: web::Data<PgPool>,
-    company_id: web::Path<uuid::Uuid>,
-) -> impl Responder {
-    let company = sqlx::query_as!(
-        Company,
-        r#"
-        SELECT id, name, description, location, website, created_at, updated_at
-        FROM companies
-        WHERE id = $1
-        "#,
-        *company_id
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{company_id}",
+    tag = "companies",
+    responses(
+        (status = 200, body = Company),
+        (status = 404, body = ErrorBody),
     )
-    .fetch_optional(&**pool)
-    .await;
+)]
+pub async fn get_company(
+    companies: web::Data<CompanyService>,
+    company_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let company = companies.get(*company_id).await?;
 
-    match company {
-        Ok(Some(company)) => HttpResponse::Ok().json(company),
-        Ok(None) => HttpResponse::NotFound().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
-    }
+    Ok(HttpResponse::Ok().json(company))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/companies/{slug}",
+    tag = "companies",
+    params(("slug" = String, Path)),
+    responses(
+        (status = 200, body = CompanyPage),
+        (status = 404, body = ErrorBody),
+    ),
+    security(())
+)]
+pub async fn get_company_page(
+    companies: web::Data<CompanyService>,
+    slug: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let page = companies.page(&slug).await?;
+
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/companies",
+    tag = "companies",
+    request_body = CreateCompanyDto,
+    responses(
+        (status = 201, body = Company),
+        (status = 409, description = "Name already taken", body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+    )
+)]
 pub async fn create_company(
-    pool: web::Data<PgPool>,
+    audit: web::Data<AuditService>,
+    companies: web::Data<CompanyService>,
+    audit_ctx: AuditContext,
     company_dto: web::Json<CreateCompanyDto>,
-) -> impl Responder {
-    if let Err(e) = company_dto.validate() {
-        return HttpResponse::BadRequest().json(e);
-    }
+) -> Result<HttpResponse, AppError> {
+    let company = companies.create(&company_dto).await?;
 
-    let result = sqlx::query_as!(
-        Company,
-        r#"
-        INSERT INTO companies (name, description, location, website)
-        VALUES ($1, $2, $3, $4)
-        RETURNING id, name, description, location, website, created_at, updated_at
-        "#,
-        company_dto.name,
-        company_dto.description,
-        company_dto.location,
-        company_dto.website
-    )
-    .fetch_one(&**pool)
-    .await;
+    audit.record(&audit_ctx, "create", "company", company.id, None, Some(&company)).await;
 
-    match result {
-        Ok(company) => HttpResponse::Created().json(company),
-        Err(_) => HttpResponse::InternalServerError().finish(),
-    }
+    Ok(HttpResponse::Created().json(company))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/companies/{company_id}",
+    tag = "companies",
+    request_body = UpdateCompanyDto,
+    responses(
+        (status = 200, body = Company),
+        (status = 404, body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+    )
+)]
 pub async fn update_company(
-    pool: web::Data<PgPool>,
-    company_id: web::Path<uuid::Uuid>,
+    audit: web::Data<AuditService>,
+    companies: web::Data<CompanyService>,
+    audit_ctx: AuditContext,
+    company_id: web::Path<Uuid>,
     company_dto: web::Json<UpdateCompanyDto>,
-) -> impl Responder {
-    if let Err(e) = company_dto.validate() {
-        return HttpResponse::BadRequest().json(e);
-    }
+) -> Result<HttpResponse, AppError> {
+    let change = companies.update(*company_id, &company_dto).await?;
 
-    let mut sql = String::from(
-        r#"
-        UPDATE companies
-        SET
-        "#,
-    );
-
-    let mut params: Vec<String> = Vec::new();
-    let mut param_count = 1;
-    let mut updates = Vec::new();
-
-    if let Some(name) = &company_dto.name {
-        updates.push(format!("name = ${}", param_count));
-        params.push(name.clone());
-        param_count += 1;
-    }
-
-    if let Some(description) = &company_dto.description {
-        updates.push(format!("description = ${}", param_count));
-        params.push(description.clone());
-        param_count += 1;
-    }
-
-    if let Some(location) = &company_dto.location {
-        updates.push(format!("location = ${}", param_count));
-        params.push(location.clone());
-        param_count += 1;
-    }
-
-    if let Some(website) = &company_dto.website {
-        updates.push(format!("website = ${}", param_count));
-        params.push(website.clone());
-        param_count += 1;
-    }
+    audit.record(&audit_ctx, "update", "company", change.after.id, Some(&change.before), Some(&change.after)).await;
 
-    if updates.is_empty() {
-        return HttpResponse::BadRequest().json("No fields to update");
-    }
-
-    sql.push_str(&updates.join(", "));
-    sql.push_str(&format!(
-        r#"
-        , updated_at = CURRENT_TIMESTAMP
-        WHERE id = ${}
-        RETURNING id, name, description, location, website, created_at, updated_at
-        "#,
-        param_count
-    ));
-    params.push(company_id.to_string());
-
-    let result = sqlx::query_as::<_, Company>(&sql)
-        .bind(&params[0])
-        .bind(&params[1])
-        .bind(&params[2])
-        .bind(&params[3])
-        .bind(&params[4])
-        .fetch_optional(&**pool)
-        .await;
-
-    match result {
-        Ok(Some(company)) => HttpResponse::Ok().json(company),
-        Ok(None) => HttpResponse::NotFound().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
-    }
+    Ok(HttpResponse::Ok().json(change.after))
 }
 
-pub async fn delete_company(
-    pool: web::Data<PgPool>,
-    company_id: web::Path<uuid::Uuid>,
-) -> impl Responder {
-    let result = sqlx::query!(
-        r#"
-        DELETE FROM companies
-        WHERE id = $1
-        "#,
-        *company_id
+#[utoipa::path(
+    put,
+    path = "/api/v1/companies/{company_id}/verification",
+    tag = "companies",
+    request_body = VerifyCompanyDto,
+    responses(
+        (status = 200, body = Company),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, body = ErrorBody),
     )
-    .execute(&**pool)
-    .await;
+)]
+pub async fn set_company_verified(
+    audit: web::Data<AuditService>,
+    users: web::Data<UserService>,
+    companies: web::Data<CompanyService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    company_id: web::Path<Uuid>,
+    verify_dto: web::Json<VerifyCompanyDto>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let change = companies.set_verified(*company_id, verify_dto.verified).await?;
+
+    audit.record(&audit_ctx, "verify", "company", change.after.id, Some(&change.before), Some(&change.after)).await;
+
+    Ok(HttpResponse::Ok().json(change.after))
+}
 
-    match result {
-        Ok(_) => HttpResponse::NoContent().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+#[utoipa::path(
+    delete,
+    path = "/api/v1/companies/{company_id}",
+    tag = "companies",
+    responses((status = 204))
+)]
+pub async fn delete_company(
+    audit: web::Data<AuditService>,
+    companies: web::Data<CompanyService>,
+    audit_ctx: AuditContext,
+    company_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    if let Some(before) = companies.delete(*company_id).await? {
+        audit.record(&audit_ctx, "delete", "company", before.id, Some(&before), None).await;
     }
-} 
\ No newline at end of file
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,135 @@
+use actix_web::{web, HttpResponse};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use utoipa::ToSchema;
+use crate::migrate;
+
+/// Longest a readiness probe waits for the database.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Whether this instance should keep receiving traffic. Cleared on shutdown so
+/// load balancers stop routing here before connections are closed.
+#[derive(Debug, Default)]
+pub struct Readiness {
+    draining: AtomicBool,
+}
+
+impl Readiness {
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+}
+
+/// Identifies the running build; filled in by `build.rs`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    pub build_time: &'static str,
+}
+
+pub const BUILD_INFO: BuildInfo = BuildInfo {
+    version: env!("CARGO_PKG_VERSION"),
+    git_sha: env!("GIT_SHA"),
+    build_time: env!("BUILD_TIME"),
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+enum Check {
+    Ok,
+    Unreachable,
+    Pending,
+    Unknown,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ReadinessReport {
+    status: &'static str,
+    draining: bool,
+    database: Check,
+    migrations: Check,
+}
+
+/// Probe routes, served at the root outside `/api` and without authentication.
+pub fn health_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/healthz", web::get().to(healthz))
+        .route("/readyz", web::get().to(readyz))
+        .route("/version", web::get().to(version));
+}
+
+/// Liveness: answers as long as the process can serve requests.
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "system",
+    responses((status = 200, body = Object, example = json!({ "status": "ok" }))),
+    security(())
+)]
+pub async fn healthz() -> HttpResponse {
+    HttpResponse::Ok().json(json!({ "status": "ok" }))
+}
+
+/// Readiness: the database is reachable, the schema is current and the server
+/// is not shutting down.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "system",
+    responses(
+        (status = 200, body = ReadinessReport),
+        (status = 503, description = "Draining, or the database is unreachable or behind", body = ReadinessReport),
+    ),
+    security(())
+)]
+pub async fn readyz(pool: web::Data<PgPool>, readiness: web::Data<Readiness>) -> HttpResponse {
+    let draining = readiness.is_draining();
+
+    let (database, migrations) =
+        match actix_rt::time::timeout(READINESS_TIMEOUT, migrate::pending(&pool)).await {
+            Ok(Ok(0)) => (Check::Ok, Check::Ok),
+            Ok(Ok(_)) => (Check::Ok, Check::Pending),
+            Ok(Err(e)) => {
+                tracing::warn!(error = %e, "Readiness check failed");
+                (Check::Unreachable, Check::Unknown)
+            }
+            Err(_) => {
+                tracing::warn!("Readiness check timed out after {:?}", READINESS_TIMEOUT);
+                (Check::Unreachable, Check::Unknown)
+            }
+        };
+
+    let ready = !draining && matches!((&database, &migrations), (Check::Ok, Check::Ok));
+    let report = ReadinessReport {
+        status: if ready { "ready" } else { "unavailable" },
+        draining,
+        database,
+        migrations,
+    };
+
+    if ready {
+        HttpResponse::Ok().json(report)
+    } else {
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/version",
+    tag = "system",
+    responses((status = 200, body = BuildInfo)),
+    security(())
+)]
+pub async fn version() -> HttpResponse {
+    HttpResponse::Ok().json(BUILD_INFO)
+}
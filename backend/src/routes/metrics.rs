@@ -0,0 +1,21 @@
+use actix_web::{http::header::ContentType, web, HttpResponse};
+use sqlx::PgPool;
+use crate::metrics::Metrics;
+
+/// Served at the root, outside `/api`, for the Prometheus scraper.
+pub fn metrics_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(metrics));
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "system",
+    responses((status = 200, description = "Prometheus text exposition format", body = String, content_type = "text/plain")),
+    security(())
+)]
+pub async fn metrics(pool: web::Data<PgPool>, metrics: web::Data<Metrics>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType(prometheus::TEXT_FORMAT.parse().unwrap()))
+        .body(metrics.render(&pool).await)
+}
@@ -1,4 +1,13 @@
+pub mod api_keys;
 pub mod auth;
 pub mod jobs;
 pub mod companies;
-pub mod users; 
\ No newline at end of file
+pub mod users;
+pub mod applications;
+pub mod offers;
+pub mod reviews;
+pub mod admin;
+pub mod health;
+pub mod metrics;
+pub mod webhooks;
+pub mod well_known;
@@ -0,0 +1,169 @@
+use actix_web::{web, HttpResponse, Scope};
+use uuid::Uuid;
+use crate::{
+    models::offers::{Offer, CreateOfferDto},
+    auth::jwt::Claims,
+    services::{AuditService, OfferService, UserService},
+    audit::AuditContext,
+    error::{AppError, ErrorBody},
+};
+
+pub fn offers_scope() -> Scope {
+    web::scope("/offers")
+        .route("", web::get().to(list_offers))
+        .route("", web::post().to(create_offer))
+        .route("/{offer_id}", web::get().to(get_offer))
+        .route("/{offer_id}/accept", web::post().to(accept_offer))
+        .route("/{offer_id}/decline", web::post().to(decline_offer))
+        .route("/{offer_id}/withdraw", web::post().to(withdraw_offer))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/offers",
+    tag = "offers",
+    responses((status = 200, description = "Offers made to the caller or by their company", body = Vec<Offer>))
+)]
+pub async fn list_offers(
+    offers: web::Data<OfferService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, AppError> {
+    let user = users.current_user(&claims).await?;
+
+    let offers = offers.list(&user).await?;
+
+    Ok(HttpResponse::Ok().json(offers))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/offers/{offer_id}",
+    tag = "offers",
+    responses(
+        (status = 200, body = Offer),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn get_offer(
+    offers: web::Data<OfferService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    offer_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user = users.current_user(&claims).await?;
+
+    let offer = offers.get(&user, *offer_id).await?;
+
+    Ok(HttpResponse::Ok().json(offer))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/offers",
+    tag = "offers",
+    request_body = CreateOfferDto,
+    responses(
+        (status = 201, body = Offer),
+        (status = 403, description = "Not a recruiter for the job's company", body = ErrorBody),
+        (status = 404, description = "Application not found", body = ErrorBody),
+        (status = 409, description = "Application closed or already has a pending offer", body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+    )
+)]
+pub async fn create_offer(
+    audit: web::Data<AuditService>,
+    offers: web::Data<OfferService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    offer_dto: web::Json<CreateOfferDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = users.current_user(&claims).await?;
+
+    let offer = offers.create(&user, &offer_dto).await?;
+
+    audit.record(&audit_ctx, "create", "offer", offer.id, None, Some(&offer)).await;
+
+    Ok(HttpResponse::Created().json(offer))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/offers/{offer_id}/accept",
+    tag = "offers",
+    responses(
+        (status = 200, body = Offer),
+        (status = 404, description = "No such offer for the caller", body = ErrorBody),
+        (status = 409, description = "Offer is no longer pending", body = ErrorBody),
+    )
+)]
+pub async fn accept_offer(
+    audit: web::Data<AuditService>,
+    offers: web::Data<OfferService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    offer_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    let accepted = offers.accept(user_id, *offer_id).await?;
+    let offer = &accepted.offer;
+
+    audit.record(&audit_ctx, "accept", "offer", offer.after.id, Some(&offer.before), Some(&offer.after)).await;
+
+    Ok(HttpResponse::Ok().json(&offer.after))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/offers/{offer_id}/decline",
+    tag = "offers",
+    responses(
+        (status = 200, body = Offer),
+        (status = 404, description = "No such offer for the caller", body = ErrorBody),
+        (status = 409, description = "Offer is no longer pending", body = ErrorBody),
+    )
+)]
+pub async fn decline_offer(
+    audit: web::Data<AuditService>,
+    offers: web::Data<OfferService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    offer_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    let change = offers.decline(user_id, *offer_id).await?;
+
+    audit.record(&audit_ctx, "decline", "offer", change.after.id, Some(&change.before), Some(&change.after)).await;
+
+    Ok(HttpResponse::Ok().json(change.after))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/offers/{offer_id}/withdraw",
+    tag = "offers",
+    responses(
+        (status = 200, body = Offer),
+        (status = 404, description = "No such offer from the caller's company", body = ErrorBody),
+        (status = 409, description = "Offer is no longer pending", body = ErrorBody),
+    )
+)]
+pub async fn withdraw_offer(
+    audit: web::Data<AuditService>,
+    offers: web::Data<OfferService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    offer_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user = users.current_user(&claims).await?;
+
+    let change = offers.withdraw(&user, *offer_id).await?;
+
+    audit.record(&audit_ctx, "withdraw", "offer", change.after.id, Some(&change.before), Some(&change.after)).await;
+
+    Ok(HttpResponse::Ok().json(change.after))
+}
@@ -0,0 +1,157 @@
+use actix_web::{web, HttpResponse, Scope};
+use uuid::Uuid;
+use crate::{
+    models::reviews::{CompanyReview, CreateReviewDto, ModerateReviewDto, ReviewQuery},
+    auth::jwt::Claims,
+    services::{ReviewService, UserService},
+    error::{AppError, ErrorBody},
+};
+
+/// Moderation queue for admins; company-scoped review routes live under `/companies`.
+pub fn reviews_scope() -> Scope {
+    web::scope("/reviews")
+        .route("", web::get().to(list_reviews_for_moderation))
+        .route("/{review_id}/moderation", web::put().to(moderate_review))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{company_id}/reviews",
+    tag = "reviews",
+    params(ReviewQuery),
+    responses((status = 200, description = "Approved reviews", body = Vec<CompanyReview>))
+)]
+pub async fn list_company_reviews(
+    reviews: web::Data<ReviewService>,
+    company_id: web::Path<Uuid>,
+    query: web::Query<ReviewQuery>,
+) -> Result<HttpResponse, AppError> {
+    let reviews = reviews.list_for_company(*company_id, &query).await?;
+
+    Ok(HttpResponse::Ok().json(reviews))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/companies/{company_id}/reviews",
+    tag = "reviews",
+    request_body = CreateReviewDto,
+    responses(
+        (status = 201, body = CompanyReview),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "Already reviewed", body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+    )
+)]
+pub async fn create_review(
+    reviews: web::Data<ReviewService>,
+    claims: web::ReqData<Claims>,
+    company_id: web::Path<Uuid>,
+    review_dto: web::Json<CreateReviewDto>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    let review = reviews.create(user_id, *company_id, &review_dto).await?;
+
+    Ok(HttpResponse::Created().json(review))
+}
+
+/// Replaces the caller's own review; edits go back through moderation.
+#[utoipa::path(
+    put,
+    path = "/api/v1/companies/{company_id}/reviews/{review_id}",
+    tag = "reviews",
+    params(("company_id" = Uuid, Path), ("review_id" = Uuid, Path)),
+    request_body = CreateReviewDto,
+    responses(
+        (status = 200, body = CompanyReview),
+        (status = 404, body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+    )
+)]
+pub async fn update_review(
+    reviews: web::Data<ReviewService>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(Uuid, Uuid)>,
+    review_dto: web::Json<CreateReviewDto>,
+) -> Result<HttpResponse, AppError> {
+    let (company_id, review_id) = path.into_inner();
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    let review = reviews.update(user_id, company_id, review_id, &review_dto).await?;
+
+    Ok(HttpResponse::Ok().json(review))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/companies/{company_id}/reviews/{review_id}",
+    tag = "reviews",
+    params(("company_id" = Uuid, Path), ("review_id" = Uuid, Path)),
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn delete_review(
+    reviews: web::Data<ReviewService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (company_id, review_id) = path.into_inner();
+
+    let user = users.current_user(&claims).await?;
+
+    reviews.delete(&user, company_id, review_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reviews",
+    tag = "reviews",
+    params(ReviewQuery),
+    responses(
+        (status = 200, description = "Reviews with the given status, pending by default", body = Vec<CompanyReview>),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+    )
+)]
+pub async fn list_reviews_for_moderation(
+    reviews: web::Data<ReviewService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<ReviewQuery>,
+) -> Result<HttpResponse, AppError> {
+    users.require_admin(&claims).await?;
+
+    let reviews = reviews.list_for_moderation(&query).await?;
+
+    Ok(HttpResponse::Ok().json(reviews))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/reviews/{review_id}/moderation",
+    tag = "reviews",
+    request_body = ModerateReviewDto,
+    responses(
+        (status = 200, body = CompanyReview),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn moderate_review(
+    reviews: web::Data<ReviewService>,
+    users: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    review_id: web::Path<Uuid>,
+    moderate_dto: web::Json<ModerateReviewDto>,
+) -> Result<HttpResponse, AppError> {
+    let admin = users.require_admin(&claims).await?;
+
+    let review = reviews.moderate(&admin, *review_id, &moderate_dto).await?;
+
+    Ok(HttpResponse::Ok().json(review))
+}
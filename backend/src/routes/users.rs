@@ -1,92 +1,188 @@
 use actix_web::{web, HttpResponse, Scope};
-use sqlx::PgPool;
-use validator::Validate;
+use uuid::Uuid;
 use crate::{
-    models::users::{User, UpdateUserDto},
+    models::{
+        two_factor::{RecoveryCodes, TotpEnrollment, TwoFactorCodeDto, TwoFactorStatus},
+        users::{UpdateUserDto, User},
+    },
     auth::jwt::Claims,
+    services::{AuditService, TwoFactorService, UserService},
+    audit::AuditContext,
+    error::{AppError, ErrorBody},
 };
 
 pub fn users_scope() -> Scope {
     web::scope("/users")
         .route("/profile", web::get().to(get_profile))
         .route("/profile", web::put().to(update_profile))
+        .route("/2fa", web::get().to(two_factor_status))
+        .route("/2fa", web::post().to(enroll_two_factor))
+        .route("/2fa", web::delete().to(disable_two_factor))
+        .route("/2fa/confirm", web::post().to(confirm_two_factor))
+        .route("/2fa/recovery-codes", web::post().to(regenerate_recovery_codes))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/profile",
+    tag = "users",
+    responses(
+        (status = 200, body = User),
+    )
+)]
 pub async fn get_profile(
-    pool: web::Data<PgPool>,
+    users: web::Data<UserService>,
     claims: web::ReqData<Claims>,
-) -> HttpResponse {
+) -> Result<HttpResponse, AppError> {
     let user_id = uuid::Uuid::parse_str(&claims.sub).unwrap();
 
-    let result = sqlx::query_as!(
-        User,
-        r#"
-        SELECT id, email, password_hash, name, created_at, updated_at
-        FROM users
-        WHERE id = $1
-        "#,
-        user_id
-    )
-    .fetch_optional(&**pool)
-    .await;
-
-    match result {
-        Ok(Some(user)) => HttpResponse::Ok().json(user),
-        Ok(None) => HttpResponse::NotFound().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
-    }
+    let user = users.get(user_id).await?;
+
+    Ok(HttpResponse::Ok().json(user))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/profile",
+    tag = "users",
+    request_body = UpdateUserDto,
+    responses(
+        (status = 200, body = User),
+        (status = 409, description = "Email already registered", body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+    )
+)]
 pub async fn update_profile(
-    pool: web::Data<PgPool>,
+    audit: web::Data<AuditService>,
+    users: web::Data<UserService>,
     claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
     user_dto: web::Json<UpdateUserDto>,
-) -> HttpResponse {
-    if let Err(_) = user_dto.validate() {
-        return HttpResponse::BadRequest().finish();
-    }
-
+) -> Result<HttpResponse, AppError> {
     let user_id = uuid::Uuid::parse_str(&claims.sub).unwrap();
 
-    let mut sql = String::from("UPDATE users SET");
-    let mut params: Vec<String> = Vec::new();
-    let mut param_count = 1;
-
-    if let Some(email) = &user_dto.email {
-        sql.push_str(&format!(" email = ${},", param_count));
-        params.push(email.clone());
-        param_count += 1;
-    }
-
-    if let Some(password) = &user_dto.password {
-        sql.push_str(&format!(" password_hash = ${},", param_count));
-        params.push(crate::models::User::hash_password(password).unwrap());
-        param_count += 1;
-    }
-
-    if let Some(name) = &user_dto.name {
-        sql.push_str(&format!(" name = ${},", param_count));
-        params.push(name.clone());
-        param_count += 1;
-    }
-
-    sql.push_str(" updated_at = CURRENT_TIMESTAMP");
-    sql.push_str(&format!(" WHERE id = ${}", param_count));
-    params.push(user_id.to_string());
-
-    sql.push_str(" RETURNING id, email, password_hash, name, created_at, updated_at");
-
-    let result = sqlx::query_as::<_, User>(&sql)
-        .bind(&params[0])
-        .bind(&params[1])
-        .bind(&params[2])
-        .bind(&params[3])
-        .fetch_optional(&**pool)
+    let change = users.update_profile(user_id, &user_dto).await?;
+
+    audit.record(&audit_ctx, "update", "user", change.after.id, Some(&change.before), Some(&change.after)).await;
+
+    Ok(HttpResponse::Ok().json(change.after))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/2fa",
+    tag = "users",
+    responses(
+        (status = 200, body = TwoFactorStatus),
+    )
+)]
+pub async fn two_factor_status(
+    two_factor: web::Data<TwoFactorService>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    Ok(HttpResponse::Ok().json(two_factor.status(user_id).await?))
+}
+
+/// Starts TOTP enrollment, returning the secret and an `otpauth://` URI for
+/// authenticator apps.
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/2fa",
+    tag = "users",
+    responses(
+        (status = 200, body = TotpEnrollment),
+        (status = 409, description = "Already enabled", body = ErrorBody),
+    )
+)]
+pub async fn enroll_two_factor(
+    two_factor: web::Data<TwoFactorService>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    Ok(HttpResponse::Ok().json(two_factor.enroll(user_id).await?))
+}
+
+/// Enables two-factor authentication with a first code and returns the recovery codes.
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/2fa/confirm",
+    tag = "users",
+    request_body = TwoFactorCodeDto,
+    responses(
+        (status = 200, body = RecoveryCodes),
+        (status = 400, description = "Enrollment not started or invalid code", body = ErrorBody),
+        (status = 409, description = "Already enabled", body = ErrorBody),
+    )
+)]
+pub async fn confirm_two_factor(
+    audit: web::Data<AuditService>,
+    two_factor: web::Data<TwoFactorService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    dto: web::Json<TwoFactorCodeDto>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    let codes = two_factor.confirm(user_id, &dto).await?;
+
+    audit.record::<TwoFactorStatus>(&audit_ctx, "enable_two_factor", "user", user_id, None, None).await;
+
+    Ok(HttpResponse::Ok().json(codes))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/2fa",
+    tag = "users",
+    request_body = TwoFactorCodeDto,
+    responses(
+        (status = 204),
+        (status = 401, description = "Invalid code", body = ErrorBody),
+    )
+)]
+pub async fn disable_two_factor(
+    audit: web::Data<AuditService>,
+    two_factor: web::Data<TwoFactorService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    dto: web::Json<TwoFactorCodeDto>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    two_factor.disable(user_id, &dto).await?;
+
+    audit.record::<TwoFactorStatus>(&audit_ctx, "disable_two_factor", "user", user_id, None, None).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/2fa/recovery-codes",
+    tag = "users",
+    request_body = TwoFactorCodeDto,
+    responses(
+        (status = 200, body = RecoveryCodes),
+        (status = 401, description = "Invalid code", body = ErrorBody),
+    )
+)]
+pub async fn regenerate_recovery_codes(
+    audit: web::Data<AuditService>,
+    two_factor: web::Data<TwoFactorService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    dto: web::Json<TwoFactorCodeDto>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).unwrap();
+
+    let codes = two_factor.regenerate_recovery_codes(user_id, &dto).await?;
+
+    audit
+        .record::<TwoFactorStatus>(&audit_ctx, "regenerate_recovery_codes", "user", user_id, None, None)
         .await;
 
-    match result {
-        Ok(Some(user)) => HttpResponse::Ok().json(user),
-        Ok(None) => HttpResponse::NotFound().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
-    }
-} 
\ No newline at end of file
+    Ok(HttpResponse::Ok().json(codes))
+}
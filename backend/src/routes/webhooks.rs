@@ -0,0 +1,168 @@
+//! A company's webhook endpoints and their deliveries, managed by its
+//! recruiters. The routes are served under `/companies/{company_id}/webhooks`.
+
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+use crate::{
+    models::webhooks::{CreateWebhookDto, CreatedWebhookEndpoint, WebhookDelivery, WebhookDeliveryDetails, WebhookEndpoint},
+    auth::jwt::Claims,
+    services::{AuditService, UserService, WebhookService},
+    audit::AuditContext,
+    error::{AppError, ErrorBody},
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{company_id}/webhooks",
+    tag = "webhooks",
+    responses(
+        (status = 200, body = Vec<WebhookEndpoint>),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+    )
+)]
+pub async fn list_webhooks(
+    users: web::Data<UserService>,
+    webhooks: web::Data<WebhookService>,
+    claims: web::ReqData<Claims>,
+    company_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user = users.current_user(&claims).await?;
+
+    let endpoints = webhooks.list_endpoints(&user, *company_id).await?;
+
+    Ok(HttpResponse::Ok().json(endpoints))
+}
+
+/// Responds with the signing secret, which cannot be retrieved again.
+#[utoipa::path(
+    post,
+    path = "/api/v1/companies/{company_id}/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhookDto,
+    responses(
+        (status = 201, body = CreatedWebhookEndpoint),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+        (status = 400, description = "Invalid payload", body = ErrorBody),
+    )
+)]
+pub async fn create_webhook(
+    audit: web::Data<AuditService>,
+    users: web::Data<UserService>,
+    webhooks: web::Data<WebhookService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    company_id: web::Path<Uuid>,
+    webhook_dto: web::Json<CreateWebhookDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = users.current_user(&claims).await?;
+
+    let created = webhooks.create_endpoint(&user, *company_id, &webhook_dto).await?;
+
+    audit.record(&audit_ctx, "create", "webhook", created.endpoint.id, None, Some(&created.endpoint)).await;
+
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/companies/{company_id}/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(("company_id" = Uuid, Path), ("webhook_id" = Uuid, Path)),
+    responses(
+        (status = 204),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn delete_webhook(
+    audit: web::Data<AuditService>,
+    users: web::Data<UserService>,
+    webhooks: web::Data<WebhookService>,
+    claims: web::ReqData<Claims>,
+    audit_ctx: AuditContext,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (company_id, webhook_id) = path.into_inner();
+    let user = users.current_user(&claims).await?;
+
+    let before = webhooks.delete_endpoint(&user, company_id, webhook_id).await?;
+
+    audit.record(&audit_ctx, "delete", "webhook", before.id, Some(&before), None).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{company_id}/webhooks/{webhook_id}/deliveries",
+    tag = "webhooks",
+    params(("company_id" = Uuid, Path), ("webhook_id" = Uuid, Path)),
+    responses(
+        (status = 200, body = Vec<WebhookDelivery>),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn list_deliveries(
+    users: web::Data<UserService>,
+    webhooks: web::Data<WebhookService>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (company_id, webhook_id) = path.into_inner();
+    let user = users.current_user(&claims).await?;
+
+    let deliveries = webhooks.list_deliveries(&user, company_id, webhook_id).await?;
+
+    Ok(HttpResponse::Ok().json(deliveries))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{company_id}/webhooks/{webhook_id}/deliveries/{delivery_id}",
+    tag = "webhooks",
+    params(("company_id" = Uuid, Path), ("webhook_id" = Uuid, Path), ("delivery_id" = Uuid, Path)),
+    responses(
+        (status = 200, body = WebhookDeliveryDetails),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn get_delivery(
+    users: web::Data<UserService>,
+    webhooks: web::Data<WebhookService>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (company_id, webhook_id, delivery_id) = path.into_inner();
+    let user = users.current_user(&claims).await?;
+
+    let delivery = webhooks.get_delivery(&user, company_id, webhook_id, delivery_id).await?;
+
+    Ok(HttpResponse::Ok().json(delivery))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/companies/{company_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver",
+    tag = "webhooks",
+    params(("company_id" = Uuid, Path), ("webhook_id" = Uuid, Path), ("delivery_id" = Uuid, Path)),
+    responses(
+        (status = 202, description = "Queued for another attempt", body = WebhookDelivery),
+        (status = 403, description = "Not a recruiter for this company", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn redeliver(
+    users: web::Data<UserService>,
+    webhooks: web::Data<WebhookService>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (company_id, webhook_id, delivery_id) = path.into_inner();
+    let user = users.current_user(&claims).await?;
+
+    let delivery = webhooks.redeliver(&user, company_id, webhook_id, delivery_id).await?;
+
+    Ok(HttpResponse::Accepted().json(delivery))
+}
@@ -0,0 +1,25 @@
+use actix_web::{http::header, web, HttpResponse};
+use crate::auth::jwt::JwtConfig;
+
+/// How long verifiers may cache the key set. Publish a new key at least this
+/// long before it starts signing.
+const JWKS_MAX_AGE_SECS: u32 = 5 * 60;
+
+pub fn well_known_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/.well-known/jwks.json", web::get().to(jwks));
+}
+
+/// The public keys our tokens are signed with, for other services to verify
+/// them without sharing a secret.
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "system",
+    responses((status = 200, description = "A JSON Web Key Set (RFC 7517)", body = Object)),
+    security(())
+)]
+pub async fn jwks(jwt_config: web::Data<JwtConfig>) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, format!("public, max-age={}", JWKS_MAX_AGE_SECS)))
+        .json(jwt_config.jwks())
+}
@@ -0,0 +1,212 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    error::{is_foreign_key_violation, AppError},
+    models::admin::{AdminUserView, ChangeRoleDto, SuspendUserDto, TakedownDto, UserSearchQuery},
+    models::audit::{AuditEvent, AuditQuery},
+    models::companies::Company,
+    models::jobs::Job,
+    models::stats::{AdminStats, StatsQuery, MAX_STATS_RANGE_DAYS},
+    models::users::{User, UserRole},
+    repositories::{AdminRepository, CompanyRepository, JobRepository},
+    services::Change,
+};
+
+/// Moderation and reporting for admins. The caller checks that the requester
+/// is one and passes them in where the rules depend on who is acting.
+pub struct AdminService {
+    admin: Arc<dyn AdminRepository>,
+    jobs: Arc<dyn JobRepository>,
+    companies: Arc<dyn CompanyRepository>,
+}
+
+impl AdminService {
+    pub fn new(
+        admin: Arc<dyn AdminRepository>,
+        jobs: Arc<dyn JobRepository>,
+        companies: Arc<dyn CompanyRepository>,
+    ) -> Self {
+        Self { admin, jobs, companies }
+    }
+
+    /// The dashboard, over the last 30 days unless the query says otherwise.
+    pub async fn stats(&self, query: &StatsQuery) -> Result<AdminStats, AppError> {
+        let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = query.from.unwrap_or(to - Duration::days(29));
+
+        if from > to {
+            return Err(AppError::BadRequest("`from` must not be after `to`".to_string()));
+        }
+
+        if (to - from).num_days() >= MAX_STATS_RANGE_DAYS {
+            return Err(AppError::BadRequest(format!(
+                "Date range cannot exceed {} days",
+                MAX_STATS_RANGE_DAYS
+            )));
+        }
+
+        Ok(self.admin.stats(from, to).await?)
+    }
+
+    pub async fn search_users(&self, query: &UserSearchQuery) -> Result<Vec<AdminUserView>, AppError> {
+        Ok(self.admin.search_users(query).await?)
+    }
+
+    pub async fn suspend_user(
+        &self,
+        admin: &User,
+        id: Uuid,
+        dto: &SuspendUserDto,
+    ) -> Result<Change<AdminUserView>, AppError> {
+        dto.validate()?;
+
+        if admin.id == id {
+            return Err(AppError::BadRequest("Admins cannot suspend themselves".to_string()));
+        }
+
+        let before = self.find_user(id).await?;
+        let after = self.admin.suspend_user(id, &dto.reason).await?.ok_or_else(user_not_found)?;
+
+        Ok(Change { before, after })
+    }
+
+    pub async fn unsuspend_user(&self, id: Uuid) -> Result<Change<AdminUserView>, AppError> {
+        let before = self.find_user(id).await?;
+        let after = self.admin.unsuspend_user(id).await?.ok_or_else(user_not_found)?;
+
+        Ok(Change { before, after })
+    }
+
+    /// Forces the user to log in again.
+    pub async fn revoke_user_tokens(&self, id: Uuid) -> Result<Change<AdminUserView>, AppError> {
+        let before = self.find_user(id).await?;
+        let after = self.admin.revoke_user_tokens(id).await?.ok_or_else(user_not_found)?;
+
+        Ok(Change { before, after })
+    }
+
+    /// Only recruiters are tied to a company, and admins cannot demote themselves.
+    pub async fn change_user_role(
+        &self,
+        admin: &User,
+        id: Uuid,
+        dto: &ChangeRoleDto,
+    ) -> Result<Change<AdminUserView>, AppError> {
+        if admin.id == id && dto.role != UserRole::Admin {
+            return Err(AppError::BadRequest("Admins cannot demote themselves".to_string()));
+        }
+
+        let company_id = match (dto.role, dto.company_id) {
+            (UserRole::Recruiter, Some(company_id)) => Some(company_id),
+            (UserRole::Recruiter, None) => {
+                return Err(AppError::BadRequest("Recruiters must belong to a company".to_string()))
+            }
+            _ => None,
+        };
+
+        let before = self.find_user(id).await?;
+
+        let after = match self.admin.change_user_role(id, dto.role, company_id).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return Err(user_not_found()),
+            Err(e) if is_foreign_key_violation(&e) => {
+                return Err(AppError::BadRequest("Company not found".to_string()))
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Change { before, after })
+    }
+
+    /// Soft-deletes the user and returns them as they were.
+    pub async fn delete_user(&self, admin: &User, id: Uuid) -> Result<AdminUserView, AppError> {
+        if admin.id == id {
+            return Err(AppError::BadRequest("Admins cannot delete themselves".to_string()));
+        }
+
+        let before = self.find_user(id).await?;
+
+        if !self.admin.soft_delete_user(id).await? {
+            return Err(user_not_found());
+        }
+
+        Ok(before)
+    }
+
+    pub async fn restore_user(&self, id: Uuid) -> Result<AdminUserView, AppError> {
+        self.admin
+            .restore_user(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Deleted user not found".to_string()))
+    }
+
+    pub async fn take_down_job(&self, id: Uuid, dto: &TakedownDto) -> Result<(), AppError> {
+        dto.validate()?;
+
+        if !self.admin.take_down_job(id, &dto.reason).await? {
+            return Err(AppError::NotFound("Job not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Restores a soft-deleted or taken-down job, unless its company is
+    /// removed too: that is restored as a whole through
+    /// [`restore_company`](Self::restore_company).
+    pub async fn restore_job(&self, id: Uuid) -> Result<Job, AppError> {
+        match self.admin.find_job_deletion(id).await? {
+            Some(state) if state.company_deleted_at.is_some() || state.company_taken_down_at.is_some() => {
+                return Err(AppError::Conflict(
+                    "The job's company is deleted or taken down; restore the company instead".to_string(),
+                ))
+            }
+            Some(state) if state.deleted_at.is_some() || state.taken_down_at.is_some() => {}
+            _ => return Err(AppError::NotFound("Deleted or taken-down job not found".to_string())),
+        }
+
+        self.admin.restore_job(id).await?;
+
+        self.jobs
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Job not found".to_string()))
+    }
+
+    /// Takes the company down together with all of its jobs.
+    pub async fn take_down_company(&self, id: Uuid, dto: &TakedownDto) -> Result<(), AppError> {
+        dto.validate()?;
+
+        if !self.admin.take_down_company(id, &dto.reason).await? {
+            return Err(AppError::NotFound("Company not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Restores the company and the jobs that were deleted or taken down along
+    /// with it.
+    pub async fn restore_company(&self, id: Uuid) -> Result<Company, AppError> {
+        if !self.admin.restore_company(id).await? {
+            return Err(AppError::NotFound("Deleted or taken-down company not found".to_string()));
+        }
+
+        self.companies
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Company not found".to_string()))
+    }
+
+    pub async fn audit_events(&self, query: &AuditQuery) -> Result<Vec<AuditEvent>, AppError> {
+        Ok(self.admin.list_audit_events(query).await?)
+    }
+
+    async fn find_user(&self, id: Uuid) -> Result<AdminUserView, AppError> {
+        self.admin.find_user(id).await?.ok_or_else(user_not_found)
+    }
+}
+
+fn user_not_found() -> AppError {
+    AppError::NotFound("User not found".to_string())
+}
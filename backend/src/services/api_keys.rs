@@ -0,0 +1,137 @@
+use chrono::{Duration, Utc};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    auth::oidc::random_token,
+    error::AppError,
+    models::{
+        api_keys::{ApiKey, CreateApiKeyDto, CreatedApiKey, NewApiKey},
+        users::User,
+    },
+    repositories::{ApiKeyRepository, CompanyRepository},
+};
+
+/// Marks a bearer token as an API key rather than a JWT.
+pub const API_KEY_PREFIX: &str = "chk_";
+
+/// Random bytes in a key's public prefix, hex-encoded.
+const PREFIX_BYTES: usize = 6;
+
+/// Uses within this long of the last recorded one are not written back, so a
+/// busy integration does not update its key on every request.
+const LAST_USED_PRECISION_SECS: i64 = 60;
+
+/// Whether a bearer token is an API key.
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(API_KEY_PREFIX)
+}
+
+pub struct ApiKeyService {
+    api_keys: Arc<dyn ApiKeyRepository>,
+    companies: Arc<dyn CompanyRepository>,
+}
+
+impl ApiKeyService {
+    pub fn new(api_keys: Arc<dyn ApiKeyRepository>, companies: Arc<dyn CompanyRepository>) -> Self {
+        Self { api_keys, companies }
+    }
+
+    pub async fn list(&self, user: &User, company_id: Uuid) -> Result<Vec<ApiKey>, AppError> {
+        self.require_manager(user, company_id).await?;
+
+        Ok(self.api_keys.list_for_company(company_id).await?)
+    }
+
+    /// Issues a key of the form `chk_<prefix>_<secret>`. Only its hash is kept.
+    pub async fn create(&self, user: &User, company_id: Uuid, dto: &CreateApiKeyDto) -> Result<CreatedApiKey, AppError> {
+        dto.validate()?;
+        self.require_manager(user, company_id).await?;
+
+        if dto.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(AppError::BadRequest("expires_at must be in the future".to_string()));
+        }
+
+        let mut prefix = [0u8; PREFIX_BYTES];
+        OsRng.fill_bytes(&mut prefix);
+        let prefix: String = prefix.iter().map(|b| format!("{:02x}", b)).collect();
+        let key = format!("{}{}_{}", API_KEY_PREFIX, prefix, random_token());
+
+        let mut scopes = Vec::with_capacity(dto.scopes.len());
+        for scope in &dto.scopes {
+            if !scopes.contains(scope) {
+                scopes.push(*scope);
+            }
+        }
+
+        let api_key = self
+            .api_keys
+            .create(&NewApiKey {
+                company_id,
+                created_by: user.id,
+                name: dto.name.clone(),
+                prefix,
+                key_hash: hash_key(&key),
+                scopes,
+                expires_at: dto.expires_at,
+            })
+            .await?;
+
+        Ok(CreatedApiKey { api_key, key })
+    }
+
+    pub async fn revoke(&self, user: &User, company_id: Uuid, id: Uuid) -> Result<ApiKey, AppError> {
+        self.require_manager(user, company_id).await?;
+
+        self.api_keys
+            .revoke(company_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("API key not found".to_string()))
+    }
+
+    /// The active key `key` belongs to, recording that it was used.
+    pub async fn authenticate(&self, key: &str) -> Result<ApiKey, AppError> {
+        let invalid = || AppError::Unauthorized("Invalid API key".to_string());
+
+        let prefix = key
+            .strip_prefix(API_KEY_PREFIX)
+            .and_then(|rest| rest.split_once('_'))
+            .map(|(prefix, _)| prefix)
+            .ok_or_else(invalid)?;
+
+        let api_key = self
+            .api_keys
+            .find_active(prefix)
+            .await?
+            .filter(|api_key| api_key.key_hash == hash_key(key))
+            .ok_or_else(invalid)?;
+
+        let stale = api_key
+            .last_used_at
+            .is_none_or(|at| Utc::now() - at >= Duration::seconds(LAST_USED_PRECISION_SECS));
+        if stale {
+            self.api_keys.record_use(api_key.id).await?;
+        }
+
+        Ok(api_key)
+    }
+
+    async fn require_manager(&self, user: &User, company_id: Uuid) -> Result<(), AppError> {
+        if self.companies.find_by_id(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Company not found".to_string()));
+        }
+
+        if !user.can_manage_company(company_id) {
+            return Err(AppError::Forbidden("Not a recruiter for this company".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Keys carry 256 random bits, so a fast hash suffices.
+fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    error::AppError,
+    models::applications::{Application, ApplicationStatus, CreateApplicationDto, UpdateApplicationStatusDto},
+    models::users::User,
+    repositories::{ApplicationRepository, JobRepository},
+    services::Change,
+};
+
+pub struct ApplicationService {
+    applications: Arc<dyn ApplicationRepository>,
+    jobs: Arc<dyn JobRepository>,
+}
+
+impl ApplicationService {
+    pub fn new(applications: Arc<dyn ApplicationRepository>, jobs: Arc<dyn JobRepository>) -> Self {
+        Self { applications, jobs }
+    }
+
+    pub async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<Application>, AppError> {
+        Ok(self.applications.list_for_user(user_id).await?)
+    }
+
+    /// Applications to a company's jobs. The caller checks that the
+    /// requester may see them.
+    pub async fn list_for_company(&self, company_id: Uuid) -> Result<Vec<Application>, AppError> {
+        Ok(self.applications.list_for_company(company_id).await?)
+    }
+
+    pub async fn apply(&self, user_id: Uuid, dto: &CreateApplicationDto) -> Result<Application, AppError> {
+        dto.validate()?;
+
+        match self.jobs.find_visible(dto.job_id).await? {
+            Some(job) if job.is_active => {}
+            _ => return Err(AppError::NotFound("Job not found or inactive".to_string())),
+        }
+
+        if self.applications.exists(user_id, dto.job_id).await? {
+            return Err(AppError::Conflict("You have already applied for this job".to_string()));
+        }
+
+        Ok(self.applications.create(user_id, dto).await?)
+    }
+
+    /// Moves an application through the pipeline on behalf of one of the
+    /// company's recruiters. Rejected, accepted and closed applications stay
+    /// where they are.
+    pub async fn update_status(
+        &self,
+        user: &User,
+        id: Uuid,
+        dto: &UpdateApplicationStatusDto,
+    ) -> Result<Change<Application>, AppError> {
+        // Accepting happens through offers, closing through an accepted offer.
+        if matches!(dto.status, ApplicationStatus::Accepted | ApplicationStatus::Closed) {
+            return Err(AppError::BadRequest("Status can only be set through an offer".to_string()));
+        }
+
+        let company_id = self
+            .applications
+            .find_company_id(id)
+            .await?
+            .ok_or_else(application_not_found)?;
+
+        if !user.can_manage_company(company_id) {
+            return Err(AppError::Forbidden("Not a recruiter for this company".to_string()));
+        }
+
+        let before = self.applications.find_by_id(id).await?.ok_or_else(application_not_found)?;
+        if !before.status.can_move_to(dto.status) {
+            return Err(application_closed());
+        }
+
+        // Checked again under the row lock, in case it closed in the meantime.
+        let after = self
+            .applications
+            .update_status(id, dto.status)
+            .await?
+            .ok_or_else(application_closed)?;
+
+        Ok(Change { before, after })
+    }
+}
+
+fn application_not_found() -> AppError {
+    AppError::NotFound("Application not found".to_string())
+}
+
+fn application_closed() -> AppError {
+    AppError::Conflict("The application is no longer open".to_string())
+}
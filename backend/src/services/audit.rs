@@ -0,0 +1,88 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+use crate::{audit::AuditContext, models::audit::NewAuditEvent, repositories::AuditRepository};
+
+/// Fields never written to the audit log.
+const REDACTED_FIELDS: &[&str] = &["password_hash"];
+
+/// Fields that change on every write and would only add noise to a diff.
+const IGNORED_CHANGES: &[&str] = &["updated_at"];
+
+pub struct AuditService {
+    audit: Arc<dyn AuditRepository>,
+}
+
+impl AuditService {
+    pub fn new(audit: Arc<dyn AuditRepository>) -> Self {
+        Self { audit }
+    }
+
+    /// Appends an event to the audit log.
+    ///
+    /// Auditing is best effort: a failure is logged but never fails the request
+    /// whose mutation has already been committed.
+    pub async fn record<T: Serialize>(
+        &self,
+        ctx: &AuditContext,
+        action: &str,
+        entity_type: &str,
+        entity_id: Uuid,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) {
+        let before = to_json(before);
+        let after = to_json(after);
+        let changes = diff(before.as_ref(), after.as_ref());
+
+        let event = NewAuditEvent {
+            actor_id: ctx.actor_id,
+            api_key_id: ctx.api_key_id,
+            action: action.to_string(),
+            entity_type: entity_type.to_string(),
+            entity_id,
+            before,
+            after,
+            changes,
+            request_id: ctx.request_id.clone(),
+            ip_address: ctx.ip_address.clone(),
+            user_agent: ctx.user_agent.clone(),
+            method: ctx.method.clone(),
+            path: ctx.path.clone(),
+        };
+
+        if let Err(e) = self.audit.record(&event).await {
+            tracing::error!(error = %e, "Failed to record audit event {} {} {}", action, entity_type, entity_id);
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: Option<&T>) -> Option<Value> {
+    let mut value = serde_json::to_value(value?).ok()?;
+    if let Value::Object(fields) = &mut value {
+        for field in REDACTED_FIELDS {
+            fields.remove(*field);
+        }
+    }
+    Some(value)
+}
+
+/// Top-level fields whose value differs between `before` and `after`.
+fn diff(before: Option<&Value>, after: Option<&Value>) -> Value {
+    let empty = Map::new();
+    let before = before.and_then(Value::as_object).unwrap_or(&empty);
+    let after = after.and_then(Value::as_object).unwrap_or(&empty);
+
+    let mut changes = Map::new();
+    for key in before.keys().chain(after.keys()) {
+        if IGNORED_CHANGES.contains(&key.as_str()) || changes.contains_key(key) {
+            continue;
+        }
+        let (from, to) = (before.get(key), after.get(key));
+        if from != to {
+            changes.insert(key.clone(), json!({ "from": from, "to": to }));
+        }
+    }
+    Value::Object(changes)
+}
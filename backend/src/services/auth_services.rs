@@ -1,13 +0,0 @@
-use sqlx::PgPool;
-
-pub struct AuthService {
-    pub pool: PgPool,
-}
-
-impl AuthService {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
-    }
-
-    // TODO: Add authentication methods
-}
\ No newline at end of file
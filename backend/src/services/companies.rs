@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    error::{is_unique_violation, AppError},
+    models::companies::{slugify, Company, CompanyPage, CreateCompanyDto, UpdateCompanyDto},
+    repositories::{CompanyRepository, JobRepository},
+    services::Change,
+};
+
+/// Number of open jobs embedded in a public company page.
+const COMPANY_PAGE_JOBS: i64 = 50;
+
+pub struct CompanyService {
+    companies: Arc<dyn CompanyRepository>,
+    jobs: Arc<dyn JobRepository>,
+}
+
+impl CompanyService {
+    pub fn new(companies: Arc<dyn CompanyRepository>, jobs: Arc<dyn JobRepository>) -> Self {
+        Self { companies, jobs }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Company>, AppError> {
+        Ok(self.companies.list().await?)
+    }
+
+    /// A company as shown to the public; taken-down companies are not found.
+    pub async fn get(&self, id: Uuid) -> Result<Company, AppError> {
+        self.companies.find_visible(id).await?.ok_or_else(company_not_found)
+    }
+
+    /// Any company that has not been deleted, for moderation.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Company>, AppError> {
+        Ok(self.companies.find_by_id(id).await?)
+    }
+
+    /// The public page behind `slug`, with the company's open jobs.
+    pub async fn page(&self, slug: &str) -> Result<CompanyPage, AppError> {
+        let company = self
+            .companies
+            .find_visible_by_slug(slug)
+            .await?
+            .ok_or_else(company_not_found)?;
+
+        let open_jobs = self.jobs.list_open_for_company(company.id, COMPANY_PAGE_JOBS).await?;
+        let job_count = self.jobs.count_open_for_company(company.id).await?;
+
+        Ok(CompanyPage {
+            company,
+            open_jobs,
+            job_count,
+        })
+    }
+
+    pub async fn create(&self, dto: &CreateCompanyDto) -> Result<Company, AppError> {
+        dto.validate()?;
+
+        let mut slug = slugify(&dto.name);
+        if slug.is_empty() || self.companies.slug_exists(&slug).await? {
+            let suffix = &Uuid::new_v4().simple().to_string()[..8];
+            slug = if slug.is_empty() {
+                suffix.to_string()
+            } else {
+                format!("{}-{}", slug, suffix)
+            };
+        }
+
+        match self.companies.create(dto, &slug).await {
+            Ok(company) => Ok(company),
+            Err(e) if is_unique_violation(&e) => Err(AppError::Conflict("Company slug already exists".to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn update(&self, id: Uuid, dto: &UpdateCompanyDto) -> Result<Change<Company>, AppError> {
+        dto.validate()?;
+
+        let before = self.companies.find_by_id(id).await?.ok_or_else(company_not_found)?;
+
+        if dto.is_empty() {
+            return Err(AppError::BadRequest("No fields to update".to_string()));
+        }
+
+        let after = self.companies.update(id, dto).await?.ok_or_else(company_not_found)?;
+
+        Ok(Change { before, after })
+    }
+
+    pub async fn set_verified(&self, id: Uuid, verified: bool) -> Result<Change<Company>, AppError> {
+        let before = self.companies.find_by_id(id).await?.ok_or_else(company_not_found)?;
+        let after = self
+            .companies
+            .set_verified(id, verified)
+            .await?
+            .ok_or_else(company_not_found)?;
+
+        Ok(Change { before, after })
+    }
+
+    /// Soft-deletes the company and its jobs. Returns the company as it was, or
+    /// `None` if it was already gone.
+    pub async fn delete(&self, id: Uuid) -> Result<Option<Company>, AppError> {
+        let Some(company) = self.companies.find_by_id(id).await? else {
+            return Ok(None);
+        };
+
+        self.companies.soft_delete(id).await?;
+
+        Ok(Some(company))
+    }
+
+    pub async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, AppError> {
+        Ok(self.companies.purge_deleted(cutoff).await?)
+    }
+}
+
+fn company_not_found() -> AppError {
+    AppError::NotFound("Company not found".to_string())
+}
@@ -1,13 +0,0 @@
-use sqlx::PgPool;
-
-pub struct JobService {
-    pub pool: PgPool,
-}
-
-impl JobService {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
-    }
-
-    // TODO: Add job-related methods
-}
\ No newline at end of file
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    error::AppError,
+    models::jobs::{CreateJobDto, Job, JobPage, JobQuery, UpdateJobDto},
+    repositories::JobRepository,
+    services::Change,
+};
+
+pub struct JobService {
+    jobs: Arc<dyn JobRepository>,
+}
+
+impl JobService {
+    pub fn new(jobs: Arc<dyn JobRepository>) -> Self {
+        Self { jobs }
+    }
+
+    pub async fn list(&self, query: &JobQuery) -> Result<Vec<Job>, AppError> {
+        Ok(self.jobs.list(query).await?)
+    }
+
+    /// One page of [`list`](Self::list) with the total number of matches.
+    pub async fn page(&self, query: &JobQuery) -> Result<JobPage, AppError> {
+        let items = self.jobs.list(query).await?;
+        let total = self.jobs.count(query).await?;
+
+        Ok(JobPage { items, page: query.page(), per_page: query.per_page(), total })
+    }
+
+    /// A job as shown to the public; taken-down jobs are not found.
+    pub async fn get(&self, id: Uuid) -> Result<Job, AppError> {
+        self.jobs.find_visible(id).await?.ok_or_else(job_not_found)
+    }
+
+    pub async fn create(&self, dto: &CreateJobDto) -> Result<Job, AppError> {
+        dto.validate()?;
+
+        Ok(self.jobs.create(dto).await?)
+    }
+
+    /// Taken-down jobs are not found until an admin restores them.
+    pub async fn update(&self, id: Uuid, dto: &UpdateJobDto) -> Result<Change<Job>, AppError> {
+        dto.validate()?;
+
+        let before = self.jobs.find_visible(id).await?.ok_or_else(job_not_found)?;
+        let after = self.jobs.update(id, dto).await?.ok_or_else(job_not_found)?;
+
+        Ok(Change { before, after })
+    }
+
+    /// Soft-deletes the job. Returns it as it was, or `None` if it was already
+    /// gone or is taken down.
+    pub async fn delete(&self, id: Uuid) -> Result<Option<Job>, AppError> {
+        let Some(job) = self.jobs.find_visible(id).await? else {
+            return Ok(None);
+        };
+
+        self.jobs.soft_delete(id).await?;
+
+        Ok(Some(job))
+    }
+
+    pub async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, AppError> {
+        Ok(self.jobs.purge_deleted(cutoff).await?)
+    }
+}
+
+fn job_not_found() -> AppError {
+    AppError::NotFound("Job not found".to_string())
+}
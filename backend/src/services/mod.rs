@@ -1,12 +1,119 @@
-pub mod auth;
-pub mod jobs;
-pub mod companies;
+//! Business rules, written against the repository traits so handlers stay thin.
+
+pub mod admin;
+pub mod api_keys;
+pub mod audit;
 pub mod applications;
+pub mod companies;
+pub mod jobs;
+pub mod offers;
+pub mod oidc;
+pub mod reviews;
+pub mod two_factor;
+pub mod users;
+pub mod webhooks;
 
 use sqlx::PgPool;
-use crate::config::Config;
+use std::sync::Arc;
+use crate::{
+    config::{OidcConfig, WebhookConfig},
+    repositories::{
+        AdminRepository, ApiKeyRepository, ApplicationRepository, AuditRepository,
+        CompanyRepository, IdentityRepository, JobRepository, OfferRepository, PgAdminRepository,
+        PgApiKeyRepository, PgApplicationRepository, PgAuditRepository, PgCompanyRepository,
+        PgIdentityRepository, PgJobRepository, PgOfferRepository, PgReviewRepository,
+        PgTwoFactorRepository, PgUserRepository, PgWebhookRepository, ReviewRepository,
+        TwoFactorRepository, UserRepository, WebhookRepository,
+    },
+};
+
+pub use admin::AdminService;
+pub use api_keys::ApiKeyService;
+pub use audit::AuditService;
+pub use applications::ApplicationService;
+pub use companies::CompanyService;
+pub use jobs::JobService;
+pub use offers::{AcceptedOffer, OfferService};
+pub use oidc::OidcService;
+pub use reviews::ReviewService;
+pub use two_factor::TwoFactorService;
+pub use users::UserService;
+pub use webhooks::WebhookService;
+
+/// An entity as it was before and after a change, for the audit log.
+#[derive(Debug)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+}
+
+/// Every service, sharing one set of repositories.
+#[derive(Clone)]
+pub struct Services {
+    pub users: Arc<UserService>,
+    pub jobs: Arc<JobService>,
+    pub companies: Arc<CompanyService>,
+    pub applications: Arc<ApplicationService>,
+    pub offers: Arc<OfferService>,
+    pub reviews: Arc<ReviewService>,
+    pub admin: Arc<AdminService>,
+    pub two_factor: Arc<TwoFactorService>,
+    pub oidc: Arc<OidcService>,
+    pub api_keys: Arc<ApiKeyService>,
+    pub webhooks: Arc<WebhookService>,
+    pub audit: Arc<AuditService>,
+}
+
+impl Services {
+    /// Services with OIDC and webhook delivery in their default configuration;
+    /// see [`AppState::with_oidc`](crate::AppState::with_oidc) and
+    /// [`AppState::with_webhooks`](crate::AppState::with_webhooks).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        jobs: Arc<dyn JobRepository>,
+        companies: Arc<dyn CompanyRepository>,
+        applications: Arc<dyn ApplicationRepository>,
+        offers: Arc<dyn OfferRepository>,
+        reviews: Arc<dyn ReviewRepository>,
+        admin: Arc<dyn AdminRepository>,
+        two_factor: Arc<dyn TwoFactorRepository>,
+        identities: Arc<dyn IdentityRepository>,
+        api_keys: Arc<dyn ApiKeyRepository>,
+        webhooks: Arc<dyn WebhookRepository>,
+        audit: Arc<dyn AuditRepository>,
+    ) -> Self {
+        Self {
+            users: Arc::new(UserService::new(users.clone())),
+            jobs: Arc::new(JobService::new(jobs.clone())),
+            companies: Arc::new(CompanyService::new(companies.clone(), jobs.clone())),
+            applications: Arc::new(ApplicationService::new(applications, jobs.clone())),
+            offers: Arc::new(OfferService::new(offers)),
+            reviews: Arc::new(ReviewService::new(reviews)),
+            admin: Arc::new(AdminService::new(admin, jobs, companies.clone())),
+            two_factor: Arc::new(TwoFactorService::new(users.clone(), two_factor)),
+            oidc: Arc::new(OidcService::new(&OidcConfig::default(), users, identities)),
+            api_keys: Arc::new(ApiKeyService::new(api_keys, companies.clone())),
+            webhooks: Arc::new(WebhookService::new(&WebhookConfig::default(), webhooks, companies)),
+            audit: Arc::new(AuditService::new(audit)),
+        }
+    }
 
-pub struct AppState {
-    pub db: PgPool,
-    pub config: Config,
-} 
\ No newline at end of file
+    /// Services backed by the Postgres repositories.
+    pub fn postgres(pool: &PgPool) -> Self {
+        Self::new(
+            Arc::new(PgUserRepository::new(pool.clone())),
+            Arc::new(PgJobRepository::new(pool.clone())),
+            Arc::new(PgCompanyRepository::new(pool.clone())),
+            Arc::new(PgApplicationRepository::new(pool.clone())),
+            Arc::new(PgOfferRepository::new(pool.clone())),
+            Arc::new(PgReviewRepository::new(pool.clone())),
+            Arc::new(PgAdminRepository::new(pool.clone())),
+            Arc::new(PgTwoFactorRepository::new(pool.clone())),
+            Arc::new(PgIdentityRepository::new(pool.clone())),
+            Arc::new(PgApiKeyRepository::new(pool.clone())),
+            Arc::new(PgWebhookRepository::new(pool.clone())),
+            Arc::new(PgAuditRepository::new(pool.clone())),
+        )
+    }
+}
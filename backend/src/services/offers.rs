@@ -0,0 +1,175 @@
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    error::{is_unique_violation, AppError},
+    models::applications::Application,
+    models::offers::{CreateOfferDto, Offer, OfferParties, OfferStatus},
+    models::users::{User, UserRole},
+    repositories::OfferRepository,
+    services::Change,
+};
+
+/// An accepted offer and what accepting it changed.
+#[derive(Debug)]
+pub struct AcceptedOffer {
+    pub offer: Change<Offer>,
+    /// The company that made the offer.
+    pub company_id: Uuid,
+    /// The accepted application, then any closed along with it.
+    pub applications: Vec<Application>,
+}
+
+pub struct OfferService {
+    offers: Arc<dyn OfferRepository>,
+}
+
+impl OfferService {
+    pub fn new(offers: Arc<dyn OfferRepository>) -> Self {
+        Self { offers }
+    }
+
+    /// Candidates see offers made to them, recruiters those made by their
+    /// company and admins every offer.
+    pub async fn list(&self, user: &User) -> Result<Vec<Offer>, AppError> {
+        let company_id = match user.role {
+            UserRole::Recruiter => user.company_id,
+            _ => None,
+        };
+
+        Ok(self.offers.list(user.id, company_id, user.role == UserRole::Admin).await?)
+    }
+
+    /// An offer the user is the candidate for or may manage.
+    pub async fn get(&self, user: &User, id: Uuid) -> Result<Offer, AppError> {
+        let (offer, parties) = self.find(id).await?;
+        if parties.candidate_id != user.id && !user.can_manage_company(parties.company_id) {
+            return Err(offer_not_found());
+        }
+
+        Ok(offer)
+    }
+
+    /// Makes an offer for an open application on behalf of one of the job's
+    /// company's recruiters, in the currency of the job's salary range.
+    pub async fn create(&self, user: &User, dto: &CreateOfferDto) -> Result<Offer, AppError> {
+        dto.validate()?;
+
+        if dto.expires_at <= Utc::now() {
+            return Err(AppError::BadRequest("Offer expiry must be in the future".to_string()));
+        }
+
+        if dto.start_date < Utc::now().date_naive() {
+            return Err(AppError::BadRequest("Start date cannot be in the past".to_string()));
+        }
+
+        let target = self
+            .offers
+            .find_target(dto.application_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Application not found".to_string()))?;
+
+        if !user.can_manage_company(target.company_id) {
+            return Err(AppError::Forbidden("Not a recruiter for this company".to_string()));
+        }
+
+        if !target.status.is_open() {
+            return Err(AppError::Conflict("Application is no longer open".to_string()));
+        }
+
+        let currency = target
+            .currency
+            .ok_or_else(|| AppError::BadRequest("Job salary range has no currency".to_string()))?;
+
+        match self.offers.create(user.id, target.job_id, &currency, dto).await {
+            Ok(offer) => Ok(offer),
+            Err(e) if is_unique_violation(&e) => {
+                Err(AppError::Conflict("Application already has a pending offer".to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The candidate accepts the offer; see [`OfferRepository::accept`].
+    pub async fn accept(&self, user_id: Uuid, id: Uuid) -> Result<AcceptedOffer, AppError> {
+        let (before, parties) = self.find(id).await?;
+        if parties.candidate_id != user_id {
+            return Err(offer_not_found());
+        }
+
+        self.ensure_pending(&before).await?;
+
+        let (after, applications) = self.offers.accept(id).await?.ok_or_else(no_longer_pending)?;
+
+        Ok(AcceptedOffer {
+            offer: Change { before, after },
+            company_id: parties.company_id,
+            applications,
+        })
+    }
+
+    pub async fn decline(&self, user_id: Uuid, id: Uuid) -> Result<Change<Offer>, AppError> {
+        let (offer, parties) = self.find(id).await?;
+        if parties.candidate_id != user_id {
+            return Err(offer_not_found());
+        }
+
+        self.respond(offer, OfferStatus::Declined).await
+    }
+
+    /// One of the company's recruiters takes the offer back.
+    pub async fn withdraw(&self, user: &User, id: Uuid) -> Result<Change<Offer>, AppError> {
+        let (offer, parties) = self.find(id).await?;
+        if !user.can_manage_company(parties.company_id) {
+            return Err(offer_not_found());
+        }
+
+        self.respond(offer, OfferStatus::Withdrawn).await
+    }
+
+    pub async fn expire_overdue(&self) -> Result<u64, AppError> {
+        Ok(self.offers.expire_overdue().await?)
+    }
+
+    async fn find(&self, id: Uuid) -> Result<(Offer, OfferParties), AppError> {
+        let offer = self.offers.find_by_id(id).await?.ok_or_else(offer_not_found)?;
+        let parties = self
+            .offers
+            .find_parties(offer.application_id)
+            .await?
+            .ok_or_else(offer_not_found)?;
+
+        Ok((offer, parties))
+    }
+
+    async fn respond(&self, before: Offer, status: OfferStatus) -> Result<Change<Offer>, AppError> {
+        self.ensure_pending(&before).await?;
+
+        let after = self.offers.respond(before.id, status).await?.ok_or_else(no_longer_pending)?;
+
+        Ok(Change { before, after })
+    }
+
+    /// Rejects responses to offers that are no longer open, expiring them lazily if needed.
+    async fn ensure_pending(&self, offer: &Offer) -> Result<(), AppError> {
+        if offer.status != OfferStatus::Pending {
+            return Err(no_longer_pending());
+        }
+
+        if offer.expires_at <= Utc::now() {
+            self.offers.expire_overdue().await?;
+            return Err(AppError::Conflict("Offer has expired".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+fn offer_not_found() -> AppError {
+    AppError::NotFound("Offer not found".to_string())
+}
+
+fn no_longer_pending() -> AppError {
+    AppError::Conflict("Offer is no longer pending".to_string())
+}
@@ -0,0 +1,157 @@
+use chrono::{Duration, Utc};
+use reqwest::Client;
+use std::{collections::BTreeMap, sync::Arc, time::Duration as StdDuration};
+use validator::Validate;
+use crate::{
+    auth::oidc::{random_token, IdTokenClaims, OidcProvider},
+    config::OidcConfig,
+    error::{is_unique_violation, AppError},
+    models::{
+        identities::{OidcAuthorization, OidcCallbackDto, OidcLoginState},
+        users::User,
+    },
+    repositories::{IdentityRepository, UserRepository},
+};
+
+/// Upper bound on each request to a provider.
+const PROVIDER_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+
+/// Longest name taken from a provider, matching [`CreateUserDto`](crate::models::users::CreateUserDto).
+const MAX_NAME_LEN: usize = 100;
+
+pub struct OidcService {
+    providers: BTreeMap<String, OidcProvider>,
+    login_timeout: Duration,
+    users: Arc<dyn UserRepository>,
+    identities: Arc<dyn IdentityRepository>,
+}
+
+impl OidcService {
+    pub fn new(config: &OidcConfig, users: Arc<dyn UserRepository>, identities: Arc<dyn IdentityRepository>) -> Self {
+        let http = Client::builder()
+            .timeout(PROVIDER_TIMEOUT)
+            .build()
+            .expect("HTTP client configuration is valid");
+
+        let providers = config
+            .providers
+            .iter()
+            .map(|(name, provider)| (name.clone(), OidcProvider::new(provider.clone(), http.clone())))
+            .collect();
+
+        Self {
+            providers,
+            login_timeout: Duration::seconds(config.login_timeout_secs),
+            users,
+            identities,
+        }
+    }
+
+    /// Names of the configured providers.
+    pub fn providers(&self) -> Vec<&str> {
+        self.providers.keys().map(String::as_str).collect()
+    }
+
+    /// Starts a login at `provider`, remembering what is needed to finish it.
+    pub async fn authorize(&self, provider: &str) -> Result<OidcAuthorization, AppError> {
+        let client = self.provider(provider)?;
+
+        let login = OidcLoginState {
+            state: random_token(),
+            provider: provider.to_string(),
+            code_verifier: random_token(),
+            nonce: random_token(),
+            expires_at: Utc::now() + self.login_timeout,
+        };
+        let authorization_url = client
+            .authorization_url(&login.state, &login.nonce, &login.code_verifier)
+            .await?;
+        self.identities.save_state(&login).await?;
+
+        Ok(OidcAuthorization {
+            authorization_url,
+            expires_in: self.login_timeout.num_seconds(),
+        })
+    }
+
+    /// Finishes a login and returns the local user, linking or creating one
+    /// by verified email the first time an identity is seen.
+    pub async fn complete(&self, provider: &str, dto: &OidcCallbackDto) -> Result<User, AppError> {
+        let client = self.provider(provider)?;
+        dto.validate()?;
+
+        let login = self
+            .identities
+            .take_state(&dto.state)
+            .await?
+            .filter(|login| login.provider == provider)
+            .ok_or_else(|| AppError::Unauthorized("Invalid or expired login".to_string()))?;
+
+        let claims = client.exchange(&dto.code, &login.code_verifier, &login.nonce).await?;
+
+        let user_id = match self.identities.find(provider, &claims.sub).await? {
+            Some(identity) => {
+                let email = claims.email.as_deref().unwrap_or(&identity.email);
+                self.identities.record_login(identity.id, email).await?;
+                identity.user_id
+            }
+            None => self.link(provider, &claims).await?,
+        };
+
+        let user = self
+            .users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Account not found".to_string()))?;
+
+        if self.users.is_suspended(user.id).await? {
+            return Err(AppError::Forbidden("Account suspended".to_string()));
+        }
+
+        Ok(user)
+    }
+
+    /// Links a new identity to the user with its email, or to a new user.
+    /// Only emails the provider has verified are trusted for this.
+    async fn link(&self, provider: &str, claims: &IdTokenClaims) -> Result<uuid::Uuid, AppError> {
+        let email = match &claims.email {
+            Some(email) if claims.email_verified => email,
+            _ => {
+                return Err(AppError::Forbidden(
+                    "The identity provider has not verified an email address for this account".to_string(),
+                ))
+            }
+        };
+
+        if let Some(user) = self.users.find_by_email(email).await? {
+            return match self.identities.link(user.id, provider, &claims.sub, email).await {
+                Ok(identity) => Ok(identity.user_id),
+                Err(e) if is_unique_violation(&e) => Err(AppError::Conflict(format!(
+                    "This account is already linked to another {} login",
+                    provider
+                ))),
+                Err(e) => Err(e.into()),
+            };
+        }
+
+        let name = claims
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| email.split('@').next().unwrap_or(email));
+        let name: String = name.chars().take(MAX_NAME_LEN).collect();
+
+        match self.identities.create_user(email, &name, provider, &claims.sub).await {
+            Ok(id) => Ok(id),
+            Err(e) if is_unique_violation(&e) => Err(AppError::Conflict("Email already exists".to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn provider(&self, name: &str) -> Result<&OidcProvider, AppError> {
+        self.providers
+            .get(name)
+            .ok_or_else(|| AppError::NotFound("Unknown identity provider".to_string()))
+    }
+}
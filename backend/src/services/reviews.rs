@@ -0,0 +1,98 @@
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    error::{is_unique_violation, AppError},
+    models::reviews::{CompanyReview, CreateReviewDto, ModerateReviewDto, ReviewQuery, ReviewStatus},
+    models::users::{User, UserRole},
+    repositories::ReviewRepository,
+};
+
+/// Company reviews and their moderation. A company's rating only counts
+/// approved reviews, so it is recomputed whenever one may have changed.
+pub struct ReviewService {
+    reviews: Arc<dyn ReviewRepository>,
+}
+
+impl ReviewService {
+    pub fn new(reviews: Arc<dyn ReviewRepository>) -> Self {
+        Self { reviews }
+    }
+
+    pub async fn list_for_company(&self, company_id: Uuid, query: &ReviewQuery) -> Result<Vec<CompanyReview>, AppError> {
+        Ok(self.reviews.list_for_company(company_id, query).await?)
+    }
+
+    /// Reviews with the requested status, pending by default. The caller
+    /// checks that the requester is an admin.
+    pub async fn list_for_moderation(&self, query: &ReviewQuery) -> Result<Vec<CompanyReview>, AppError> {
+        let status = query.status.unwrap_or(ReviewStatus::Pending);
+
+        Ok(self.reviews.list_by_status(status, query).await?)
+    }
+
+    /// Adds the user's review of the company, pending moderation.
+    pub async fn create(&self, user_id: Uuid, company_id: Uuid, dto: &CreateReviewDto) -> Result<CompanyReview, AppError> {
+        dto.validate()?;
+
+        match self.reviews.create(company_id, user_id, dto).await {
+            Ok(Some(review)) => Ok(review),
+            Ok(None) => Err(AppError::NotFound("Company not found".to_string())),
+            Err(e) if is_unique_violation(&e) => {
+                Err(AppError::Conflict("You have already reviewed this company".to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Replaces the user's own review; edits go back through moderation.
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        id: Uuid,
+        dto: &CreateReviewDto,
+    ) -> Result<CompanyReview, AppError> {
+        dto.validate()?;
+
+        let review = self
+            .reviews
+            .update(id, company_id, user_id, dto)
+            .await?
+            .ok_or_else(review_not_found)?;
+
+        self.reviews.refresh_company_rating(company_id).await?;
+
+        Ok(review)
+    }
+
+    /// Authors delete their own reviews, admins anyone's.
+    pub async fn delete(&self, user: &User, company_id: Uuid, id: Uuid) -> Result<(), AppError> {
+        let author_id = (user.role != UserRole::Admin).then_some(user.id);
+
+        if !self.reviews.delete(id, company_id, author_id).await? {
+            return Err(review_not_found());
+        }
+
+        self.reviews.refresh_company_rating(company_id).await?;
+
+        Ok(())
+    }
+
+    /// Records an admin's decision on a review.
+    pub async fn moderate(&self, admin: &User, id: Uuid, dto: &ModerateReviewDto) -> Result<CompanyReview, AppError> {
+        let review = self
+            .reviews
+            .moderate(id, dto.status, dto.note.as_deref(), admin.id)
+            .await?
+            .ok_or_else(review_not_found)?;
+
+        self.reviews.refresh_company_rating(review.company_id).await?;
+
+        Ok(review)
+    }
+}
+
+fn review_not_found() -> AppError {
+    AppError::NotFound("Review not found".to_string())
+}
@@ -0,0 +1,229 @@
+use chrono::Utc;
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    error::AppError,
+    models::two_factor::{RecoveryCodes, TotpEnrollment, TotpSecret, TwoFactorCodeDto, TwoFactorStatus},
+    repositories::{TwoFactorRepository, UserRepository},
+};
+
+/// Shown by authenticator apps next to the account.
+const ISSUER: &str = "CareerHub";
+
+/// 160 bits, as RFC 4226 recommends.
+const SECRET_BYTES: usize = 20;
+const DIGITS: usize = 6;
+const STEP_SECS: u64 = 30;
+/// Codes from one step either side of the current one are accepted, to allow for clock drift.
+const SKEW_STEPS: u64 = 1;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LEN: usize = 10;
+/// Lowercase letters and digits without the easily confused `0`, `1`, `i`, `l` and `o`.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+pub struct TwoFactorService {
+    users: Arc<dyn UserRepository>,
+    two_factor: Arc<dyn TwoFactorRepository>,
+}
+
+impl TwoFactorService {
+    pub fn new(users: Arc<dyn UserRepository>, two_factor: Arc<dyn TwoFactorRepository>) -> Self {
+        Self { users, two_factor }
+    }
+
+    pub async fn status(&self, user_id: Uuid) -> Result<TwoFactorStatus, AppError> {
+        let enabled_at = self.enabled(user_id).await?.and_then(|secret| secret.enabled_at);
+        let recovery_codes_remaining = match enabled_at {
+            Some(_) => self.two_factor.count_recovery_codes(user_id).await?,
+            None => 0,
+        };
+
+        Ok(TwoFactorStatus {
+            enabled: enabled_at.is_some(),
+            enabled_at,
+            recovery_codes_remaining,
+        })
+    }
+
+    pub async fn is_enabled(&self, user_id: Uuid) -> Result<bool, AppError> {
+        Ok(self.enabled(user_id).await?.is_some())
+    }
+
+    /// Starts enrollment with a fresh secret. Two-factor authentication stays
+    /// off until [`confirm`](Self::confirm) sees a code generated from it.
+    pub async fn enroll(&self, user_id: Uuid) -> Result<TotpEnrollment, AppError> {
+        let user = self
+            .users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let mut secret = vec![0u8; SECRET_BYTES];
+        OsRng.fill_bytes(&mut secret);
+        let totp = totp(secret, &user.email)?;
+        let encoded = totp.get_secret_base32();
+
+        if !self.two_factor.save_pending(user_id, &encoded).await? {
+            return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+        }
+
+        Ok(TotpEnrollment {
+            secret: encoded,
+            otpauth_uri: totp.get_url(),
+        })
+    }
+
+    /// Turns two-factor authentication on once the user proves their
+    /// authenticator works, and issues the recovery codes.
+    pub async fn confirm(&self, user_id: Uuid, dto: &TwoFactorCodeDto) -> Result<RecoveryCodes, AppError> {
+        dto.validate()?;
+
+        let secret = match self.two_factor.find(user_id).await? {
+            Some(secret) if secret.enabled_at.is_some() => {
+                return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()))
+            }
+            Some(secret) => secret,
+            None => return Err(AppError::BadRequest("Two-factor enrollment has not been started".to_string())),
+        };
+
+        let step = matching_step(&secret, &dto.code)?
+            .ok_or_else(|| AppError::BadRequest("Invalid two-factor code".to_string()))?;
+
+        let (codes, hashes) = generate_recovery_codes();
+        self.two_factor.enable(user_id, step, &hashes).await?;
+
+        Ok(RecoveryCodes { recovery_codes: codes })
+    }
+
+    /// Turns two-factor authentication off, given a current code or a recovery code.
+    pub async fn disable(&self, user_id: Uuid, dto: &TwoFactorCodeDto) -> Result<(), AppError> {
+        dto.validate()?;
+
+        self.verify(user_id, &dto.code).await?;
+        self.two_factor.delete(user_id).await?;
+
+        Ok(())
+    }
+
+    /// Replaces every recovery code, given a current TOTP code.
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: Uuid,
+        dto: &TwoFactorCodeDto,
+    ) -> Result<RecoveryCodes, AppError> {
+        dto.validate()?;
+
+        if !is_totp_code(&dto.code) {
+            return Err(AppError::BadRequest("A code from the authenticator app is required".to_string()));
+        }
+        self.verify(user_id, &dto.code).await?;
+
+        let (codes, hashes) = generate_recovery_codes();
+        self.two_factor.replace_recovery_codes(user_id, &hashes).await?;
+
+        Ok(RecoveryCodes { recovery_codes: codes })
+    }
+
+    /// Accepts a current TOTP code or an unused recovery code, each only once.
+    pub async fn verify(&self, user_id: Uuid, code: &str) -> Result<(), AppError> {
+        let invalid = || AppError::Unauthorized("Invalid two-factor code".to_string());
+
+        let secret = self
+            .enabled(user_id)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Two-factor authentication is not enabled".to_string()))?;
+
+        let accepted = if is_totp_code(code) {
+            match matching_step(&secret, code)? {
+                Some(step) => self.two_factor.use_step(user_id, step).await?,
+                None => false,
+            }
+        } else {
+            self.two_factor
+                .use_recovery_code(user_id, &hash_recovery_code(code))
+                .await?
+        };
+
+        if accepted {
+            Ok(())
+        } else {
+            Err(invalid())
+        }
+    }
+
+    async fn enabled(&self, user_id: Uuid) -> Result<Option<TotpSecret>, AppError> {
+        Ok(self
+            .two_factor
+            .find(user_id)
+            .await?
+            .filter(|secret| secret.enabled_at.is_some()))
+    }
+}
+
+fn totp(secret: Vec<u8>, account_name: &str) -> Result<TOTP, AppError> {
+    TOTP::new(
+        Algorithm::SHA1,
+        DIGITS,
+        SKEW_STEPS as u8,
+        STEP_SECS,
+        secret,
+        Some(ISSUER.to_string()),
+        account_name.to_string(),
+    )
+    .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// The time step `code` was generated for, if it is within the allowed skew.
+fn matching_step(secret: &TotpSecret, code: &str) -> Result<Option<i64>, AppError> {
+    let bytes = Secret::Encoded(secret.secret.clone())
+        .to_bytes()
+        .map_err(|_| AppError::Internal("Stored TOTP secret is not base32".to_string()))?;
+    let totp = totp(bytes, "")?;
+
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    let current = Utc::now().timestamp() as u64 / STEP_SECS;
+
+    Ok((current.saturating_sub(SKEW_STEPS)..=current + SKEW_STEPS)
+        .find(|step| totp.generate(step * STEP_SECS) == code)
+        .map(|step| step as i64))
+}
+
+fn is_totp_code(code: &str) -> bool {
+    let digits: Vec<char> = code.chars().filter(|c| !c.is_whitespace()).collect();
+    digits.len() == DIGITS && digits.iter().all(char::is_ascii_digit)
+}
+
+/// Fresh recovery codes, formatted for display, and the hashes to store.
+fn generate_recovery_codes() -> (Vec<String>, Vec<String>) {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code: String = (0..RECOVERY_CODE_LEN)
+                .map(|_| {
+                    let index = OsRng.next_u32() as usize % RECOVERY_CODE_ALPHABET.len();
+                    RECOVERY_CODE_ALPHABET[index] as char
+                })
+                .collect();
+            let hash = hash_recovery_code(&code);
+            let (head, tail) = code.split_at(RECOVERY_CODE_LEN / 2);
+
+            (format!("{}-{}", head, tail), hash)
+        })
+        .unzip()
+}
+
+/// Recovery codes are random enough that a fast hash suffices. Case, spaces
+/// and dashes are ignored.
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    auth::jwt::Claims,
+    error::{is_unique_violation, AppError},
+    models::users::{CreateUserDto, LoginDto, UpdateUserDto, User, UserRole},
+    repositories::{UserChanges, UserRepository},
+    services::Change,
+};
+
+pub struct UserService {
+    users: Arc<dyn UserRepository>,
+}
+
+impl UserService {
+    pub fn new(users: Arc<dyn UserRepository>) -> Self {
+        Self { users }
+    }
+
+    /// Creates an account and returns its id.
+    pub async fn register(&self, dto: &CreateUserDto) -> Result<Uuid, AppError> {
+        dto.validate()?;
+
+        let password_hash = User::hash_password(&dto.password)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        match self.users.create(&dto.email, &password_hash, &dto.name).await {
+            Ok(id) => Ok(id),
+            Err(e) if is_unique_violation(&e) => Err(AppError::Conflict("Email already exists".to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Checks a login attempt, refusing suspended accounts.
+    pub async fn authenticate(&self, dto: &LoginDto) -> Result<User, AppError> {
+        dto.validate()?;
+
+        let user = match self.users.find_by_email(&dto.email).await? {
+            Some(user)
+                if user
+                    .password_hash
+                    .as_deref()
+                    .is_some_and(|hash| User::verify_password(&dto.password, hash).unwrap_or(false)) =>
+            {
+                user
+            }
+            _ => return Err(AppError::Unauthorized("Invalid credentials".to_string())),
+        };
+
+        if self.users.is_suspended(user.id).await? {
+            return Err(AppError::Forbidden("Account suspended".to_string()));
+        }
+
+        Ok(user)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<User, AppError> {
+        self.users.find_by_id(id).await?.ok_or_else(user_not_found)
+    }
+
+    pub async fn update_profile(&self, id: Uuid, dto: &UpdateUserDto) -> Result<Change<User>, AppError> {
+        dto.validate()?;
+
+        let before = self.users.find_by_id(id).await?.ok_or_else(user_not_found)?;
+
+        let password_hash = dto
+            .password
+            .as_deref()
+            .map(User::hash_password)
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let changes = UserChanges {
+            email: dto.email.clone(),
+            password_hash,
+            name: dto.name.clone(),
+        };
+
+        let after = match self.users.update(id, &changes).await {
+            Ok(user) => user.ok_or_else(user_not_found)?,
+            Err(e) if is_unique_violation(&e) => return Err(AppError::Conflict("Email already exists".to_string())),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Change { before, after })
+    }
+
+    /// Loads the user behind a validated token.
+    pub async fn current_user(&self, claims: &Claims) -> Result<User, AppError> {
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+        self.users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid token".to_string()))
+    }
+
+    /// Refuses a validly signed token whose account has since been deleted or
+    /// suspended, or whose tokens were revoked after it was issued.
+    pub async fn verify_session(&self, claims: &Claims) -> Result<(), AppError> {
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+        let state = self
+            .users
+            .session_state(user_id)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid token".to_string()))?;
+
+        if state.suspended_at.is_some() {
+            return Err(AppError::Forbidden("Account suspended".to_string()));
+        }
+
+        let revoked = state
+            .tokens_revoked_at
+            .map(|revoked_at| claims.iat <= revoked_at.timestamp())
+            .unwrap_or(false);
+        if revoked {
+            return Err(AppError::Unauthorized("Token revoked".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Like [`current_user`](Self::current_user), but answers `403 Forbidden` for anyone but admins.
+    pub async fn require_admin(&self, claims: &Claims) -> Result<User, AppError> {
+        let user = self.current_user(claims).await?;
+
+        if user.role != UserRole::Admin {
+            return Err(AppError::Forbidden("Admin access required".to_string()));
+        }
+
+        Ok(user)
+    }
+
+    pub async fn purge_deleted(&self, cutoff: DateTime<Utc>) -> Result<u64, AppError> {
+        Ok(self.users.purge_deleted(cutoff).await?)
+    }
+}
+
+fn user_not_found() -> AppError {
+    AppError::NotFound("User not found".to_string())
+}
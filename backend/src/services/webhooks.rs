@@ -0,0 +1,408 @@
+use chrono::{Duration, Utc};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect, Client, Url,
+};
+use ring::hmac;
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration as StdDuration, Instant},
+};
+use thiserror::Error;
+use uuid::Uuid;
+use validator::Validate;
+use crate::{
+    auth::oidc::random_token,
+    config::WebhookConfig,
+    error::AppError,
+    models::{
+        users::User,
+        webhooks::{
+            AttemptOutcome, CreateWebhookDto, CreatedWebhookEndpoint, DeliveryStatus, DueDelivery,
+            NewWebhookEndpoint, WebhookDelivery, WebhookDeliveryDetails, WebhookEndpoint,
+        },
+    },
+    repositories::{CompanyRepository, WebhookRepository},
+};
+
+/// `t=<unix time>,v1=<hex HMAC-SHA256 of "<t>.<body>">`. Binding the time in
+/// lets receivers reject old payloads replayed at them.
+pub const SIGNATURE_HEADER: &str = "X-CareerHub-Signature";
+pub const EVENT_HEADER: &str = "X-CareerHub-Event";
+/// Stays the same across retries and redeliveries, for receivers to deduplicate on.
+pub const DELIVERY_HEADER: &str = "X-CareerHub-Delivery";
+
+/// Deliveries listed per endpoint.
+const DELIVERY_HISTORY: i64 = 100;
+
+pub struct WebhookService {
+    webhooks: Arc<dyn WebhookRepository>,
+    companies: Arc<dyn CompanyRepository>,
+    http: Client,
+    destinations: Arc<Destinations>,
+    config: WebhookConfig,
+}
+
+impl WebhookService {
+    pub fn new(config: &WebhookConfig, webhooks: Arc<dyn WebhookRepository>, companies: Arc<dyn CompanyRepository>) -> Self {
+        let destinations = Arc::new(Destinations {
+            allowed_hosts: config.allowed_hosts.iter().map(|host| host.to_ascii_lowercase()).collect(),
+        });
+
+        // Redirects are not followed, so an endpoint cannot bounce deliveries
+        // elsewhere, and names resolve through `destinations`, so the address
+        // connected to is one that was checked.
+        let http = Client::builder()
+            .timeout(StdDuration::from_secs(config.timeout_secs))
+            .redirect(redirect::Policy::none())
+            .dns_resolver(destinations.clone())
+            .build()
+            .expect("HTTP client configuration is valid");
+
+        Self {
+            webhooks,
+            companies,
+            http,
+            destinations,
+            config: config.clone(),
+        }
+    }
+
+    pub async fn list_endpoints(&self, user: &User, company_id: Uuid) -> Result<Vec<WebhookEndpoint>, AppError> {
+        self.require_manager(user, company_id).await?;
+
+        Ok(self.webhooks.list_endpoints(company_id).await?)
+    }
+
+    /// Registers an endpoint with a fresh signing secret.
+    pub async fn create_endpoint(
+        &self,
+        user: &User,
+        company_id: Uuid,
+        dto: &CreateWebhookDto,
+    ) -> Result<CreatedWebhookEndpoint, AppError> {
+        dto.validate()?;
+        self.require_manager(user, company_id).await?;
+
+        self.destinations
+            .check(&dto.url)
+            .await
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+        let mut events = Vec::with_capacity(dto.events.len());
+        for event in &dto.events {
+            if !events.contains(event) {
+                events.push(*event);
+            }
+        }
+
+        let secret = format!("whsec_{}", random_token());
+        let endpoint = self
+            .webhooks
+            .create_endpoint(&NewWebhookEndpoint {
+                company_id,
+                created_by: user.id,
+                url: dto.url.clone(),
+                secret: secret.clone(),
+                events,
+            })
+            .await?;
+
+        Ok(CreatedWebhookEndpoint { endpoint, secret })
+    }
+
+    /// Deletes an endpoint and its pending deliveries, returning it as it was.
+    pub async fn delete_endpoint(&self, user: &User, company_id: Uuid, id: Uuid) -> Result<WebhookEndpoint, AppError> {
+        let endpoint = self.endpoint(user, company_id, id).await?;
+
+        self.webhooks.delete_endpoint(company_id, id).await?;
+
+        Ok(endpoint)
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        user: &User,
+        company_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        self.endpoint(user, company_id, endpoint_id).await?;
+
+        Ok(self.webhooks.list_deliveries(endpoint_id, DELIVERY_HISTORY).await?)
+    }
+
+    /// A delivery together with every attempt made for it.
+    pub async fn get_delivery(
+        &self,
+        user: &User,
+        company_id: Uuid,
+        endpoint_id: Uuid,
+        id: Uuid,
+    ) -> Result<WebhookDeliveryDetails, AppError> {
+        self.endpoint(user, company_id, endpoint_id).await?;
+
+        let delivery = self
+            .webhooks
+            .find_delivery(endpoint_id, id)
+            .await?
+            .ok_or_else(delivery_not_found)?;
+        let attempt_log = self.webhooks.list_attempts(id).await?;
+
+        Ok(WebhookDeliveryDetails { delivery, attempt_log })
+    }
+
+    /// Queues a delivery to be sent again as soon as possible, with a fresh
+    /// set of attempts.
+    pub async fn redeliver(
+        &self,
+        user: &User,
+        company_id: Uuid,
+        endpoint_id: Uuid,
+        id: Uuid,
+    ) -> Result<WebhookDelivery, AppError> {
+        self.endpoint(user, company_id, endpoint_id).await?;
+
+        self.webhooks
+            .redeliver(endpoint_id, id)
+            .await?
+            .ok_or_else(delivery_not_found)
+    }
+
+    /// Attempts every delivery that is due, up to the batch size, and
+    /// returns how many were attempted.
+    pub async fn deliver_due(&self) -> Result<usize, AppError> {
+        // Long enough for every attempt in the batch to finish, since they
+        // run concurrently.
+        let lease_until = Utc::now() + Duration::seconds(2 * self.config.timeout_secs as i64);
+        let due = self.webhooks.claim_due(self.config.batch_size, lease_until).await?;
+        let count = due.len();
+
+        futures::future::join_all(due.into_iter().map(|delivery| self.attempt(delivery))).await;
+
+        Ok(count)
+    }
+
+    async fn attempt(&self, delivery: DueDelivery) {
+        let body = delivery.payload.to_string();
+        let timestamp = Utc::now().timestamp();
+
+        let started = Instant::now();
+        // Checked again because what a name resolves to may have changed since
+        // the endpoint was registered.
+        let result = match self.destinations.check(&delivery.url).await {
+            Ok(()) => self
+                .http
+                .post(&delivery.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, sign(&delivery.secret, timestamp, &body))
+                .header(EVENT_HEADER, &delivery.event)
+                .header(DELIVERY_HEADER, delivery.id.to_string())
+                .body(body)
+                .send()
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let duration_ms = started.elapsed().as_millis().min(i32::MAX as u128) as i32;
+
+        // Only the status is kept: the response body is the receiver's, and
+        // may not be meant for whoever reads the delivery log.
+        let (response_status, error) = match result {
+            Ok(response) if response.status().is_success() => (Some(response.status().as_u16() as i32), None),
+            Ok(response) => (Some(response.status().as_u16() as i32), Some(format!("HTTP {}", response.status()))),
+            Err(e) => (None, Some(e)),
+        };
+
+        let attempts = delivery.attempts + 1;
+        let now = Utc::now();
+        let (status, next_attempt_at) = if error.is_none() {
+            (DeliveryStatus::Delivered, now)
+        } else if attempts >= self.config.max_attempts {
+            (DeliveryStatus::Failed, now)
+        } else {
+            (DeliveryStatus::Pending, now + self.backoff(attempts))
+        };
+
+        let outcome = AttemptOutcome {
+            response_status,
+            error,
+            duration_ms,
+            status,
+            next_attempt_at,
+        };
+        if let Err(e) = self.webhooks.record_attempt(delivery.id, &outcome).await {
+            tracing::error!(error = %e, "Failed to record webhook attempt for delivery {}", delivery.id);
+        }
+    }
+
+    /// Delay after the `attempts`-th failed attempt.
+    fn backoff(&self, attempts: i32) -> Duration {
+        let exponent = (attempts - 1).clamp(0, 30) as u32;
+        let secs = self
+            .config
+            .backoff_base_secs
+            .saturating_mul(1i64 << exponent)
+            .min(self.config.backoff_max_secs);
+
+        Duration::seconds(secs)
+    }
+
+    async fn endpoint(&self, user: &User, company_id: Uuid, id: Uuid) -> Result<WebhookEndpoint, AppError> {
+        self.require_manager(user, company_id).await?;
+
+        self.webhooks
+            .find_endpoint(company_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Webhook not found".to_string()))
+    }
+
+    async fn require_manager(&self, user: &User, company_id: Uuid) -> Result<(), AppError> {
+        if self.companies.find_by_id(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Company not found".to_string()));
+        }
+
+        if !user.can_manage_company(company_id) {
+            return Err(AppError::Forbidden("Not a recruiter for this company".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// The [`SIGNATURE_HEADER`] value for `body` sent at `timestamp`.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, format!("{}.{}", timestamp, body).as_bytes());
+    let hex: String = tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+
+    format!("t={},v1={}", timestamp, hex)
+}
+
+#[derive(Error, Debug)]
+pub enum DestinationError {
+    #[error("url must be an http:// or https:// URL")]
+    InvalidUrl,
+
+    #[error("{0} could not be resolved")]
+    Unresolved(String),
+
+    #[error("{0} is not a public address")]
+    NotPublic(String),
+}
+
+/// Where deliveries may be sent: public addresses, plus the configured
+/// `allowed_hosts`. Also the client's resolver, so a name that starts
+/// resolving somewhere private is refused at connect time too.
+struct Destinations {
+    allowed_hosts: Vec<String>,
+}
+
+impl Destinations {
+    async fn check(&self, url: &str) -> Result<(), DestinationError> {
+        let url = Url::parse(url).map_err(|_| DestinationError::InvalidUrl)?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(DestinationError::InvalidUrl);
+        }
+
+        let host = url.host_str().ok_or(DestinationError::InvalidUrl)?;
+        if self.allows(host) {
+            return Ok(());
+        }
+
+        match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            Ok(ip) if is_public(ip) => Ok(()),
+            Ok(_) => Err(DestinationError::NotPublic(host.to_string())),
+            Err(_) => self.resolve_public(host).await.map(|_| ()),
+        }
+    }
+
+    /// Every address `host` resolves to, as long as they are all public.
+    async fn resolve_public(&self, host: &str) -> Result<Vec<SocketAddr>, DestinationError> {
+        let unresolved = || DestinationError::Unresolved(host.to_string());
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await.map_err(|_| unresolved())?.collect();
+
+        if addrs.is_empty() {
+            return Err(unresolved());
+        }
+        if !self.allows(host) && !addrs.iter().all(|addr| is_public(addr.ip())) {
+            return Err(DestinationError::NotPublic(host.to_string()));
+        }
+
+        Ok(addrs)
+    }
+
+    fn allows(&self, host: &str) -> bool {
+        self.allowed_hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+}
+
+impl Resolve for Destinations {
+    fn resolve(&self, name: Name) -> Resolving {
+        let destinations = Destinations {
+            allowed_hosts: self.allowed_hosts.clone(),
+        };
+
+        Box::pin(async move {
+            let addrs = destinations
+                .resolve_public(name.as_str())
+                .await
+                .map_err(|e| io::Error::new(io::ErrorKind::PermissionDenied, e.to_string()))?;
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether `ip` is reachable on the public internet, rather than this host,
+/// its networks or a range set aside for something else.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public_v4(v4);
+            }
+
+            let segments = ip.segments();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                // Unique local, fc00::/7.
+                || segments[0] & 0xfe00 == 0xfc00
+                // Link local and the deprecated site local, fe80::/10 and fec0::/10.
+                || segments[0] & 0xff80 == 0xfe80
+                // Documentation, 2001:db8::/32.
+                || (segments[0], segments[1]) == (0x2001, 0x0db8)
+                // IPv4-compatible and NAT64 addresses, which can point anywhere in IPv4.
+                || ip.to_ipv4().is_some()
+                || segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0])
+        }
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network", 0.0.0.0/8.
+        || a == 0
+        // Carrier-grade NAT, 100.64.0.0/10.
+        || (a == 100 && b & 0xc0 == 64)
+        // Protocol assignments and benchmarking, 192.0.0.0/24 and 198.18.0.0/15.
+        || ip.octets()[..3] == [192, 0, 0]
+        || (a == 198 && b & 0xfe == 18)
+        // Reserved, 240.0.0.0/4.
+        || a >= 240)
+}
+
+fn delivery_not_found() -> AppError {
+    AppError::NotFound("Delivery not found".to_string())
+}
@@ -0,0 +1,240 @@
+//! Logging and tracing. Everything is recorded through `tracing`: events are
+//! printed as text in development and as JSON in production, and spans can
+//! additionally be exported to an OpenTelemetry collector over OTLP.
+//!
+//! Each request runs inside an `http_request` span opened by
+//! [`RequestTracing`], carrying the request id and, once authenticated, the
+//! user id or API key id, so every event logged while handling it can be
+//! attributed.
+//! Repository calls open `db.*` spans beneath it.
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::HeaderMap,
+    Error, HttpMessage,
+};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use opentelemetry::{global, propagation::Extractor, trace::TracerProvider as _};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{propagation::TraceContextPropagator, trace::SdkTracerProvider, Resource};
+use std::{rc::Rc, time::Instant};
+use thiserror::Error;
+use tracing::{field::Empty, Instrument, Span, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{
+    fmt::MakeWriter, layer::SubscriberExt, registry::LookupSpan, util::SubscriberInitExt, EnvFilter, Layer,
+};
+use crate::{
+    config::{Config, LogFormat},
+    request_id::RequestId,
+};
+
+/// Path collectors serve OTLP/HTTP trace exports on.
+const OTLP_TRACES_PATH: &str = "/v1/traces";
+
+#[derive(Error, Debug)]
+pub enum TelemetryError {
+    #[error("invalid log filter: {0}")]
+    Filter(#[from] tracing_subscriber::filter::ParseError),
+
+    #[error("could not create the OTLP exporter: {0}")]
+    Exporter(#[from] opentelemetry_otlp::ExporterBuildError),
+
+    #[error("a global subscriber is already installed: {0}")]
+    Init(#[from] tracing_subscriber::util::TryInitError),
+}
+
+/// Keeps the span exporter alive; call [`Telemetry::shutdown`] before exiting
+/// so buffered spans are flushed.
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Telemetry {
+    pub fn shutdown(self) {
+        if let Some(provider) = self.provider {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush spans: {}", e);
+            }
+        }
+    }
+}
+
+/// Installs the global subscriber described by `config`. Records from crates
+/// that still use `log` are forwarded to it.
+pub fn init(config: &Config) -> Result<Telemetry, TelemetryError> {
+    let settings = &config.telemetry;
+
+    let filter = match std::env::var("RUST_LOG") {
+        Ok(directives) if !directives.is_empty() => EnvFilter::try_new(directives)?,
+        _ => EnvFilter::try_new(&settings.log_filter)?,
+    };
+
+    let provider = settings
+        .otlp_endpoint
+        .as_deref()
+        .map(|endpoint| tracer_provider(endpoint, &settings.service_name))
+        .transpose()?;
+    let otel = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+    });
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer(settings.log_format(config.environment), std::io::stdout))
+        .with(otel)
+        .try_init()?;
+
+    Ok(Telemetry { provider })
+}
+
+/// The layer that prints events to `writer`, as human-readable lines or as one
+/// JSON object per line listing the spans the event happened in.
+pub fn fmt_layer<S, W>(format: LogFormat, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer);
+
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Json => layer
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .boxed(),
+    }
+}
+
+fn tracer_provider(endpoint: &str, service_name: &str) -> Result<SdkTracerProvider, TelemetryError> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}{}", endpoint.trim_end_matches('/'), OTLP_TRACES_PATH))
+        .build()?;
+
+    // Continue traces started by callers that send a W3C `traceparent` header.
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name.to_string()).build())
+        .build())
+}
+
+/// Records the authenticated user on the current request's span.
+pub fn record_user(user_id: &str) {
+    Span::current().record("user_id", user_id);
+}
+
+/// Records the API key a request authenticated with on its span.
+pub fn record_api_key(api_key_id: &str) {
+    Span::current().record("api_key_id", api_key_id);
+}
+
+/// Runs each request inside an `http_request` span and logs its outcome.
+/// Must be wrapped inside [`RequestIdMiddleware`](crate::request_id::RequestIdMiddleware)
+/// so the request id is known.
+pub struct RequestTracing;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTracing
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestTracingService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestTracingService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestTracingService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTracingService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let request_id = req.extensions().get::<RequestId>().map(|id| id.0.clone());
+
+        let span = tracing::info_span!(
+            "http_request",
+            otel.name = %req.method(),
+            otel.kind = "server",
+            http.request.method = %req.method(),
+            url.path = %req.path(),
+            http.route = Empty,
+            http.response.status_code = Empty,
+            request_id = request_id.as_deref().unwrap_or("-"),
+            user_id = Empty,
+            api_key_id = Empty,
+        );
+        let parent = global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(req.headers())));
+        // Only fails when the span is disabled or already has a parent.
+        let _ = span.set_parent(parent);
+
+        let started = Instant::now();
+
+        Box::pin(
+            async move {
+                let result = service.call(req).await;
+                let latency_ms = started.elapsed().as_millis() as u64;
+                let span = Span::current();
+
+                let status = match &result {
+                    Ok(res) => {
+                        if let Some(route) = res.request().match_pattern() {
+                            span.record("otel.name", format!("{} {}", res.request().method(), route).as_str());
+                            span.record("http.route", route.as_str());
+                        }
+                        res.status()
+                    }
+                    Err(e) => e.as_response_error().status_code(),
+                };
+                span.record("http.response.status_code", status.as_u16());
+
+                if status.is_server_error() {
+                    tracing::error!(status = status.as_u16(), latency_ms, "Request failed");
+                } else {
+                    tracing::info!(status = status.as_u16(), latency_ms, "Request completed");
+                }
+
+                result
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Reads trace context propagation headers from an actix request.
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
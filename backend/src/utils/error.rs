@@ -1,63 +0,0 @@
-use axum::{
-    http::StatusCode,
-    response::{IntoResponse, Response},
-    Json,
-};
-use serde_json::json;
-use thiserror::Error;
-use sqlx::migrate::MigrateError;
-
-#[derive(Error, Debug)]
-pub enum AppError {
-    #[error("Authentication error: {0}")]
-    Auth(String),
-
-    #[error("Authorization error: {0}")]
-    Forbidden(String),
-
-    #[error("Database error: {0}")]
-    Database(#[from] sqlx::Error),
-
-    #[error("Validation error: {0}")]
-    Validation(String),
-
-    #[error("Not found: {0}")]
-    NotFound(String),
-
-    #[error("Internal server error: {0}")]
-    Internal(String),
-
-    #[error("Not implemented: {0}")]
-    NotImplemented(String),
-}
-
-impl From<MigrateError> for AppError {
-    fn from(err: MigrateError) -> Self {
-        AppError::Database(sqlx::Error::from(err))
-    }
-}
-
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            AppError::Auth(msg) => (StatusCode::UNAUTHORIZED, msg),
-            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
-            AppError::Database(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Database error occurred".to_string(),
-            ),
-            AppError::Validation(msg) => (StatusCode::BAD_REQUEST, msg),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
-            AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-            AppError::NotImplemented(msg) => (StatusCode::NOT_IMPLEMENTED, msg),
-        };
-
-        let body = json!({
-            "error": error_message
-        });
-
-        (status, Json(body)).into_response()
-    }
-}
-
-pub type Result<T> = std::result::Result<T, AppError>; 
\ No newline at end of file
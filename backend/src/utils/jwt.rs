@@ -1,48 +0,0 @@
-use jsonwebtoken::{encode, decode, Header, EncodingKey, DecodingKey, Validation, errors::Error as JwtError};
-use serde::{Deserialize, Serialize};
-use chrono::{Utc, Duration};
-use uuid::Uuid;
-
-use crate::{
-    models::auth::{AuthUser, UserRole},
-    utils::{AppError, Result},
-};
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Claims {
-    pub sub: Uuid,
-    pub email: String,
-    pub role: UserRole,
-    pub exp: i64,
-}
-
-pub fn create_token(user: &AuthUser, secret: &str) -> Result<String> {
-    let expiration = Utc::now()
-        .checked_add_signed(Duration::hours(24))
-        .expect("valid timestamp")
-        .timestamp();
-
-    let claims = Claims {
-        sub: user.id,
-        email: user.email.clone(),
-        role: user.role.clone(),
-        exp: expiration,
-    };
-
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
-    .map_err(|_| AppError::Internal("Failed to create token".into()))
-}
-
-pub fn validate_token(token: &str, secret: &str) -> Result<Claims> {
-    decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_bytes()),
-        &Validation::default(),
-    )
-    .map(|data| data.claims)
-    .map_err(|_| AppError::Auth("Invalid token".into()))
-} 
\ No newline at end of file
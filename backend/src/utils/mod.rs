@@ -1,6 +0,0 @@
-pub mod jwt;
-pub mod password;
-pub mod error;
-
-pub use error::AppError;
-pub type Result<T> = std::result::Result<T, AppError>; 
\ No newline at end of file
@@ -0,0 +1,105 @@
+//! Versions of the HTTP API.
+//!
+//! Every version is served under its own prefix, `/api/v1`, `/api/v2`, ...,
+//! from the same route scopes. A scope whose routes changed in a version takes
+//! the [`ApiVersion`] and registers that version's handlers in place of the
+//! older ones, so versions run side by side and share everything else. The
+//! unversioned `/api` routes predate versioning and stay as a deprecated alias
+//! of v1.
+
+use actix_web::{
+    http::header::{HttpDate, LINK},
+    middleware::{Condition, DefaultHeaders},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fmt, time::SystemTime};
+use crate::config::{ApiConfig, DeprecationConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiVersion {
+    V1,
+    /// Pages `GET /jobs` with the total number of matches.
+    V2,
+}
+
+impl ApiVersion {
+    pub const ALL: [ApiVersion; 2] = [ApiVersion::V1, ApiVersion::V2];
+    pub const LATEST: ApiVersion = ApiVersion::V2;
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+
+    /// Where the version is served, e.g. `/api/v1`.
+    pub fn prefix(&self) -> String {
+        format!("/api/{}", self.as_str())
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// When the unversioned `/api` routes were superseded by `/api/v1`
+/// (2026-10-19T00:00:00Z).
+const LEGACY_DEPRECATED_AT: i64 = 1_792_368_000;
+
+/// Marks every response as deprecated (RFC 9745), says when the routes stop
+/// being served (RFC 8594) and links to their replacement.
+#[derive(Debug, Clone)]
+pub struct Deprecation {
+    pub deprecated_at: DateTime<Utc>,
+    pub sunset_at: Option<DateTime<Utc>>,
+    /// Path prefix of the routes to move to.
+    pub successor: String,
+}
+
+impl Deprecation {
+    /// The schedule configured for `version`, if any.
+    pub fn of_version(config: &ApiConfig, version: ApiVersion) -> Option<Self> {
+        config
+            .deprecations
+            .get(&version)
+            .map(|DeprecationConfig { deprecated_at, sunset_at }| Self {
+                deprecated_at: *deprecated_at,
+                sunset_at: *sunset_at,
+                successor: ApiVersion::LATEST.prefix(),
+            })
+    }
+
+    /// The unversioned `/api` routes, always deprecated in favour of v1.
+    pub fn of_legacy(config: &ApiConfig) -> Self {
+        Self {
+            deprecated_at: DateTime::from_timestamp(LEGACY_DEPRECATED_AT, 0).expect("valid timestamp"),
+            sunset_at: config.legacy_sunset_at,
+            successor: ApiVersion::V1.prefix(),
+        }
+    }
+
+    fn headers(&self) -> DefaultHeaders {
+        let mut headers = DefaultHeaders::new()
+            .add(("Deprecation", format!("@{}", self.deprecated_at.timestamp())))
+            .add((LINK, format!("<{}>; rel=\"successor-version\"", self.successor)));
+
+        if let Some(sunset_at) = self.sunset_at {
+            headers = headers.add(("Sunset", HttpDate::from(SystemTime::from(sunset_at)).to_string()));
+        }
+
+        headers
+    }
+}
+
+/// Middleware adding the headers of `deprecation`, or nothing when it is `None`.
+pub fn deprecation_headers(deprecation: Option<&Deprecation>) -> Condition<DefaultHeaders> {
+    match deprecation {
+        Some(deprecation) => Condition::new(true, deprecation.headers()),
+        None => Condition::new(false, DefaultHeaders::new()),
+    }
+}
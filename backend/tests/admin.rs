@@ -0,0 +1,242 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{admin_token, bearer, create_company, create_job, init_app, login, register_and_login, send};
+
+#[sqlx::test]
+async fn taken_down_jobs_stay_down_until_restored(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+    let job_uri = format!("/api/jobs/{}", job["id"].as_str().unwrap());
+    let page_uri = format!("/api/public/companies/{}", company["slug"].as_str().unwrap());
+
+    let (recruiter_id, recruiter) = register_and_login(&app, "recruiter@example.com").await;
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/admin/users/{}/role", recruiter_id))
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "role": "Recruiter", "company_id": company["id"] }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/admin/jobs/{}/takedown", job["id"].as_str().unwrap()))
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "reason": "Misleading salary" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NO_CONTENT);
+
+    // The company cannot bring it back...
+    let req = test::TestRequest::put()
+        .uri(&job_uri)
+        .insert_header(bearer(&recruiter))
+        .set_json(json!({ "is_active": true }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+    let req = test::TestRequest::delete().uri(&job_uri).insert_header(bearer(&recruiter)).to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NO_CONTENT);
+    let deleted: bool = sqlx::query_scalar("SELECT deleted_at IS NOT NULL FROM jobs WHERE id = $1::uuid")
+        .bind(job["id"].as_str().unwrap())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(!deleted);
+
+    // ...nobody can apply to it, and the company page leaves it out.
+    let (_, candidate) = register_and_login(&app, "candidate@example.com").await;
+    let application = json!({ "job_id": job["id"], "resume_url": "https://example.com/cv.pdf" });
+    let req = test::TestRequest::post()
+        .uri("/api/applications")
+        .insert_header(bearer(&candidate))
+        .set_json(&application)
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+
+    let (status, page) = send(&app, test::TestRequest::get().uri(&page_uri).to_request()).await;
+    assert_eq!(status, StatusCode::OK, "{}", page);
+    assert_eq!(page["job_count"], 0);
+    assert_eq!(page["open_jobs"], json!([]));
+
+    // Until an admin restores it.
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/admin/jobs/{}/restore", job["id"].as_str().unwrap()))
+        .insert_header(bearer(&admin))
+        .to_request();
+    let (status, restored) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", restored);
+    assert_eq!(restored["is_active"], false);
+
+    // Then the company may reopen it.
+    let req = test::TestRequest::put()
+        .uri(&job_uri)
+        .insert_header(bearer(&recruiter))
+        .set_json(json!({ "is_active": true }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+    let (_, page) = send(&app, test::TestRequest::get().uri(&page_uri).to_request()).await;
+    assert_eq!(page["job_count"], 1);
+    let req = test::TestRequest::post()
+        .uri("/api/applications")
+        .insert_header(bearer(&candidate))
+        .set_json(&application)
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::CREATED);
+}
+
+#[sqlx::test]
+async fn stats_cover_at_most_a_year(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let (_, user) = register_and_login(&app, "user@example.com").await;
+
+    let stats = |token: &str, query: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/api/admin/stats{}", query))
+            .insert_header(bearer(token))
+            .to_request()
+    };
+
+    assert_eq!(send(&app, stats(&user, "")).await.0, StatusCode::FORBIDDEN);
+
+    let (status, body) = send(&app, stats(&admin, "")).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    // A leap year's 366 days are the most one request may span.
+    let (status, body) = send(&app, stats(&admin, "?from=2024-01-01&to=2024-12-31")).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = send(&app, stats(&admin, "?from=2024-01-01&to=2025-01-01")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["message"], "Date range cannot exceed 366 days");
+
+    let (status, body) = send(&app, stats(&admin, "?from=2024-02-01&to=2024-01-01")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["message"], "`from` must not be after `to`");
+}
+
+#[sqlx::test]
+async fn suspending_and_revoking_cut_off_existing_tokens(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let (user_id, token) = register_and_login(&app, "user@example.com").await;
+
+    let admin_action = |action: &str, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/api/admin/users/{}/{}", user_id, action))
+            .insert_header(bearer(&admin))
+            .set_json(body)
+            .to_request()
+    };
+    let profile = |token: &str| {
+        test::TestRequest::get()
+            .uri("/api/users/profile")
+            .insert_header(bearer(token))
+            .to_request()
+    };
+
+    let (status, body) = send(&app, admin_action("suspend", json!({ "reason": "Spam" }))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, body) = send(&app, profile(&token)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["message"], "Account suspended");
+
+    // Lifting the suspension lets the same token back in.
+    assert_eq!(send(&app, admin_action("unsuspend", json!({}))).await.0, StatusCode::OK);
+    assert_eq!(send(&app, profile(&token)).await.0, StatusCode::OK);
+
+    assert_eq!(send(&app, admin_action("revoke-tokens", json!({}))).await.0, StatusCode::NO_CONTENT);
+    let (status, body) = send(&app, profile(&token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["message"], "Token revoked");
+
+    // Tokens are compared by the second they were issued in.
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let fresh = login(&app, "user@example.com").await;
+    assert_eq!(send(&app, profile(&fresh)).await.0, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn soft_deleted_jobs_and_companies_can_be_restored(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let company_uri = format!("/api/companies/{}", company["id"].as_str().unwrap());
+    let kept = create_job(&app, &admin, &company["id"]).await;
+    let dropped = create_job(&app, &admin, &company["id"]).await;
+
+    let get = |uri: &str| test::TestRequest::get().uri(uri).insert_header(bearer(&admin)).to_request();
+    let delete = |uri: &str| test::TestRequest::delete().uri(uri).insert_header(bearer(&admin)).to_request();
+    let restore = |uri: &str| test::TestRequest::post().uri(uri).insert_header(bearer(&admin)).to_request();
+    let job_uri = |job: &serde_json::Value| format!("/api/jobs/{}", job["id"].as_str().unwrap());
+    let restore_job_uri = |job: &serde_json::Value| format!("/api/admin/jobs/{}/restore", job["id"].as_str().unwrap());
+    let restore_company_uri = format!("/api/admin/companies/{}/restore", company["id"].as_str().unwrap());
+
+    // A job deleted on its own comes back on its own.
+    assert_eq!(send(&app, delete(&job_uri(&dropped))).await.0, StatusCode::NO_CONTENT);
+    assert_eq!(send(&app, get(&job_uri(&dropped))).await.0, StatusCode::NOT_FOUND);
+    let (status, restored) = send(&app, restore(&restore_job_uri(&dropped))).await;
+    assert_eq!(status, StatusCode::OK, "{}", restored);
+    assert_eq!(restored["id"], dropped["id"]);
+    assert_eq!(send(&app, get(&job_uri(&dropped))).await.0, StatusCode::OK);
+    assert_eq!(send(&app, restore(&restore_job_uri(&dropped))).await.0, StatusCode::NOT_FOUND);
+
+    // Deleted again before the company goes, it stays deleted when the company returns.
+    assert_eq!(send(&app, delete(&job_uri(&dropped))).await.0, StatusCode::NO_CONTENT);
+    assert_eq!(send(&app, delete(&company_uri)).await.0, StatusCode::NO_CONTENT);
+    assert_eq!(send(&app, get(&company_uri)).await.0, StatusCode::NOT_FOUND);
+    assert_eq!(send(&app, get(&job_uri(&kept))).await.0, StatusCode::NOT_FOUND);
+
+    // Jobs of a deleted company only come back with it.
+    assert_eq!(send(&app, restore(&restore_job_uri(&kept))).await.0, StatusCode::CONFLICT);
+
+    let (status, restored) = send(&app, restore(&restore_company_uri)).await;
+    assert_eq!(status, StatusCode::OK, "{}", restored);
+    assert_eq!(send(&app, get(&company_uri)).await.0, StatusCode::OK);
+    assert_eq!(send(&app, get(&job_uri(&kept))).await.0, StatusCode::OK);
+    assert_eq!(send(&app, get(&job_uri(&dropped))).await.0, StatusCode::NOT_FOUND);
+    assert_eq!(send(&app, restore(&restore_company_uri)).await.0, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn soft_deleted_users_can_be_restored(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let (user_id, token) = register_and_login(&app, "user@example.com").await;
+    let profile = |token: &str| {
+        test::TestRequest::get()
+            .uri("/api/users/profile")
+            .insert_header(bearer(token))
+            .to_request()
+    };
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/admin/users/{}", user_id))
+        .insert_header(bearer(&admin))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NO_CONTENT);
+    assert_eq!(send(&app, profile(&token)).await.0, StatusCode::UNAUTHORIZED);
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "user@example.com", "password": common::PASSWORD }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::UNAUTHORIZED);
+
+    let restore = || {
+        test::TestRequest::post()
+            .uri(&format!("/api/admin/users/{}/restore", user_id))
+            .insert_header(bearer(&admin))
+            .to_request()
+    };
+    let (status, restored) = send(&app, restore()).await;
+    assert_eq!(status, StatusCode::OK, "{}", restored);
+    assert_eq!(restored["id"], user_id.to_string());
+    assert_eq!(send(&app, restore()).await.0, StatusCode::NOT_FOUND);
+
+    assert_eq!(send(&app, profile(&token)).await.0, StatusCode::OK);
+    let fresh = login(&app, "user@example.com").await;
+    assert_eq!(send(&app, profile(&fresh)).await.0, StatusCode::OK);
+}
@@ -0,0 +1,273 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, Error,
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{
+    admin_token, bearer, create_company, create_job, init_app, job_payload, register_and_login, send,
+};
+
+/// Registers `email` as a recruiter for `company_id` and returns their token.
+async fn recruiter_token<S, B>(app: &S, pool: &PgPool, email: &str, company_id: &Value) -> String
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let (id, token) = register_and_login(app, email).await;
+
+    sqlx::query("UPDATE users SET role = 'recruiter', company_id = $2 WHERE id = $1")
+        .bind(id)
+        .bind(Uuid::parse_str(company_id.as_str().unwrap()).unwrap())
+        .execute(pool)
+        .await
+        .unwrap();
+
+    token
+}
+
+async fn create_key<S, B>(app: &S, token: &str, company_id: &Value, body: Value) -> (StatusCode, Value)
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/companies/{}/api-keys", company_id.as_str().unwrap()))
+        .insert_header(bearer(token))
+        .set_json(body)
+        .to_request();
+
+    send(app, req).await
+}
+
+#[sqlx::test]
+async fn recruiters_manage_their_companys_keys(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let recruiter = recruiter_token(&app, &pool, "recruiter@example.com", &company["id"]).await;
+
+    let (status, created) = create_key(
+        &app,
+        &recruiter,
+        &company["id"],
+        json!({ "name": "Job board", "scopes": ["jobs:write"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", created);
+    let key = created["key"].as_str().unwrap();
+    let prefix = created["prefix"].as_str().unwrap();
+    assert!(key.starts_with(&format!("chk_{}_", prefix)));
+    assert_eq!(created["scopes"], json!(["jobs:write"]));
+    assert!(created.get("key_hash").is_none());
+
+    let list_uri = format!("/api/companies/{}/api-keys", company["id"].as_str().unwrap());
+    let req = test::TestRequest::get().uri(&list_uri).insert_header(bearer(&recruiter)).to_request();
+    let (status, keys) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(keys.as_array().unwrap().len(), 1);
+    assert_eq!(keys[0]["prefix"], prefix);
+    assert!(keys[0].get("key").is_none());
+    assert!(keys[0].get("key_hash").is_none());
+
+    let (_, outsider) = register_and_login(&app, "outsider@example.com").await;
+    let req = test::TestRequest::get().uri(&list_uri).insert_header(bearer(&outsider)).to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+    let (status, _) = create_key(&app, &outsider, &company["id"], json!({ "name": "Mine", "scopes": ["jobs:write"] })).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Keys cannot mint more keys.
+    let (status, _) = create_key(&app, key, &company["id"], json!({ "name": "Child", "scopes": ["jobs:write"] })).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("{}/{}", list_uri, created["id"].as_str().unwrap()))
+        .insert_header(bearer(&recruiter))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NO_CONTENT);
+
+    let req = test::TestRequest::post()
+        .uri("/api/jobs")
+        .insert_header(bearer(key))
+        .set_json(job_payload(&company["id"]))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn keys_post_jobs_for_their_own_company(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let other = create_company(&app, &admin, "Globex").await;
+    let other_job = create_job(&app, &admin, &other["id"]).await;
+
+    let (_, created) = create_key(&app, &admin, &company["id"], json!({ "name": "ATS", "scopes": ["jobs:write"] })).await;
+    let key = created["key"].as_str().unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/jobs")
+        .insert_header(bearer(key))
+        .set_json(job_payload(&company["id"]))
+        .to_request();
+    let (status, job) = send(&app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", job);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/jobs/{}", job["id"].as_str().unwrap()))
+        .insert_header(bearer(key))
+        .set_json(json!({ "title": "Staff Engineer" }))
+        .to_request();
+    let (status, job) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", job);
+    assert_eq!(job["title"], "Staff Engineer");
+
+    let req = test::TestRequest::post()
+        .uri("/api/jobs")
+        .insert_header(bearer(key))
+        .set_json(job_payload(&other["id"]))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/jobs/{}", other_job["id"].as_str().unwrap()))
+        .insert_header(bearer(key))
+        .set_json(json!({ "title": "Hijacked" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+
+    // Only the endpoints made for keys accept them, and only with the right scope.
+    let req = test::TestRequest::get().uri("/api/users/profile").insert_header(bearer(key)).to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/companies/{}/applications", company["id"].as_str().unwrap()))
+        .insert_header(bearer(key))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+
+    let key_id = Uuid::parse_str(created["id"].as_str().unwrap()).unwrap();
+    let last_used_at: Option<chrono::DateTime<chrono::Utc>> =
+        sqlx::query_scalar("SELECT last_used_at FROM api_keys WHERE id = $1")
+            .bind(key_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(last_used_at.is_some());
+
+    let (actor_id, api_key_id): (Option<Uuid>, Option<Uuid>) =
+        sqlx::query_as("SELECT actor_id, api_key_id FROM audit_events WHERE entity_type = 'job' AND action = 'update'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(actor_id, None);
+    assert_eq!(api_key_id, Some(key_id));
+}
+
+#[sqlx::test]
+async fn keys_read_their_companys_applications(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let other = create_company(&app, &admin, "Globex").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+
+    let (_, candidate) = register_and_login(&app, "candidate@example.com").await;
+    let req = test::TestRequest::post()
+        .uri("/api/applications")
+        .insert_header(bearer(&candidate))
+        .set_json(json!({ "job_id": job["id"], "resume_url": "https://example.com/cv.pdf" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::CREATED);
+
+    let (_, created) = create_key(
+        &app,
+        &admin,
+        &company["id"],
+        json!({ "name": "ATS sync", "scopes": ["applications:read"] }),
+    )
+    .await;
+    let key = created["key"].as_str().unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/companies/{}/applications", company["id"].as_str().unwrap()))
+        .insert_header(bearer(key))
+        .to_request();
+    let (status, applications) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(applications.as_array().unwrap().len(), 1);
+    assert_eq!(applications[0]["job_id"], job["id"]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/companies/{}/applications", other["id"].as_str().unwrap()))
+        .insert_header(bearer(key))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+
+    let req = test::TestRequest::post()
+        .uri("/api/jobs")
+        .insert_header(bearer(key))
+        .set_json(job_payload(&company["id"]))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+
+    // Candidates cannot see who else applied.
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/companies/{}/applications", company["id"].as_str().unwrap()))
+        .insert_header(bearer(&candidate))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test]
+async fn expired_and_forged_keys_are_rejected(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+
+    let (status, _) = create_key(
+        &app,
+        &admin,
+        &company["id"],
+        json!({ "name": "Old", "scopes": ["jobs:write"], "expires_at": "2020-01-01T00:00:00Z" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = create_key(&app, &admin, &company["id"], json!({ "name": "None", "scopes": [] })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = create_key(&app, &admin, &company["id"], json!({ "name": "Bad", "scopes": ["jobs:delete"] })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, created) = create_key(
+        &app,
+        &admin,
+        &company["id"],
+        json!({ "name": "Short-lived", "scopes": ["jobs:write"], "expires_at": "2999-01-01T00:00:00Z" }),
+    )
+    .await;
+    let key = created["key"].as_str().unwrap();
+    let post_job = |key: &str| {
+        test::TestRequest::post()
+            .uri("/api/jobs")
+            .insert_header(bearer(key))
+            .set_json(job_payload(&company["id"]))
+            .to_request()
+    };
+    assert_eq!(send(&app, post_job(key)).await.0, StatusCode::CREATED);
+
+    let forged = format!("chk_{}_{}", created["prefix"].as_str().unwrap(), "x".repeat(43));
+    assert_eq!(send(&app, post_job(&forged)).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, post_job("chk_nonsense")).await.0, StatusCode::UNAUTHORIZED);
+
+    sqlx::query("UPDATE api_keys SET expires_at = NOW() - INTERVAL '1 minute'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(send(&app, post_job(key)).await.0, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use careerhub_backend::{
+    config::RateLimitConfig,
+    rate_limit::{MemoryStore, RateLimiter},
+    AppState,
+};
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use common::{admin_token, bearer, create_company, create_job, init_app, init_app_with, jwt_config, send};
+
+/// The address recorded for a company created from peer `10.0.0.1` with a
+/// forged `X-Forwarded-For`.
+async fn recorded_ip(pool: PgPool, trust_forwarded_for: bool) -> Option<String> {
+    let config = RateLimitConfig { trust_forwarded_for, ..Default::default() };
+    let state = AppState::new(pool.clone(), jwt_config())
+        .with_rate_limiter(RateLimiter::new(config, Arc::new(MemoryStore::default())));
+    let app = init_app_with(&state).await;
+    let admin = admin_token(&app, &pool).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/companies")
+        .peer_addr("10.0.0.1:40000".parse().unwrap())
+        .insert_header(("X-Forwarded-For", "203.0.113.7"))
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "name": "Acme", "industry": "Software", "size": "Large" }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+
+    sqlx::query_scalar("SELECT ip_address FROM audit_events WHERE entity_type = 'company' AND action = 'create'")
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn forwarded_addresses_are_ignored_by_default(pool: PgPool) {
+    assert_eq!(recorded_ip(pool, false).await.as_deref(), Some("10.0.0.1"));
+}
+
+#[sqlx::test]
+async fn forwarded_addresses_are_recorded_behind_a_trusted_proxy(pool: PgPool) {
+    assert_eq!(recorded_ip(pool, true).await.as_deref(), Some("203.0.113.7"));
+}
+
+#[sqlx::test]
+async fn every_mutation_is_recorded_once(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let req = test::TestRequest::get().uri("/api/users/profile").insert_header(bearer(&admin)).to_request();
+    let (_, profile) = send(&app, req).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+    let job_uri = format!("/api/jobs/{}", job["id"].as_str().unwrap());
+
+    let req = test::TestRequest::put()
+        .uri(&job_uri)
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "is_active": false }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+    let req = test::TestRequest::delete().uri(&job_uri).insert_header(bearer(&admin)).to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NO_CONTENT);
+
+    // Neither reads nor failed writes are recorded.
+    let req = test::TestRequest::get().uri(&job_uri).insert_header(bearer(&admin)).to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+    let req = test::TestRequest::put()
+        .uri(&job_uri)
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "is_active": true }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/admin/audit-events?entity_type=job&entity_id={}", job["id"].as_str().unwrap()))
+        .insert_header(bearer(&admin))
+        .to_request();
+    let (status, events) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", events);
+    let events = events.as_array().unwrap();
+    let actions: Vec<_> = events.iter().map(|event| event["action"].as_str().unwrap()).collect();
+    assert_eq!(actions, ["delete", "update", "create"]);
+
+    for event in events {
+        assert_eq!(event["actor_id"], profile["id"]);
+        assert!(event["request_id"].is_string());
+    }
+    assert_eq!(events[1]["method"], "PUT");
+    assert_eq!(events[1]["path"], job_uri.as_str());
+    assert_eq!(events[1]["changes"], json!({ "is_active": { "from": true, "to": false } }));
+    assert!(events[0]["after"].is_null());
+
+    let req = test::TestRequest::get()
+        .uri("/api/admin/audit-events?entity_type=company")
+        .insert_header(bearer(&admin))
+        .to_request();
+    let (_, events) = send(&app, req).await;
+    assert_eq!(events.as_array().unwrap().len(), 1);
+    assert_eq!(events[0]["action"], "create");
+    assert_eq!(events[0]["entity_id"], company["id"]);
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{init_app, register, send, PASSWORD};
+
+#[sqlx::test]
+async fn register_returns_the_new_user_id(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+
+    let id = register(&app, "alice@example.com").await;
+
+    let email: String = sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(email, "alice@example.com");
+}
+
+#[sqlx::test]
+async fn register_rejects_a_duplicate_email(pool: PgPool) {
+    let app = init_app(pool).await;
+    register(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/register")
+        .set_json(json!({ "email": "alice@example.com", "password": PASSWORD, "name": "Alice" }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "conflict");
+}
+
+#[sqlx::test]
+async fn register_reports_invalid_fields(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/register")
+        .set_json(json!({ "email": "not-an-email", "password": "short", "name": "Alice" }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "validation_failed");
+    assert!(body["error"]["details"]["email"].is_array());
+    assert!(body["error"]["details"]["password"].is_array());
+}
+
+#[sqlx::test]
+async fn register_rejects_malformed_json(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/register")
+        .insert_header(("content-type", "application/json"))
+        .set_payload("{\"email\":")
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "bad_request");
+}
+
+#[sqlx::test]
+async fn login_returns_a_token_and_the_user(pool: PgPool) {
+    let app = init_app(pool).await;
+    let id = register(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "alice@example.com", "password": PASSWORD }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["token"].as_str().is_some_and(|t| !t.is_empty()));
+    assert_eq!(body["user"]["id"], json!(id));
+    assert_eq!(body["user"]["email"], "alice@example.com");
+}
+
+#[sqlx::test]
+async fn login_rejects_a_wrong_password(pool: PgPool) {
+    let app = init_app(pool).await;
+    register(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "alice@example.com", "password": "wrong-password" }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["code"], "unauthorized");
+}
+
+#[sqlx::test]
+async fn login_rejects_an_unknown_email(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "nobody@example.com", "password": PASSWORD }))
+        .to_request();
+    let (status, _) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn login_refuses_suspended_accounts(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let id = register(&app, "alice@example.com").await;
+
+    sqlx::query("UPDATE users SET suspended_at = NOW() WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "alice@example.com", "password": PASSWORD }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["code"], "forbidden");
+}
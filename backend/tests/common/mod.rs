@@ -0,0 +1,180 @@
+//! Shared harness for the integration tests.
+//!
+//! Every test gets its own database from `#[sqlx::test]`, created from
+//! `migrations/`, and drives the real route scopes through [`init_app`].
+#![allow(dead_code)]
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::{header, StatusCode},
+    test, App, Error,
+};
+use careerhub_backend::{
+    auth::jwt::{JwtConfig, JwtKey},
+    request_id::RequestIdMiddleware,
+    telemetry::RequestTracing,
+    AppState,
+};
+use chrono::Duration;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub const PASSWORD: &str = "correct-horse-battery";
+
+pub fn jwt_config() -> JwtConfig {
+    JwtConfig::new(JwtKey::hmac(b"test-secret"), Duration::hours(1))
+}
+
+/// The application as `main` serves it, minus CORS.
+pub async fn init_app(
+    pool: PgPool,
+) -> impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = Error> {
+    init_app_with(&AppState::new(pool, jwt_config())).await
+}
+
+/// Like [`init_app`], for tests that need to reach into the shared state.
+pub async fn init_app_with(
+    state: &AppState,
+) -> impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = Error> {
+    test::init_service(
+        App::new()
+            .wrap(RequestTracing)
+            .wrap(RequestIdMiddleware)
+            .configure(|cfg| careerhub_backend::configure(cfg, state)),
+    )
+    .await
+}
+
+/// Sends `req` and returns the status with the body parsed as JSON (`Null` when empty).
+pub async fn send<S, B>(app: &S, req: Request) -> (StatusCode, Value)
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let resp = test::call_service(app, req).await;
+    let status = resp.status();
+    let body = test::read_body(resp).await;
+
+    let json = if body.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&body).expect("response body is not JSON")
+    };
+
+    (status, json)
+}
+
+pub fn bearer(token: &str) -> (header::HeaderName, String) {
+    (header::AUTHORIZATION, format!("Bearer {}", token))
+}
+
+pub async fn register<S, B>(app: &S, email: &str) -> Uuid
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri("/api/auth/register")
+        .set_json(json!({ "email": email, "password": PASSWORD, "name": "Test User" }))
+        .to_request();
+
+    let (status, body) = send(app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "register failed: {}", body);
+
+    serde_json::from_value(body).unwrap()
+}
+
+pub async fn login<S, B>(app: &S, email: &str) -> String
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": email, "password": PASSWORD }))
+        .to_request();
+
+    let (status, body) = send(app, req).await;
+    assert_eq!(status, StatusCode::OK, "login failed: {}", body);
+
+    body["token"].as_str().unwrap().to_string()
+}
+
+/// Registers `email` and returns its id together with a bearer token.
+pub async fn register_and_login<S, B>(app: &S, email: &str) -> (Uuid, String)
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let id = register(app, email).await;
+    let token = login(app, email).await;
+
+    (id, token)
+}
+
+/// Registers an account and promotes it to admin.
+pub async fn admin_token<S, B>(app: &S, pool: &PgPool) -> String
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let (id, token) = register_and_login(app, "admin@example.com").await;
+
+    sqlx::query("UPDATE users SET role = 'admin' WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    token
+}
+
+pub async fn create_company<S, B>(app: &S, token: &str, name: &str) -> Value
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri("/api/companies")
+        .insert_header(bearer(token))
+        .set_json(json!({ "name": name, "industry": "Software", "size": "Large" }))
+        .to_request();
+
+    let (status, body) = send(app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "create company failed: {}", body);
+
+    body
+}
+
+pub fn job_payload(company_id: &Value) -> Value {
+    json!({
+        "title": "Backend Engineer",
+        "description": "Build and run the API",
+        "company_id": company_id,
+        "location": "Berlin",
+        "job_type": "Fulltime",
+        "experience_level": "Senior",
+        "salary_range": { "min": 70000, "max": 90000, "currency": "EUR" },
+        "skills": ["rust", "postgres"]
+    })
+}
+
+pub async fn create_job<S, B>(app: &S, token: &str, company_id: &Value) -> Value
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri("/api/jobs")
+        .insert_header(bearer(token))
+        .set_json(job_payload(company_id))
+        .to_request();
+
+    let (status, body) = send(app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "create job failed: {}", body);
+
+    body
+}
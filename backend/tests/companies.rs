@@ -0,0 +1,205 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{admin_token, bearer, create_company, create_job, init_app, register_and_login, send};
+
+#[sqlx::test]
+async fn company_routes_require_a_token(pool: PgPool) {
+    let app = init_app(pool).await;
+    let id = Uuid::new_v4();
+
+    let requests = [
+        test::TestRequest::get().uri("/api/companies"),
+        test::TestRequest::post().uri("/api/companies"),
+        test::TestRequest::get().uri(&format!("/api/companies/{}", id)),
+        test::TestRequest::put().uri(&format!("/api/companies/{}", id)),
+        test::TestRequest::delete().uri(&format!("/api/companies/{}", id)),
+        test::TestRequest::put().uri(&format!("/api/companies/{}/verification", id)),
+    ];
+
+    for req in requests {
+        let (status, _) = send(&app, req.to_request()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[sqlx::test]
+async fn create_company_derives_a_unique_slug(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    let first = create_company(&app, &token, "Acme Labs").await;
+    assert_eq!(first["slug"], "acme-labs");
+    assert_eq!(first["verified"], false);
+
+    let second = create_company(&app, &token, "Acme Labs").await;
+    let slug = second["slug"].as_str().unwrap();
+    assert!(slug.starts_with("acme-labs-"), "{}", slug);
+}
+
+#[sqlx::test]
+async fn create_company_validates_the_payload(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/companies")
+        .insert_header(bearer(&token))
+        .set_json(json!({ "name": "Acme", "website": "not a url" }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"]["details"]["website"].is_array());
+}
+
+#[sqlx::test]
+async fn list_and_get_companies(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let acme = create_company(&app, &token, "Acme").await;
+    create_company(&app, &token, "Globex").await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/companies")
+        .insert_header(bearer(&token))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 2);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/companies/{}", acme["id"].as_str().unwrap()))
+        .insert_header(bearer(&token))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["name"], "Acme");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/companies/{}", Uuid::new_v4()))
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn update_company_changes_only_the_given_fields(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+    let uri = format!("/api/companies/{}", company["id"].as_str().unwrap());
+
+    let req = test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(bearer(&token))
+        .set_json(json!({ "location": "Berlin", "size": "Small" }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["location"], "Berlin");
+    assert_eq!(body["size"], "Small");
+    assert_eq!(body["name"], "Acme");
+    assert_eq!(body["industry"], "Software");
+
+    let req = test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(bearer(&token))
+        .set_json(json!({}))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["message"], "No fields to update");
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/companies/{}", Uuid::new_v4()))
+        .insert_header(bearer(&token))
+        .set_json(json!({ "location": "Berlin" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn verification_is_reserved_for_admins(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &token, "Acme").await;
+    let uri = format!("/api/companies/{}/verification", company["id"].as_str().unwrap());
+
+    let req = test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(bearer(&token))
+        .set_json(json!({ "verified": true }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["code"], "forbidden");
+
+    let req = test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "verified": true }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["verified"], true);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/companies/{}/verification", Uuid::new_v4()))
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "verified": true }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn public_page_is_served_without_a_token(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+    create_job(&app, &token, &company["id"]).await;
+
+    let req = test::TestRequest::get().uri("/api/public/companies/acme").to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], company["id"]);
+    assert_eq!(body["job_count"], 1);
+    assert_eq!(body["open_jobs"].as_array().unwrap().len(), 1);
+
+    let req = test::TestRequest::get().uri("/api/public/companies/initech").to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn delete_company_hides_it_and_its_jobs(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+    let job = create_job(&app, &token, &company["id"]).await;
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/companies/{}", company["id"].as_str().unwrap()))
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NO_CONTENT);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/companies/{}", company["id"].as_str().unwrap()))
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/jobs/{}", job["id"].as_str().unwrap()))
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::get().uri("/api/public/companies/acme").to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,349 @@
+use careerhub_backend::{
+    config::{Config, ConfigError, Environment, LogFormat, RateLimitStoreKind},
+    versioning::ApiVersion,
+};
+
+fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn problems(result: Result<Config, ConfigError>) -> Vec<String> {
+    match result {
+        Err(ConfigError::Invalid(problems)) => problems,
+        other => panic!("expected validation problems, got {:?}", other),
+    }
+}
+
+#[test]
+fn defaults_need_only_a_jwt_secret() {
+    let config = Config::from_sources(None, env(&[("JWT_SECRET", "dev-secret")])).unwrap();
+
+    assert_eq!(config.environment, Environment::Development);
+    assert_eq!(config.server.host, "127.0.0.1");
+    assert_eq!(config.server.port, 8080);
+    assert_eq!(config.database.max_connections, 5);
+    assert_eq!(config.cors.allowed_origins, vec!["*"]);
+    assert_eq!(config.jwt.jwt_config().unwrap().expiration.num_hours(), 24);
+}
+
+#[test]
+fn file_overrides_defaults_and_env_overrides_file() {
+    let file = r#"
+        [server]
+        port = 9000
+        workers = 2
+
+        [database]
+        max_connections = 20
+
+        [jwt]
+        secret = "from-file"
+    "#;
+    let vars = env(&[
+        ("CAREERHUB__SERVER__PORT", "9100"),
+        ("CAREERHUB__CORS__ALLOWED_ORIGINS", "https://a.example, https://b.example"),
+        ("PORT", "3000"),
+        ("UNRELATED", "ignored"),
+    ]);
+
+    let config = Config::from_sources(Some(file), vars).unwrap();
+
+    assert_eq!(config.server.port, 9100);
+    assert_eq!(config.server.workers, Some(2));
+    assert_eq!(config.server.keep_alive_secs, 5);
+    assert_eq!(config.database.max_connections, 20);
+    assert_eq!(config.cors.allowed_origins, vec!["https://a.example", "https://b.example"]);
+    assert_eq!(config.jwt.secret, "from-file");
+}
+
+#[test]
+fn legacy_variables_are_honoured() {
+    let vars = env(&[
+        ("DATABASE_URL", "postgres://app@db/careerhub"),
+        ("JWT_SECRET", "dev-secret"),
+        ("JWT_EXPIRES_IN", "3600"),
+        ("SOFT_DELETE_RETENTION_DAYS", "7"),
+    ]);
+
+    let config = Config::from_sources(None, vars).unwrap();
+
+    assert_eq!(config.database.url, "postgres://app@db/careerhub");
+    assert_eq!(config.jwt.expiration_secs, 3600);
+    assert_eq!(config.retention.soft_delete_days, 7);
+}
+
+#[test]
+fn malformed_sources_are_rejected() {
+    let vars = env(&[("JWT_SECRET", "dev-secret")]);
+
+    assert!(matches!(
+        Config::from_sources(Some("[server]\nprot = 80"), vars.clone()),
+        Err(ConfigError::Parse(_))
+    ));
+    assert!(matches!(
+        Config::from_sources(Some("[server"), vars.clone()),
+        Err(ConfigError::Parse(_))
+    ));
+
+    let mut bad_port = vars;
+    bad_port.push(("CAREERHUB__SERVER__PORT".to_string(), "eighty".to_string()));
+    match Config::from_sources(None, bad_port) {
+        Err(ConfigError::Env { var, .. }) => assert_eq!(var, "CAREERHUB__SERVER__PORT"),
+        other => panic!("expected an env error, got {:?}", other),
+    }
+}
+
+#[test]
+fn validation_reports_every_problem() {
+    let file = r#"
+        [server]
+        workers = 0
+
+        [database]
+        url = "mysql://localhost/careerhub"
+        max_connections = 2
+        min_connections = 3
+
+        [cors]
+        allowed_origins = ["https://careerhub.example/app"]
+
+        [webhooks]
+        allowed_hosts = ["localhost", "http://127.0.0.1"]
+    "#;
+
+    let problems = problems(Config::from_sources(Some(file), Vec::new()));
+
+    assert_eq!(problems.len(), 6, "{:?}", problems);
+    assert!(problems.iter().any(|p| p.starts_with("server.workers")));
+    assert!(problems.iter().any(|p| p.starts_with("database.url")));
+    assert!(problems.iter().any(|p| p.starts_with("database.min_connections")));
+    assert!(problems.iter().any(|p| p.starts_with("cors.allowed_origins")));
+    assert!(problems.iter().any(|p| p.starts_with("jwt.secret")));
+    assert!(problems.iter().any(|p| p.starts_with("webhooks.allowed_hosts entry \"http://127.0.0.1\"")));
+}
+
+#[test]
+fn production_requires_explicit_origins_and_a_strong_secret() {
+    let vars = env(&[("ENVIRONMENT", "production"), ("JWT_SECRET", "dev-secret")]);
+    let problems = problems(Config::from_sources(None, vars));
+
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems.iter().any(|p| p.contains("explicit origins")));
+    assert!(problems.iter().any(|p| p.contains("at least 32 characters")));
+
+    let vars = env(&[
+        ("CAREERHUB__ENVIRONMENT", "production"),
+        ("CAREERHUB__JWT__SECRET", "0123456789abcdef0123456789abcdef"),
+        ("CAREERHUB__CORS__ALLOWED_ORIGINS", "https://careerhub.example"),
+    ]);
+    let config = Config::from_sources(None, vars).unwrap();
+
+    assert_eq!(config.environment, Environment::Production);
+    assert_eq!(config.telemetry.log_format(config.environment), LogFormat::Json);
+}
+
+#[test]
+fn telemetry_settings_are_checked() {
+    let vars = env(&[
+        ("JWT_SECRET", "dev-secret"),
+        ("CAREERHUB__TELEMETRY__LOG_FILTER", "info,sqlx=[[bad"),
+        ("CAREERHUB__TELEMETRY__OTLP_ENDPOINT", "localhost:4318"),
+    ]);
+    let problems = problems(Config::from_sources(None, vars));
+
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems.iter().any(|p| p.starts_with("telemetry.log_filter")));
+    assert!(problems.iter().any(|p| p.starts_with("telemetry.otlp_endpoint")));
+
+    let vars = env(&[
+        ("JWT_SECRET", "dev-secret"),
+        ("CAREERHUB__TELEMETRY__LOG_FORMAT", "json"),
+        ("CAREERHUB__TELEMETRY__OTLP_ENDPOINT", "http://localhost:4318"),
+    ]);
+    let config = Config::from_sources(None, vars).unwrap();
+
+    assert_eq!(config.telemetry.log_format(config.environment), LogFormat::Json);
+    assert_eq!(config.telemetry.otlp_endpoint.as_deref(), Some("http://localhost:4318"));
+}
+
+#[test]
+fn rate_limits_are_configurable() {
+    let vars = env(&[
+        ("JWT_SECRET", "dev-secret"),
+        ("CAREERHUB__RATE_LIMIT__STORE", "postgres"),
+        ("CAREERHUB__RATE_LIMIT__LOGIN_PER_IP__LIMIT", "3"),
+    ]);
+    let config = Config::from_sources(None, vars).unwrap();
+
+    assert_eq!(config.rate_limit.store, RateLimitStoreKind::Postgres);
+    assert_eq!(config.rate_limit.login_per_ip.limit, 3);
+    assert_eq!(config.rate_limit.login_per_ip.window_secs, 60);
+
+    let file = r#"
+        [rate_limit]
+        register_per_ip = { limit = 0, window_secs = 60 }
+        lockout_base_secs = 600
+        lockout_max_secs = 60
+    "#;
+    let problems = problems(Config::from_sources(Some(file), env(&[("JWT_SECRET", "dev-secret")])));
+
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems.iter().any(|p| p.starts_with("rate_limit.register_per_ip")));
+    assert!(problems.iter().any(|p| p.starts_with("rate_limit.lockout_max_secs")));
+}
+
+#[test]
+fn the_worker_pool_is_configurable() {
+    let vars = env(&[
+        ("JWT_SECRET", "dev-secret"),
+        ("CAREERHUB__QUEUE__RUN_WITH_SERVER", "false"),
+        ("CAREERHUB__QUEUE__CONCURRENCY", "16"),
+    ]);
+    let config = Config::from_sources(None, vars).unwrap();
+
+    assert!(!config.queue.run_with_server);
+    assert_eq!(config.queue.concurrency, 16);
+    assert_eq!(config.queue.lease_secs, 300);
+
+    let file = r#"
+        [queue]
+        concurrency = 0
+        backoff_base_secs = 60
+        backoff_max_secs = 30
+    "#;
+    let problems = problems(Config::from_sources(Some(file), env(&[("JWT_SECRET", "dev-secret")])));
+
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems.iter().any(|p| p.starts_with("queue.concurrency")));
+    assert!(problems.iter().any(|p| p.starts_with("queue.backoff_max_secs")));
+}
+
+#[test]
+fn api_deprecations_are_configurable() {
+    let file = r#"
+        [api]
+        legacy_sunset_at = "2027-04-01T00:00:00Z"
+
+        [api.deprecations.v1]
+        deprecated_at = "2027-01-01T00:00:00Z"
+    "#;
+    let vars = env(&[
+        ("JWT_SECRET", "dev-secret"),
+        ("CAREERHUB__API__DEPRECATIONS__V1__SUNSET_AT", "2027-07-01T00:00:00Z"),
+    ]);
+    let config = Config::from_sources(Some(file), vars).unwrap();
+
+    assert_eq!(config.api.legacy_sunset_at.unwrap().to_rfc3339(), "2027-04-01T00:00:00+00:00");
+    let v1 = &config.api.deprecations[&ApiVersion::V1];
+    assert_eq!(v1.deprecated_at.to_rfc3339(), "2027-01-01T00:00:00+00:00");
+    assert_eq!(v1.sunset_at.unwrap().to_rfc3339(), "2027-07-01T00:00:00+00:00");
+
+    let file = r#"
+        [api.deprecations.v1]
+        deprecated_at = "2027-01-01T00:00:00Z"
+        sunset_at = "2026-12-01T00:00:00Z"
+
+        [api.deprecations.v2]
+        deprecated_at = "2027-01-01T00:00:00Z"
+    "#;
+    let problems = problems(Config::from_sources(Some(file), env(&[("JWT_SECRET", "dev-secret")])));
+
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems.iter().any(|p| p == "api.deprecations.v1.sunset_at must be after deprecated_at"));
+    assert!(problems.iter().any(|p| p == "api.deprecations.v2: the latest version cannot be deprecated"));
+}
+
+#[test]
+fn oidc_providers_are_configurable() {
+    let file = r#"
+        [oidc.providers.google]
+        issuer = "https://accounts.google.com"
+        client_id = "careerhub"
+        redirect_uri = "https://careerhub.example/login/google"
+    "#;
+    let vars = env(&[
+        ("JWT_SECRET", "dev-secret"),
+        ("CAREERHUB__OIDC__PROVIDERS__GOOGLE__CLIENT_SECRET", "from-env"),
+    ]);
+    let config = Config::from_sources(Some(file), vars).unwrap();
+
+    let google = &config.oidc.providers["google"];
+    assert_eq!(google.client_secret.as_deref(), Some("from-env"));
+    assert_eq!(google.scopes, ["openid", "email", "profile"]);
+
+    let file = r#"
+        environment = "production"
+
+        [cors]
+        allowed_origins = ["https://careerhub.example"]
+
+        [oidc.providers.Corp]
+        issuer = "http://sso.corp.example"
+        client_id = ""
+        redirect_uri = "https://careerhub.example/login/corp"
+        scopes = ["email"]
+    "#;
+    let vars = env(&[("JWT_SECRET", "a-production-secret-of-32-characters")]);
+    let problems = problems(Config::from_sources(Some(file), vars));
+
+    assert_eq!(problems.len(), 4, "{:?}", problems);
+    assert!(problems.iter().any(|p| p.contains("\"Corp\" must be lowercase")));
+    assert!(problems.iter().any(|p| p == "oidc.providers.Corp.issuer must use https in production"));
+    assert!(problems.iter().any(|p| p == "oidc.providers.Corp.client_id must not be empty"));
+    assert!(problems.iter().any(|p| p == "oidc.providers.Corp.scopes must include openid"));
+}
+
+#[test]
+fn jwt_keys_are_loaded_from_files() {
+    let file = format!(
+        r#"
+        [jwt]
+        secret = "legacy-secret"
+        issuer = "https://careerhub.example"
+        signing_kid = "ed-2024"
+
+        [[jwt.keys]]
+        kid = "rsa-2024"
+        algorithm = "RS256"
+        private_key_path = "{}"
+
+        [[jwt.keys]]
+        kid = "ed-2024"
+        algorithm = "EdDSA"
+        private_key_path = "{}"
+        "#,
+        fixture("jwt-rs256.pem"),
+        fixture("jwt-ed25519.pem"),
+    );
+    let jwt_config = Config::from_sources(Some(&file), Vec::new()).unwrap().jwt.jwt_config().unwrap();
+
+    assert_eq!(jwt_config.signing_key.kid(), Some("ed-2024"));
+    assert_eq!(jwt_config.issuer, "https://careerhub.example");
+    // The secret is kept for verification while clients move over.
+    let kids: Vec<_> = jwt_config.verification_keys.iter().map(|key| key.kid()).collect();
+    assert_eq!(kids, [None, Some("rsa-2024")]);
+    assert_eq!(jwt_config.jwks().keys.len(), 2);
+
+    // An RSA key file given as Ed25519 is refused when loaded.
+    let file = format!(
+        "[jwt]\nsigning_kid = \"k\"\n[[jwt.keys]]\nkid = \"k\"\nalgorithm = \"EdDSA\"\nprivate_key_path = \"{}\"",
+        fixture("jwt-rs256.pem")
+    );
+    match Config::from_sources(Some(&file), Vec::new()).unwrap().jwt.jwt_config() {
+        Err(ConfigError::Invalid(problems)) => assert!(problems[0].starts_with("jwt.keys: k:"), "{:?}", problems),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("mismatched key accepted"),
+    }
+
+    let file = "[jwt]\nsigning_kid = \"missing\"";
+    assert_eq!(
+        problems(Config::from_sources(Some(file), Vec::new())),
+        ["jwt.signing_kid: no key in jwt.keys has kid \"missing\""]
+    );
+}
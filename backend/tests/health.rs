@@ -0,0 +1,77 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use careerhub_backend::{migrate, AppState};
+use sqlx::PgPool;
+
+use common::{init_app, init_app_with, jwt_config, send};
+
+#[sqlx::test]
+async fn healthz_needs_no_token(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let req = test::TestRequest::get().uri("/healthz").to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+}
+
+#[sqlx::test]
+async fn readyz_reports_ready_on_a_migrated_database(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let req = test::TestRequest::get().uri("/readyz").to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["database"], "ok");
+    assert_eq!(body["migrations"], "ok");
+}
+
+#[sqlx::test(migrations = false)]
+async fn readyz_fails_until_migrations_are_applied(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+
+    let req = test::TestRequest::get().uri("/readyz").to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["database"], "ok");
+    assert_eq!(body["migrations"], "pending");
+
+    migrate::up(&pool).await.unwrap();
+
+    let req = test::TestRequest::get().uri("/readyz").to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn readyz_fails_while_draining(pool: PgPool) {
+    let state = AppState::new(pool, jwt_config());
+    let app = init_app_with(&state).await;
+
+    state.readiness.start_draining();
+
+    let req = test::TestRequest::get().uri("/readyz").to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["draining"], true);
+
+    // Liveness is unaffected.
+    let req = test::TestRequest::get().uri("/healthz").to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn version_reports_the_build(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let req = test::TestRequest::get().uri("/version").to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["git_sha"].as_str().is_some_and(|sha| !sha.is_empty()));
+    assert!(body["build_time"].is_string());
+}
@@ -0,0 +1,262 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{bearer, create_company, create_job, init_app, job_payload, register_and_login, send};
+
+#[sqlx::test]
+async fn job_routes_require_a_token(pool: PgPool) {
+    let app = init_app(pool).await;
+    let id = Uuid::new_v4();
+
+    let requests = [
+        test::TestRequest::get().uri("/api/jobs"),
+        test::TestRequest::post().uri("/api/jobs"),
+        test::TestRequest::get().uri(&format!("/api/jobs/{}", id)),
+        test::TestRequest::put().uri(&format!("/api/jobs/{}", id)),
+        test::TestRequest::delete().uri(&format!("/api/jobs/{}", id)),
+    ];
+
+    for req in requests {
+        let (status, body) = send(&app, req.to_request()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["message"], "Missing token");
+    }
+}
+
+#[sqlx::test]
+async fn job_routes_reject_an_invalid_token(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/jobs")
+        .insert_header(bearer("not-a-jwt"))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["message"], "Invalid token");
+}
+
+#[sqlx::test]
+async fn create_and_get_a_job(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+
+    let job = create_job(&app, &token, &company["id"]).await;
+    assert_eq!(job["title"], "Backend Engineer");
+    assert_eq!(job["company_id"], company["id"]);
+    assert_eq!(job["is_active"], true);
+    assert_eq!(job["company_verified"], false);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/jobs/{}", job["id"].as_str().unwrap()))
+        .insert_header(bearer(&token))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], job["id"]);
+    assert_eq!(body["skills"], json!(["rust", "postgres"]));
+}
+
+#[sqlx::test]
+async fn create_job_validates_the_payload(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+
+    let mut payload = job_payload(&company["id"]);
+    payload["title"] = json!("");
+
+    let req = test::TestRequest::post()
+        .uri("/api/jobs")
+        .insert_header(bearer(&token))
+        .set_json(payload)
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "validation_failed");
+    assert!(body["error"]["details"]["title"].is_array());
+}
+
+#[sqlx::test]
+async fn create_job_rejects_an_unknown_enum_value(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+
+    let mut payload = job_payload(&company["id"]);
+    payload["job_type"] = json!("Gig");
+
+    let req = test::TestRequest::post()
+        .uri("/api/jobs")
+        .insert_header(bearer(&token))
+        .set_json(payload)
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "bad_request");
+}
+
+#[sqlx::test]
+async fn list_jobs_applies_filters(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+
+    create_job(&app, &token, &company["id"]).await;
+
+    let mut frontend = job_payload(&company["id"]);
+    frontend["title"] = json!("Frontend Engineer");
+    frontend["location"] = json!("Lisbon");
+    frontend["experience_level"] = json!("Lead");
+    frontend["skills"] = json!(["typescript"]);
+    let req = test::TestRequest::post()
+        .uri("/api/jobs")
+        .insert_header(bearer(&token))
+        .set_json(frontend)
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::CREATED);
+
+    let cases = [
+        ("", 2),
+        ("?title=frontend", 1),
+        ("?location=berlin", 1),
+        ("?experience_level=Lead", 1),
+        ("?skills=rust,postgres", 1),
+        ("?skills=rust,typescript", 0),
+        ("?search=engineer", 2),
+        ("?per_page=1", 1),
+        ("?per_page=1&page=3", 0),
+    ];
+
+    for (query, expected) in cases {
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/jobs{}", query))
+            .insert_header(bearer(&token))
+            .to_request();
+        let (status, body) = send(&app, req).await;
+
+        assert_eq!(status, StatusCode::OK, "{}", query);
+        assert_eq!(body.as_array().unwrap().len(), expected, "{}", query);
+    }
+}
+
+#[sqlx::test]
+async fn list_jobs_rejects_a_malformed_query(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/jobs?page=first")
+        .insert_header(bearer(&token))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "bad_request");
+}
+
+#[sqlx::test]
+async fn get_job_answers_not_found(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    for uri in [format!("/api/jobs/{}", Uuid::new_v4()), "/api/jobs/not-a-uuid".to_string()] {
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(bearer(&token))
+            .to_request();
+        let (status, body) = send(&app, req).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+        assert_eq!(body["error"]["code"], "not_found");
+    }
+}
+
+#[sqlx::test]
+async fn update_job_changes_only_the_given_fields(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+    let job = create_job(&app, &token, &company["id"]).await;
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/jobs/{}", job["id"].as_str().unwrap()))
+        .insert_header(bearer(&token))
+        .set_json(json!({ "title": "Staff Engineer", "is_active": false, "skills": ["go"] }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["title"], "Staff Engineer");
+    assert_eq!(body["is_active"], false);
+    assert_eq!(body["skills"], json!(["go"]));
+    assert_eq!(body["location"], job["location"]);
+    assert_eq!(body["salary_range"], job["salary_range"]);
+}
+
+#[sqlx::test]
+async fn update_job_validates_and_answers_not_found(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+    let job = create_job(&app, &token, &company["id"]).await;
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/jobs/{}", job["id"].as_str().unwrap()))
+        .insert_header(bearer(&token))
+        .set_json(json!({ "location": "" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::BAD_REQUEST);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/jobs/{}", Uuid::new_v4()))
+        .insert_header(bearer(&token))
+        .set_json(json!({ "title": "Staff Engineer" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn delete_job_hides_it(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+    let job = create_job(&app, &token, &company["id"]).await;
+    let uri = format!("/api/jobs/{}", job["id"].as_str().unwrap());
+
+    let req = test::TestRequest::delete()
+        .uri(&uri)
+        .insert_header(bearer(&token))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(body.is_null());
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::get()
+        .uri("/api/jobs")
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(send(&app, req).await.1, json!([]));
+
+    // Deleting again is a no-op.
+    let req = test::TestRequest::delete()
+        .uri(&uri)
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NO_CONTENT);
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::{header, StatusCode},
+    test, Error,
+};
+use careerhub_backend::{
+    auth::jwt::{
+        generate_challenge_token, generate_token, validate_challenge_token, validate_token, JwtConfig, JwtKey,
+        CHALLENGE_AUDIENCE, CHALLENGE_TYPE,
+    },
+    models::users::{User, UserRole},
+    AppState,
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{bearer, init_app_with, login, register, send};
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn key(kid: &str, algorithm: Algorithm, file: &str) -> JwtKey {
+    JwtKey::from_pem(kid, algorithm, &std::fs::read(fixture(file)).unwrap()).unwrap()
+}
+
+fn rsa_key() -> JwtKey {
+    key("rsa-2024", Algorithm::RS256, "jwt-rs256.pem")
+}
+
+fn ed_key() -> JwtKey {
+    key("ed-2024", Algorithm::EdDSA, "jwt-ed25519.pem")
+}
+
+async fn profile_status<S, B>(app: &S, token: &str) -> StatusCode
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::get()
+        .uri("/api/users/profile")
+        .insert_header(bearer(token))
+        .to_request();
+
+    send(app, req).await.0
+}
+
+#[sqlx::test]
+async fn other_services_verify_tokens_with_the_published_keys(pool: PgPool) {
+    for (signing_key, algorithm) in [(rsa_key(), Algorithm::RS256), (ed_key(), Algorithm::EdDSA)] {
+        let kid = signing_key.kid().unwrap().to_string();
+        let jwt_config = JwtConfig::new(signing_key, Duration::hours(1));
+        let app = init_app_with(&AppState::new(pool.clone(), jwt_config)).await;
+
+        let email = format!("{}@example.com", kid);
+        let id = register(&app, &email).await;
+        let token = login(&app, &email).await;
+        assert_eq!(profile_status(&app, &token).await, StatusCode::OK);
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/.well-known/jwks.json").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(header::CACHE_CONTROL).unwrap(), "public, max-age=300");
+        let jwks: JwkSet = test::read_body_json(resp).await;
+        assert_eq!(jwks.keys.len(), 1);
+
+        // What a service holding only the key set would do.
+        let header = decode_header(&token).unwrap();
+        assert_eq!(header.alg, algorithm);
+        assert_eq!(header.kid.as_deref(), Some(kid.as_str()));
+        let jwk = jwks.find(&kid).unwrap();
+        let mut validation = Validation::new(algorithm);
+        validation.set_audience(&["careerhub"]);
+        validation.set_issuer(&["careerhub"]);
+        let claims = decode::<Value>(&token, &DecodingKey::from_jwk(jwk).unwrap(), &validation)
+            .unwrap()
+            .claims;
+
+        assert_eq!(claims["sub"], json!(id));
+        assert_eq!(claims["email"], email);
+        assert_eq!(claims["role"], "User");
+        assert!(claims.get("two_factor_pending").is_none());
+    }
+}
+
+#[sqlx::test]
+async fn rotated_keys_keep_verifying_until_removed(pool: PgPool) {
+    let before = JwtConfig::new(rsa_key(), Duration::hours(1));
+    let app = init_app_with(&AppState::new(pool.clone(), before)).await;
+    register(&app, "alice@example.com").await;
+    let old_token = login(&app, "alice@example.com").await;
+
+    // The new key signs; the old one still verifies.
+    let during = JwtConfig::new(ed_key(), Duration::hours(1)).with_verification_key(rsa_key());
+    let app = init_app_with(&AppState::new(pool.clone(), during)).await;
+    let new_token = login(&app, "alice@example.com").await;
+    assert_eq!(decode_header(&new_token).unwrap().kid.as_deref(), Some("ed-2024"));
+    assert_eq!(profile_status(&app, &old_token).await, StatusCode::OK);
+    assert_eq!(profile_status(&app, &new_token).await, StatusCode::OK);
+
+    let req = test::TestRequest::get().uri("/.well-known/jwks.json").to_request();
+    let kids: Vec<Value> = send(&app, req).await.1["keys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|key| key["kid"].clone())
+        .collect();
+    assert_eq!(kids, [json!("ed-2024"), json!("rsa-2024")]);
+
+    let after = JwtConfig::new(ed_key(), Duration::hours(1));
+    let app = init_app_with(&AppState::new(pool, after)).await;
+    assert_eq!(profile_status(&app, &old_token).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(profile_status(&app, &new_token).await, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn the_shared_secret_is_never_published(pool: PgPool) {
+    let jwt_config = JwtConfig::new(JwtKey::hmac(b"test-secret"), Duration::hours(1));
+    let app = init_app_with(&AppState::new(pool, jwt_config)).await;
+
+    let req = test::TestRequest::get().uri("/.well-known/jwks.json").to_request();
+    assert_eq!(send(&app, req).await.1, json!({ "keys": [] }));
+}
+
+#[tokio::test]
+async fn challenge_tokens_are_not_sessions() {
+    let user = User {
+        id: Uuid::new_v4(),
+        email: "alice@example.com".to_string(),
+        password_hash: None,
+        name: "Alice".to_string(),
+        role: UserRole::User,
+        company_id: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+    let jwt_config = JwtConfig::new(rsa_key(), Duration::hours(1));
+    let challenge = generate_challenge_token(&user, &jwt_config).unwrap();
+    let session = generate_token(&user, &jwt_config).unwrap();
+
+    let header = decode_header(&challenge).unwrap();
+    assert_eq!(header.typ.as_deref(), Some(CHALLENGE_TYPE));
+    assert_eq!(header.kid, None);
+
+    // Nothing holding the published keys can verify it...
+    let jwks = jwt_config.jwks();
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_audience(&[CHALLENGE_AUDIENCE]);
+    let jwk = jwks.find("rsa-2024").unwrap();
+    assert!(decode::<Value>(&challenge, &DecodingKey::from_jwk(jwk).unwrap(), &validation).is_err());
+
+    // ...nor do we take it for a session, or a session for a challenge.
+    assert!(validate_token(&challenge, &jwt_config).is_err());
+    assert!(validate_challenge_token(&session, &jwt_config).is_err());
+    let claims = validate_challenge_token(&challenge, &jwt_config).unwrap();
+    assert_eq!(claims.aud, CHALLENGE_AUDIENCE);
+    assert_eq!(claims.sub, user.id.to_string());
+
+    // A challenge outlives a key rotation, but not the old key's removal.
+    let during = JwtConfig::new(ed_key(), Duration::hours(1)).with_verification_key(rsa_key());
+    assert!(validate_challenge_token(&challenge, &during).is_ok());
+    let after = JwtConfig::new(ed_key(), Duration::hours(1));
+    assert!(validate_challenge_token(&challenge, &after).is_err());
+    let foreign = JwtConfig::new(JwtKey::hmac(b"test-secret"), Duration::hours(1));
+    assert!(validate_challenge_token(&challenge, &foreign).is_err());
+}
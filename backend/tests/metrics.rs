@@ -0,0 +1,111 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::{header, StatusCode},
+    test, Error,
+};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{bearer, create_company, create_job, init_app, register_and_login, send};
+
+/// The value of the sample `name{labels}` in a text-format scrape.
+fn sample(scrape: &str, series: &str) -> Option<f64> {
+    scrape
+        .lines()
+        .find_map(|line| line.strip_prefix(series)?.strip_prefix(' ')?.parse().ok())
+}
+
+async fn scrape<S, B>(app: &S) -> String
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    let resp = test::call_service(app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers().get(header::CONTENT_TYPE).unwrap().to_str().unwrap().starts_with("text/plain"));
+
+    String::from_utf8(test::read_body(resp).await.to_vec()).unwrap()
+}
+
+#[sqlx::test]
+async fn requests_are_counted_by_route_and_status(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    for _ in 0..2 {
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/jobs/{}", Uuid::new_v4()))
+            .insert_header(bearer(&token))
+            .to_request();
+        assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+    }
+
+    let req = test::TestRequest::get().uri("/api/jobs").to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::UNAUTHORIZED);
+
+    let scrape = scrape(&app).await;
+
+    assert_eq!(
+        sample(&scrape, r#"careerhub_http_requests_total{method="GET",route="/api/jobs/{job_id}",status="404"}"#),
+        Some(2.0)
+    );
+    assert_eq!(
+        sample(&scrape, r#"careerhub_http_requests_total{method="GET",route="/api/jobs",status="401"}"#),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample(&scrape, r#"careerhub_http_request_duration_seconds_count{method="GET",route="/api/jobs/{job_id}"}"#),
+        Some(2.0)
+    );
+    // Neither the scrape nor the probes are counted.
+    assert!(!scrape.contains(r#"route="/metrics""#));
+}
+
+#[sqlx::test]
+async fn domain_events_are_counted(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "alice@example.com", "password": "wrong-password" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::UNAUTHORIZED);
+
+    let company = create_company(&app, &token, "Acme").await;
+    let job = create_job(&app, &token, &company["id"]).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/applications")
+        .insert_header(bearer(&token))
+        .set_json(json!({ "job_id": job["id"], "resume_url": "https://example.com/cv.pdf" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::CREATED);
+
+    let scrape = scrape(&app).await;
+
+    assert_eq!(sample(&scrape, "careerhub_registrations_total"), Some(1.0));
+    assert_eq!(sample(&scrape, "careerhub_logins_total"), Some(1.0));
+    assert_eq!(sample(&scrape, "careerhub_failed_logins_total"), Some(1.0));
+    assert_eq!(sample(&scrape, "careerhub_jobs_posted_total"), Some(1.0));
+    assert_eq!(sample(&scrape, "careerhub_applications_submitted_total"), Some(1.0));
+}
+
+#[sqlx::test]
+async fn pool_and_build_are_reported(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let scrape = scrape(&app).await;
+
+    assert!(sample(&scrape, r#"careerhub_db_pool_connections{state="idle"}"#).is_some());
+    assert!(sample(&scrape, r#"careerhub_db_pool_connections{state="in_use"}"#).is_some());
+    assert!(sample(&scrape, "careerhub_db_pool_max_connections").is_some_and(|max| max >= 1.0));
+    assert!(sample(&scrape, "careerhub_db_pool_acquire_wait_seconds").is_some());
+    assert!(scrape.contains(r#"careerhub_build_info{git_sha="#));
+}
@@ -0,0 +1,90 @@
+use careerhub_backend::migrate::{self, MigrationState, MIGRATOR};
+use sqlx::PgPool;
+
+async fn table_exists(pool: &PgPool, table: &str) -> bool {
+    sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+        .bind(table)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test(migrations = false)]
+async fn up_applies_every_migration(pool: PgPool) {
+    let pending = migrate::status(&pool).await.unwrap();
+    assert!(!pending.is_empty());
+    assert!(pending.iter().all(|status| status.state == MigrationState::Pending));
+
+    migrate::up(&pool).await.unwrap();
+
+    let applied = migrate::status(&pool).await.unwrap();
+    assert_eq!(applied.len(), pending.len());
+    assert!(applied.iter().all(|status| status.state == MigrationState::Applied));
+
+    // Running again is a no-op.
+    migrate::up(&pool).await.unwrap();
+}
+
+#[sqlx::test(migrations = false)]
+async fn every_migration_can_be_reverted(pool: PgPool) {
+    migrate::up(&pool).await.unwrap();
+    let count = migrate::status(&pool).await.unwrap().len();
+
+    let latest = migrate::revert(&pool).await.unwrap().unwrap();
+    let statuses = migrate::status(&pool).await.unwrap();
+    let last = statuses.last().unwrap();
+    assert_eq!(last.version, latest);
+    assert_eq!(last.state, MigrationState::Pending);
+
+    for _ in 1..count {
+        assert!(migrate::revert(&pool).await.unwrap().is_some());
+    }
+    assert_eq!(migrate::revert(&pool).await.unwrap(), None);
+    assert!(!table_exists(&pool, "users").await);
+
+    migrate::up(&pool).await.unwrap();
+    assert!(table_exists(&pool, "users").await);
+}
+
+#[sqlx::test(migrations = false)]
+async fn status_flags_edited_and_unknown_migrations(pool: PgPool) {
+    migrate::up(&pool).await.unwrap();
+    let first = MIGRATOR.iter().next().unwrap().version;
+
+    sqlx::query("UPDATE _sqlx_migrations SET checksum = '\\x00' WHERE version = $1")
+        .bind(first)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+         VALUES (29990101000000, 'from the future', true, '\\x00', 0)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let statuses = migrate::status(&pool).await.unwrap();
+
+    assert_eq!(statuses.first().unwrap().state, MigrationState::Modified);
+    let last = statuses.last().unwrap();
+    assert_eq!(last.version, 29990101000000);
+    assert_eq!(last.state, MigrationState::Missing);
+}
+
+#[sqlx::test(migrations = false)]
+async fn schema_matches_the_models(pool: PgPool) {
+    migrate::up(&pool).await.unwrap();
+
+    let nullable: Vec<String> = sqlx::query_scalar(
+        "SELECT table_name || '.' || column_name FROM information_schema.columns \
+         WHERE table_schema = 'public' AND is_nullable = 'YES' \
+           AND table_name IN ('users', 'companies', 'jobs') \
+           AND column_name IN ('name', 'created_at', 'updated_at', 'is_active')",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+
+    assert!(nullable.is_empty(), "nullable columns: {:?}", nullable);
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, Error,
+};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use common::{admin_token, bearer, create_company, create_job, init_app, job_payload, register_and_login, send};
+
+/// Registers `email` and applies to `job`, returning the candidate's token and the application.
+async fn apply<S, B>(app: &S, email: &str, job: &Value) -> (String, Value)
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let (_, token) = register_and_login(app, email).await;
+    let req = test::TestRequest::post()
+        .uri("/api/applications")
+        .insert_header(bearer(&token))
+        .set_json(json!({ "job_id": job["id"], "resume_url": "https://example.com/cv.pdf" }))
+        .to_request();
+    let (status, application) = send(app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", application);
+
+    (token, application)
+}
+
+async fn make_offer<S, B>(app: &S, token: &str, application: &Value) -> Value
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri("/api/offers")
+        .insert_header(bearer(token))
+        .set_json(json!({
+            "application_id": application["id"],
+            "salary_amount": 80000,
+            "start_date": (Utc::now() + Duration::days(30)).date_naive(),
+            "expires_at": Utc::now() + Duration::days(7),
+        }))
+        .to_request();
+    let (status, offer) = send(app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", offer);
+
+    offer
+}
+
+fn respond(token: &str, offer: &Value, action: &str) -> Request {
+    test::TestRequest::post()
+        .uri(&format!("/api/offers/{}/{}", offer["id"].as_str().unwrap(), action))
+        .insert_header(bearer(token))
+        .to_request()
+}
+
+fn set_status(token: &str, application: &Value, status: &str) -> Request {
+    test::TestRequest::put()
+        .uri(&format!("/api/applications/{}/status", application["id"].as_str().unwrap()))
+        .insert_header(bearer(token))
+        .set_json(json!({ "status": status }))
+        .to_request()
+}
+
+async fn application_status(pool: &PgPool, application: &Value) -> String {
+    sqlx::query_scalar("SELECT status::text FROM applications WHERE id = $1::uuid")
+        .bind(application["id"].as_str().unwrap())
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn offer_status(pool: &PgPool, offer: &Value) -> String {
+    sqlx::query_scalar("SELECT status::text FROM offers WHERE id = $1::uuid")
+        .bind(offer["id"].as_str().unwrap())
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn accepting_an_offer_closes_the_other_applications(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+
+    let mut payload = job_payload(&company["id"]);
+    payload["close_applications_on_accept"] = json!(true);
+    let req = test::TestRequest::post()
+        .uri("/api/jobs")
+        .insert_header(bearer(&admin))
+        .set_json(payload)
+        .to_request();
+    let (status, job) = send(&app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", job);
+
+    let (alice, alice_application) = apply(&app, "alice@example.com", &job).await;
+    let (bob, bob_application) = apply(&app, "bob@example.com", &job).await;
+    let (_, carol_application) = apply(&app, "carol@example.com", &job).await;
+    let alice_offer = make_offer(&app, &admin, &alice_application).await;
+    let bob_offer = make_offer(&app, &admin, &bob_application).await;
+
+    // Only the candidate can accept.
+    assert_eq!(send(&app, respond(&bob, &alice_offer, "accept")).await.0, StatusCode::NOT_FOUND);
+
+    let (status, accepted) = send(&app, respond(&alice, &alice_offer, "accept")).await;
+    assert_eq!(status, StatusCode::OK, "{}", accepted);
+    assert_eq!(accepted["status"], "Accepted");
+    assert_eq!(application_status(&pool, &alice_application).await, "accepted");
+
+    assert_eq!(application_status(&pool, &bob_application).await, "closed");
+    assert_eq!(application_status(&pool, &carol_application).await, "closed");
+    assert_eq!(offer_status(&pool, &bob_offer).await, "withdrawn");
+
+    let (status, body) = send(&app, respond(&bob, &bob_offer, "accept")).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+}
+
+#[sqlx::test]
+async fn other_applications_stay_open_unless_the_job_closes_them(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+
+    let (alice, alice_application) = apply(&app, "alice@example.com", &job).await;
+    let (_, bob_application) = apply(&app, "bob@example.com", &job).await;
+    let offer = make_offer(&app, &admin, &alice_application).await;
+
+    assert_eq!(send(&app, respond(&alice, &offer, "accept")).await.0, StatusCode::OK);
+    assert_eq!(application_status(&pool, &bob_application).await, "pending");
+}
+
+#[sqlx::test]
+async fn declined_offers_cannot_be_accepted(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+    let (candidate, application) = apply(&app, "candidate@example.com", &job).await;
+    let offer = make_offer(&app, &admin, &application).await;
+
+    let (status, declined) = send(&app, respond(&candidate, &offer, "decline")).await;
+    assert_eq!(status, StatusCode::OK, "{}", declined);
+    assert_eq!(declined["status"], "Declined");
+    assert!(declined["responded_at"].is_string());
+
+    assert_eq!(send(&app, respond(&candidate, &offer, "accept")).await.0, StatusCode::CONFLICT);
+    assert_eq!(application_status(&pool, &application).await, "pending");
+}
+
+#[sqlx::test]
+async fn expired_offers_cannot_be_accepted(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+    let (candidate, application) = apply(&app, "candidate@example.com", &job).await;
+    let offer = make_offer(&app, &admin, &application).await;
+
+    sqlx::query("UPDATE offers SET expires_at = NOW() - INTERVAL '1 minute' WHERE id = $1::uuid")
+        .bind(offer["id"].as_str().unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, body) = send(&app, respond(&candidate, &offer, "accept")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["message"], "Offer has expired");
+    assert_eq!(offer_status(&pool, &offer).await, "expired");
+    assert_eq!(application_status(&pool, &application).await, "pending");
+}
+
+#[sqlx::test]
+async fn rejecting_an_application_withdraws_its_offer(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+    let (candidate, application) = apply(&app, "candidate@example.com", &job).await;
+    let offer = make_offer(&app, &admin, &application).await;
+
+    assert_eq!(send(&app, set_status(&admin, &application, "Rejected")).await.0, StatusCode::OK);
+    assert_eq!(offer_status(&pool, &offer).await, "withdrawn");
+    assert_eq!(send(&app, respond(&candidate, &offer, "accept")).await.0, StatusCode::CONFLICT);
+
+    // A rejection is final.
+    let (status, body) = send(&app, set_status(&admin, &application, "UnderReview")).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+    assert_eq!(application_status(&pool, &application).await, "rejected");
+}
+
+#[sqlx::test]
+async fn offers_on_closed_applications_cannot_be_accepted(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+    let (candidate, application) = apply(&app, "candidate@example.com", &job).await;
+    let offer = make_offer(&app, &admin, &application).await;
+
+    // Closed behind the offer's back, as a concurrent accept on the same job would.
+    sqlx::query("UPDATE applications SET status = 'closed' WHERE id = $1::uuid")
+        .bind(application["id"].as_str().unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(send(&app, respond(&candidate, &offer, "accept")).await.0, StatusCode::CONFLICT);
+    assert_eq!(application_status(&pool, &application).await, "closed");
+    assert_eq!(offer_status(&pool, &offer).await, "pending");
+}
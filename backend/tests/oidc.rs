@@ -0,0 +1,318 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, web, App, Error, HttpResponse, HttpServer,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use careerhub_backend::{
+    config::{OidcConfig, OidcProviderConfig},
+    AppState,
+};
+use chrono::Utc;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::Url;
+use ring::{rand::SystemRandom, signature::{Ed25519KeyPair, KeyPair}};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::TcpListener,
+    sync::{mpsc, Arc, Mutex},
+};
+use uuid::Uuid;
+
+use common::{bearer, init_app_with, jwt_config, login, register, send};
+
+const CLIENT_ID: &str = "careerhub";
+const CLIENT_SECRET: &str = "client-secret";
+const REDIRECT_URI: &str = "http://localhost:3000/login/mock";
+
+/// An authorization the user granted at the mock provider, waiting to be redeemed.
+struct Grant {
+    code_challenge: String,
+    claims: Value,
+}
+
+/// A minimal OpenID Connect provider: discovery, a JWKS with one Ed25519 key
+/// and a token endpoint that checks the client and PKCE. The authorization
+/// endpoint is skipped; tests call [`MockProvider::authorize`] instead.
+struct MockProvider {
+    issuer: String,
+    key: Ed25519KeyPair,
+    pkcs8: Vec<u8>,
+    grants: Mutex<HashMap<String, Grant>>,
+}
+
+impl MockProvider {
+    /// Serves a fresh provider from its own thread and actix system.
+    fn start() -> Arc<MockProvider> {
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            actix_rt::System::new().block_on(async move {
+                let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+                let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap().as_ref().to_vec();
+                let provider = Arc::new(MockProvider {
+                    issuer: issuer.clone(),
+                    key: Ed25519KeyPair::from_pkcs8(&pkcs8).unwrap(),
+                    pkcs8,
+                    grants: Mutex::default(),
+                });
+
+                let data = web::Data::from(provider.clone());
+                let server = HttpServer::new(move || {
+                    App::new()
+                        .app_data(data.clone())
+                        .route("/.well-known/openid-configuration", web::get().to(discovery))
+                        .route("/jwks", web::get().to(jwks))
+                        .route("/token", web::post().to(token))
+                })
+                .workers(1)
+                .listen(listener)
+                .unwrap()
+                .run();
+
+                tx.send(provider).unwrap();
+                server.await.unwrap();
+            });
+        });
+
+        rx.recv().unwrap()
+    }
+
+    fn config(&self) -> OidcConfig {
+        OidcConfig {
+            providers: BTreeMap::from([(
+                "mock".to_string(),
+                OidcProviderConfig {
+                    issuer: self.issuer.clone(),
+                    client_id: CLIENT_ID.to_string(),
+                    client_secret: Some(CLIENT_SECRET.to_string()),
+                    redirect_uri: REDIRECT_URI.to_string(),
+                    scopes: vec!["openid".to_string(), "email".to_string()],
+                },
+            )]),
+            ..OidcConfig::default()
+        }
+    }
+
+    /// Plays the user signing in at `authorization_url`; returns the `code`
+    /// and `state` the provider would redirect back with. `claims` are merged
+    /// over the standard ones, so tests can override them.
+    fn authorize(&self, authorization_url: &str, claims: Value) -> (String, String) {
+        let url = Url::parse(authorization_url).unwrap();
+        assert!(authorization_url.starts_with(&format!("{}/authorize?", self.issuer)), "{}", url);
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["client_id"], CLIENT_ID);
+        assert_eq!(params["redirect_uri"], REDIRECT_URI);
+        assert_eq!(params["scope"], "openid email");
+        assert_eq!(params["code_challenge_method"], "S256");
+
+        let now = Utc::now().timestamp();
+        let mut merged = json!({
+            "iss": self.issuer,
+            "aud": CLIENT_ID,
+            "iat": now,
+            "exp": now + 300,
+            "nonce": params["nonce"],
+        });
+        merged.as_object_mut().unwrap().extend(claims.as_object().unwrap().clone());
+
+        let code = Uuid::new_v4().to_string();
+        self.grants.lock().unwrap().insert(
+            code.clone(),
+            Grant {
+                code_challenge: params["code_challenge"].clone(),
+                claims: merged,
+            },
+        );
+
+        (code, params["state"].clone())
+    }
+}
+
+async fn discovery(provider: web::Data<MockProvider>) -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "issuer": provider.issuer,
+        "authorization_endpoint": format!("{}/authorize", provider.issuer),
+        "token_endpoint": format!("{}/token", provider.issuer),
+        "jwks_uri": format!("{}/jwks", provider.issuer),
+    }))
+}
+
+async fn jwks(provider: web::Data<MockProvider>) -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "keys": [{
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "kid": "mock-key",
+            "alg": "EdDSA",
+            "use": "sig",
+            "x": URL_SAFE_NO_PAD.encode(provider.key.public_key().as_ref()),
+        }]
+    }))
+}
+
+#[derive(Deserialize)]
+struct TokenRequest {
+    grant_type: String,
+    code: String,
+    redirect_uri: String,
+    client_id: String,
+    client_secret: Option<String>,
+    code_verifier: String,
+}
+
+async fn token(provider: web::Data<MockProvider>, form: web::Form<TokenRequest>) -> HttpResponse {
+    let invalid_grant = HttpResponse::BadRequest().json(json!({ "error": "invalid_grant" }));
+
+    if form.client_id != CLIENT_ID || form.client_secret.as_deref() != Some(CLIENT_SECRET) {
+        return HttpResponse::Unauthorized().json(json!({ "error": "invalid_client" }));
+    }
+    if form.grant_type != "authorization_code" || form.redirect_uri != REDIRECT_URI {
+        return invalid_grant;
+    }
+
+    let Some(grant) = provider.grants.lock().unwrap().remove(&form.code) else {
+        return invalid_grant;
+    };
+    if URL_SAFE_NO_PAD.encode(Sha256::digest(form.code_verifier.as_bytes())) != grant.code_challenge {
+        return invalid_grant;
+    }
+
+    let mut header = Header::new(Algorithm::EdDSA);
+    header.kid = Some("mock-key".to_string());
+    let id_token = jsonwebtoken::encode(&header, &grant.claims, &EncodingKey::from_ed_der(&provider.pkcs8)).unwrap();
+
+    HttpResponse::Ok().json(json!({
+        "access_token": "mock-access-token",
+        "token_type": "Bearer",
+        "expires_in": 300,
+        "id_token": id_token,
+    }))
+}
+
+fn callback(code: &str, state: &str) -> Request {
+    test::TestRequest::post()
+        .uri("/api/auth/oidc/mock/callback")
+        .set_json(json!({ "code": code, "state": state }))
+        .to_request()
+}
+
+/// Starts a login and returns the authorization URL.
+async fn authorization_url<S, B>(app: &S) -> String
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::get().uri("/api/auth/oidc/mock").to_request();
+    let (status, body) = send(app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    body["authorization_url"].as_str().unwrap().to_string()
+}
+
+fn verified(sub: &str, email: &str) -> Value {
+    json!({ "sub": sub, "email": email, "email_verified": true, "name": "Carol Candidate" })
+}
+
+#[sqlx::test]
+async fn first_login_creates_an_account(pool: PgPool) {
+    let provider = MockProvider::start();
+    let state = AppState::new(pool, jwt_config()).with_oidc(&provider.config());
+    let app = init_app_with(&state).await;
+
+    let req = test::TestRequest::get().uri("/api/auth/oidc").to_request();
+    assert_eq!(send(&app, req).await.1, json!({ "providers": ["mock"] }));
+
+    let (code, login_state) = provider.authorize(&authorization_url(&app).await, verified("1001", "carol@example.com"));
+    let (status, body) = send(&app, callback(&code, &login_state)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["user"]["email"], "carol@example.com");
+    assert_eq!(body["user"]["name"], "Carol Candidate");
+    let user_id = body["user"]["id"].clone();
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/profile")
+        .insert_header(bearer(body["token"].as_str().unwrap()))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+
+    // A login state is good for one callback.
+    assert_eq!(send(&app, callback(&code, &login_state)).await.0, StatusCode::UNAUTHORIZED);
+
+    // The same provider account, even with a changed email, is the same user.
+    let (code, login_state) = provider.authorize(&authorization_url(&app).await, verified("1001", "carol@new.example"));
+    let (status, body) = send(&app, callback(&code, &login_state)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["user"]["id"], user_id);
+
+    // Without a password, the account cannot log in with one.
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "carol@example.com", "password": "" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn verified_emails_link_existing_accounts(pool: PgPool) {
+    let provider = MockProvider::start();
+    let state = AppState::new(pool, jwt_config()).with_oidc(&provider.config());
+    let app = init_app_with(&state).await;
+    let user_id = register(&app, "alice@example.com").await;
+
+    let claims = json!({ "sub": "2002", "email": "alice@example.com", "email_verified": false });
+    let (code, login_state) = provider.authorize(&authorization_url(&app).await, claims);
+    assert_eq!(send(&app, callback(&code, &login_state)).await.0, StatusCode::FORBIDDEN);
+
+    // Providers that send the flag as a string are understood too.
+    let claims = json!({ "sub": "2002", "email": "alice@example.com", "email_verified": "true" });
+    let (code, login_state) = provider.authorize(&authorization_url(&app).await, claims);
+    let (status, body) = send(&app, callback(&code, &login_state)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["user"]["id"], json!(user_id));
+
+    // The password keeps working, and a second account at the provider
+    // cannot claim the same user.
+    login(&app, "alice@example.com").await;
+    let (code, login_state) = provider.authorize(&authorization_url(&app).await, verified("2003", "alice@example.com"));
+    assert_eq!(send(&app, callback(&code, &login_state)).await.0, StatusCode::CONFLICT);
+}
+
+#[sqlx::test]
+async fn forged_logins_are_rejected(pool: PgPool) {
+    let provider = MockProvider::start();
+    let state = AppState::new(pool, jwt_config()).with_oidc(&provider.config());
+    let app = init_app_with(&state).await;
+
+    // A token meant for another client, or for another login.
+    for claims in [
+        json!({ "sub": "3003", "email": "eve@example.com", "email_verified": true, "aud": "someone-else" }),
+        json!({ "sub": "3003", "email": "eve@example.com", "email_verified": true, "nonce": "replayed" }),
+        json!({ "sub": "3003", "email": "eve@example.com", "email_verified": true, "iss": "https://evil.example" }),
+    ] {
+        let (code, login_state) = provider.authorize(&authorization_url(&app).await, claims.clone());
+        let (status, body) = send(&app, callback(&code, &login_state)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{} -> {}", claims, body);
+    }
+
+    // A code that was never issued, and a state that was never handed out.
+    let (_, login_state) = provider.authorize(&authorization_url(&app).await, verified("3003", "eve@example.com"));
+    assert_eq!(send(&app, callback("made-up", &login_state)).await.0, StatusCode::UNAUTHORIZED);
+    let (code, _) = provider.authorize(&authorization_url(&app).await, verified("3003", "eve@example.com"));
+    assert_eq!(send(&app, callback(&code, "made-up")).await.0, StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get().uri("/api/auth/oidc/unknown").to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,195 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest, ResponseError};
+use careerhub_backend::{error::AppError, openapi::document, versioning::ApiVersion};
+use regex::Regex;
+use serde_json::Value;
+use sqlx::PgPool;
+use std::{collections::BTreeSet, fs};
+use validator::ValidationErrors;
+
+use common::{init_app, send};
+
+/// Every `(METHOD, path)` the route modules register for `version`. Scope
+/// functions (`*_scope`) are mounted under the version's prefix and route
+/// functions (`*_routes`) at the root, as in `careerhub_backend::configure`.
+fn registered_routes(version: ApiVersion) -> BTreeSet<(String, String)> {
+    let function = Regex::new(r"(?m)^pub fn (\w+)\(").unwrap();
+    let scope = Regex::new(r#"web::scope\("([^"]*)"\)"#).unwrap();
+    let route = Regex::new(r#"\.route\(\s*"([^"]*)",\s*web::(\w+)\(\)"#).unwrap();
+
+    let dir = format!("{}/src/routes", env!("CARGO_MANIFEST_DIR"));
+    let mut routes = BTreeSet::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let source = fs::read_to_string(entry.unwrap().path()).unwrap();
+        let starts: Vec<_> = function.captures_iter(&source).map(|c| (c.get(0).unwrap().start(), c[1].to_string())).collect();
+
+        for (i, (start, name)) in starts.iter().enumerate() {
+            let end = starts.get(i + 1).map_or(source.len(), |(next, _)| *next);
+            let body = &source[*start..end];
+
+            let prefix = if name.ends_with("_scope") {
+                format!("{}{}", version.prefix(), &scope.captures(body).expect("scope function without a scope")[1])
+            } else {
+                String::new()
+            };
+            for r in route.captures_iter(body) {
+                routes.insert((r[2].to_uppercase(), format!("{}{}", prefix, &r[1])));
+            }
+        }
+    }
+
+    routes
+}
+
+fn documented_routes(spec: &Value) -> BTreeSet<(String, String)> {
+    spec["paths"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .flat_map(|(path, operations)| {
+            operations.as_object().unwrap().keys().map(move |method| (method.to_uppercase(), path.clone()))
+        })
+        .collect()
+}
+
+#[test]
+fn every_route_is_documented() {
+    for version in ApiVersion::ALL {
+        let spec = serde_json::to_value(document(version)).unwrap();
+        let registered = registered_routes(version);
+        let documented = documented_routes(&spec);
+        let prefix = version.prefix();
+
+        // Guards the scan itself: a missed route would pass unnoticed.
+        assert!(registered.contains(&("POST".to_string(), format!("{}/auth/login", prefix))));
+        assert!(registered.contains(&(
+            "POST".to_string(),
+            format!("{}/companies/{{company_id}}/webhooks/{{webhook_id}}/deliveries/{{delivery_id}}/redeliver", prefix)
+        )));
+        assert!(registered.contains(&("GET".to_string(), "/healthz".to_string())));
+
+        let undocumented: Vec<_> = registered.difference(&documented).collect();
+        assert!(undocumented.is_empty(), "Routes missing from the {} document: {:?}", version, undocumented);
+
+        let stale: Vec<_> = documented.difference(&registered).collect();
+        assert!(stale.is_empty(), "Routes in the {} document that are not served: {:?}", version, stale);
+    }
+}
+
+#[test]
+fn only_public_routes_skip_authentication() {
+    for version in ApiVersion::ALL {
+        let spec = serde_json::to_value(document(version)).unwrap();
+        let prefix = version.prefix();
+
+        assert_eq!(spec["security"][0]["bearer"], Value::Array(vec![]));
+        for (path, operations) in spec["paths"].as_object().unwrap() {
+            let public = match path.strip_prefix(&prefix) {
+                Some(rest) => rest.starts_with("/auth/") || rest.starts_with("/public/"),
+                None => true,
+            };
+            for (method, operation) in operations.as_object().unwrap() {
+                let anonymous = operation["security"].as_array().is_some_and(|s| s.iter().any(|r| r == &Value::Object(Default::default())));
+                assert_eq!(anonymous, public, "{} {}", method, path);
+            }
+        }
+    }
+}
+
+/// Every status an [`AppError`] can be answered with.
+fn error_statuses() -> BTreeSet<String> {
+    [
+        AppError::BadRequest(String::new()),
+        AppError::Validation(ValidationErrors::new()),
+        AppError::Unauthorized(String::new()),
+        AppError::Forbidden(String::new()),
+        AppError::NotFound(String::new()),
+        AppError::Conflict(String::new()),
+        AppError::TooManyRequests { message: String::new(), retry_after_secs: 1 },
+        AppError::Database(sqlx::Error::RowNotFound),
+        AppError::Database(sqlx::Error::PoolTimedOut),
+        AppError::Internal(String::new()),
+    ]
+    .iter()
+    .map(|error| error.status_code().as_u16().to_string())
+    .collect()
+}
+
+#[test]
+fn documented_errors_are_ones_handlers_return() {
+    let statuses = error_statuses();
+    // Answered by the handler itself rather than through `AppError`.
+    let exempt = [("/readyz", "503")];
+
+    for version in ApiVersion::ALL {
+        let spec = serde_json::to_value(document(version)).unwrap();
+        for (path, operations) in spec["paths"].as_object().unwrap() {
+            for (method, operation) in operations.as_object().unwrap() {
+                for (status, response) in operation["responses"].as_object().unwrap() {
+                    if status.as_str() < "400" || exempt.contains(&(path.as_str(), status.as_str())) {
+                        continue;
+                    }
+
+                    assert!(statuses.contains(status), "{} {} documents {}, which no AppError maps to", method, path, status);
+                    assert_eq!(
+                        response["content"]["application/json"]["schema"]["$ref"],
+                        "#/components/schemas/ErrorBody",
+                        "{} {} {}",
+                        method,
+                        path,
+                        status
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[sqlx::test]
+async fn the_documents_are_served_with_swagger_ui(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let req = TestRequest::get().uri("/api/v1/openapi.json").to_request();
+    let (status, spec) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+
+    let schemas = &spec["components"]["schemas"];
+    for name in ["CreateJobDto", "Job", "Company", "User", "Application", "ErrorBody"] {
+        assert!(schemas[name].is_object(), "missing schema {}", name);
+    }
+    assert_eq!(schemas["JobType"]["enum"][0], "Fulltime");
+    assert_eq!(spec["components"]["securitySchemes"]["bearer"]["scheme"], "bearer");
+
+    let list_jobs = &spec["paths"]["/api/v1/jobs"]["get"];
+    let params: Vec<_> = list_jobs["parameters"].as_array().unwrap().iter().map(|p| p["name"].as_str().unwrap()).collect();
+    assert!(params.contains(&"skills") && params.contains(&"per_page"), "{:?}", params);
+    assert_eq!(
+        list_jobs["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"],
+        "#/components/schemas/Job"
+    );
+    assert!(spec["paths"]["/api/v1/companies/{company_id}"]["get"]["parameters"][0]["name"] == "company_id");
+
+    // Secrets never shown in responses stay out of the schema too.
+    assert!(schemas["ApiKey"]["properties"].get("key_hash").is_none());
+    assert!(schemas["WebhookEndpoint"]["properties"].get("secret").is_none());
+
+    // v2 replaces the job list and keeps everything else.
+    let req = TestRequest::get().uri("/api/v2/openapi.json").to_request();
+    let (status, spec) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let jobs = &spec["paths"]["/api/v2/jobs"];
+    assert_eq!(
+        jobs["get"]["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/JobPage"
+    );
+    assert!(jobs["post"].is_object());
+    assert!(spec["paths"]["/api/v2/companies/{company_id}"]["get"].is_object());
+    assert!(spec["paths"].get("/api/v1/jobs").is_none());
+
+    let resp = actix_web::test::call_service(&app, TestRequest::get().uri("/api/docs/").to_request()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = actix_web::test::read_body(resp).await;
+    assert!(String::from_utf8_lossy(&html).contains("swagger-ui"));
+}
@@ -0,0 +1,247 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use async_trait::async_trait;
+use careerhub_backend::{
+    config::{Config, QueueConfig},
+    error::AppError,
+    queue::{tasks, Job, JobContext, Worker},
+    AppState,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use common::{admin_token, bearer, init_app_with, jwt_config, register_and_login, send};
+
+/// Ids of the test jobs run so far, once per run. Tests run concurrently, so
+/// each job carries an id of its own.
+static RUNS: Mutex<Vec<Uuid>> = Mutex::new(Vec::new());
+
+fn runs(id: Uuid) -> usize {
+    RUNS.lock().unwrap().iter().filter(|run| **run == id).count()
+}
+
+/// Fails its first `failures` runs.
+#[derive(Serialize, Deserialize)]
+struct Flaky {
+    id: Uuid,
+    failures: usize,
+}
+
+impl Flaky {
+    fn new(failures: usize) -> Self {
+        Self { id: Uuid::new_v4(), failures }
+    }
+}
+
+#[async_trait]
+impl Job for Flaky {
+    const KIND: &'static str = "test.flaky";
+    const MAX_ATTEMPTS: i32 = 2;
+
+    async fn run(self, _ctx: &JobContext) -> Result<(), AppError> {
+        RUNS.lock().unwrap().push(self.id);
+        let run = runs(self.id);
+
+        if run <= self.failures {
+            Err(AppError::Internal(format!("Failure {}", run)))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn worker(state: &AppState) -> Worker {
+    Worker::new(state, &QueueConfig { backoff_base_secs: 60, ..QueueConfig::default() }).register::<Flaky>()
+}
+
+async fn job_row(pool: &PgPool, id: Uuid) -> Option<(String, i32, DateTime<Utc>, Option<String>)> {
+    sqlx::query_as("SELECT status::text, attempts, run_at, last_error FROM background_jobs WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .unwrap()
+}
+
+/// Makes every pending job due now instead of after its backoff.
+async fn make_due(pool: &PgPool) {
+    sqlx::query("UPDATE background_jobs SET run_at = NOW() WHERE status = 'pending'")
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn jobs_run_once_and_are_removed(pool: PgPool) {
+    let state = AppState::new(pool.clone(), jwt_config());
+    let worker = worker(&state);
+
+    let job = Flaky::new(0);
+    let id = state.queue.enqueue(&job).await.unwrap();
+
+    assert!(worker.work_one().await.unwrap());
+    assert_eq!(runs(job.id), 1);
+    assert!(job_row(&pool, id).await.is_none());
+    assert!(!worker.work_one().await.unwrap());
+}
+
+#[sqlx::test]
+async fn failed_jobs_back_off_and_are_dead_lettered(pool: PgPool) {
+    let state = AppState::new(pool.clone(), jwt_config());
+    let app = init_app_with(&state).await;
+    let admin = admin_token(&app, &pool).await;
+    let worker = worker(&state);
+
+    let job = Flaky::new(2);
+    let id = state.queue.enqueue(&job).await.unwrap();
+
+    assert!(worker.work_one().await.unwrap());
+    let (status, attempts, run_at, last_error) = job_row(&pool, id).await.unwrap();
+    assert_eq!(status, "pending");
+    assert_eq!(attempts, 1);
+    assert!(run_at > Utc::now() + Duration::seconds(50));
+    assert_eq!(last_error.as_deref(), Some("Internal server error: Failure 1"));
+
+    // Backing off: not due again yet.
+    assert!(!worker.work_one().await.unwrap());
+
+    make_due(&pool).await;
+    assert!(worker.work_one().await.unwrap());
+    let (status, attempts, _, last_error) = job_row(&pool, id).await.unwrap();
+    assert_eq!(status, "dead");
+    assert_eq!(attempts, 2);
+    assert_eq!(last_error.as_deref(), Some("Internal server error: Failure 2"));
+    assert!(!worker.work_one().await.unwrap());
+
+    let req = test::TestRequest::get().uri("/api/admin/queue?status=dead").insert_header(bearer(&admin)).to_request();
+    let (status, jobs) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(jobs.as_array().unwrap().len(), 1);
+    assert_eq!(jobs[0]["id"], id.to_string());
+    assert_eq!(jobs[0]["kind"], "test.flaky");
+
+    let (_, user) = register_and_login(&app, "user@example.com").await;
+    let req = test::TestRequest::get().uri("/api/admin/queue").insert_header(bearer(&user)).to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+    let retry = |token: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/api/admin/queue/{}/retry", id))
+            .insert_header(bearer(token))
+            .to_request()
+    };
+    assert_eq!(send(&app, retry(&user)).await.0, StatusCode::FORBIDDEN);
+
+    let (status, retried) = send(&app, retry(&admin)).await;
+    assert_eq!(status, StatusCode::OK, "{}", retried);
+    assert_eq!(retried["status"], "pending");
+    assert_eq!(retried["attempts"], 0);
+    // Only dead jobs can be retried.
+    assert_eq!(send(&app, retry(&admin)).await.0, StatusCode::NOT_FOUND);
+
+    assert!(worker.work_one().await.unwrap());
+    assert_eq!(runs(job.id), 3);
+    assert!(job_row(&pool, id).await.is_none());
+
+    let action: String = sqlx::query_scalar("SELECT action FROM audit_events WHERE entity_type = 'background_job'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(action, "retry");
+}
+
+#[sqlx::test]
+async fn scheduled_jobs_wait_until_they_are_due(pool: PgPool) {
+    let state = AppState::new(pool.clone(), jwt_config());
+    let worker = worker(&state);
+
+    let job = Flaky::new(0);
+    state.queue.enqueue_at(&job, Utc::now() + Duration::hours(1)).await.unwrap();
+    assert!(!worker.work_one().await.unwrap());
+
+    make_due(&pool).await;
+    assert!(worker.work_one().await.unwrap());
+    assert_eq!(runs(job.id), 1);
+}
+
+#[sqlx::test]
+async fn unique_jobs_are_queued_once_at_a_time(pool: PgPool) {
+    let state = AppState::new(pool.clone(), jwt_config());
+    let worker = worker(&state);
+
+    let first = state.queue.enqueue_unique(&Flaky::new(0), "nightly").await.unwrap();
+    assert!(first.is_some());
+    assert_eq!(state.queue.enqueue_unique(&Flaky::new(0), "nightly").await.unwrap(), None);
+    assert!(state.queue.enqueue_unique(&Flaky::new(0), "hourly").await.unwrap().is_some());
+
+    assert!(worker.work_one().await.unwrap());
+    assert!(worker.work_one().await.unwrap());
+    assert!(!worker.work_one().await.unwrap());
+
+    // Once finished, the key is free again.
+    assert!(state.queue.enqueue_unique(&Flaky::new(0), "nightly").await.unwrap().is_some());
+}
+
+#[sqlx::test]
+async fn jobs_lost_with_their_worker_are_run_again(pool: PgPool) {
+    let state = AppState::new(pool.clone(), jwt_config());
+    let worker = worker(&state);
+
+    let running = Flaky::new(0);
+    let running_id = state.queue.enqueue(&running).await.unwrap();
+    let lost = Flaky::new(0);
+    let lost_id = state.queue.enqueue(&lost).await.unwrap();
+    let exhausted = Flaky::new(0);
+    let exhausted_id = state.queue.enqueue(&exhausted).await.unwrap();
+
+    let take = |id: Uuid, attempts: i32, locked_until: DateTime<Utc>| {
+        sqlx::query("UPDATE background_jobs SET status = 'running', attempts = $2, locked_until = $3 WHERE id = $1")
+            .bind(id)
+            .bind(attempts)
+            .bind(locked_until)
+            .execute(&pool)
+    };
+    take(running_id, 1, Utc::now() + Duration::minutes(5)).await.unwrap();
+    take(lost_id, 1, Utc::now() - Duration::minutes(1)).await.unwrap();
+    take(exhausted_id, Flaky::MAX_ATTEMPTS, Utc::now() - Duration::minutes(1)).await.unwrap();
+
+    assert!(worker.work_one().await.unwrap());
+    assert!(worker.work_one().await.unwrap());
+    assert!(!worker.work_one().await.unwrap());
+
+    assert_eq!(runs(lost.id), 1);
+    assert!(job_row(&pool, lost_id).await.is_none());
+
+    // Lost on its last attempt: not run again.
+    assert_eq!(runs(exhausted.id), 0);
+    let (status, _, _, last_error) = job_row(&pool, exhausted_id).await.unwrap();
+    assert_eq!(status, "dead");
+    assert_eq!(last_error.as_deref(), Some("Lease expired on the last attempt"));
+
+    // Still within its lease.
+    assert_eq!(runs(running.id), 0);
+    assert_eq!(job_row(&pool, running_id).await.unwrap().0, "running");
+}
+
+#[sqlx::test]
+async fn workers_only_claim_the_kinds_they_run(pool: PgPool) {
+    let state = AppState::new(pool.clone(), jwt_config());
+
+    let id = state.queue.enqueue(&tasks::ExpireOffers).await.unwrap();
+    assert!(!worker(&state).work_one().await.unwrap());
+    assert_eq!(job_row(&pool, id).await.unwrap().0, "pending");
+
+    let payload: Value = sqlx::query_scalar("SELECT payload FROM background_jobs WHERE id = $1")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(payload, Value::Null);
+
+    let worker = tasks::worker(&state, &Config::default());
+    assert!(worker.work_one().await.unwrap());
+    assert!(job_row(&pool, id).await.is_none());
+}
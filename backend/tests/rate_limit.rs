@@ -0,0 +1,193 @@
+mod common;
+
+use actix_web::{
+    http::{header, StatusCode},
+    test,
+};
+use careerhub_backend::{
+    config::{Quota, RateLimitConfig},
+    error::AppError,
+    rate_limit::{Counter, MemoryStore, PgRateLimitStore, RateLimitStore, RateLimiter},
+    AppState,
+};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use common::{
+    bearer, create_company, create_job, init_app_with, jwt_config, register, register_and_login, PASSWORD,
+};
+
+fn limiter(config: RateLimitConfig) -> RateLimiter {
+    RateLimiter::new(config, Arc::new(MemoryStore::default()))
+}
+
+fn login_from(ip: &str, email: &str, password: &str) -> actix_http::Request {
+    test::TestRequest::post()
+        .uri("/api/auth/login")
+        .peer_addr(format!("{}:40000", ip).parse().unwrap())
+        .set_json(json!({ "email": email, "password": password }))
+        .to_request()
+}
+
+/// The `Retry-After` of a refusal from [`RateLimiter::check_login`].
+async fn lockout_secs(limiter: &RateLimiter, email: &str) -> Option<u64> {
+    match limiter.check_login(email).await {
+        Ok(()) => None,
+        Err(AppError::TooManyRequests { retry_after_secs, .. }) => Some(retry_after_secs),
+        Err(e) => panic!("unexpected error: {}", e),
+    }
+}
+
+#[sqlx::test]
+async fn logins_are_limited_per_address(pool: PgPool) {
+    let config = RateLimitConfig {
+        login_per_ip: Quota::new(3, 60),
+        lockout_threshold: 100,
+        ..RateLimitConfig::default()
+    };
+    let state = AppState::new(pool, jwt_config()).with_rate_limiter(limiter(config));
+    let app = init_app_with(&state).await;
+
+    for _ in 0..3 {
+        let resp = test::call_service(&app, login_from("10.0.0.1", "nobody@example.com", "wrong")).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let resp = test::call_service(&app, login_from("10.0.0.1", "nobody@example.com", "wrong")).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = resp.headers().get(header::RETRY_AFTER).unwrap().to_str().unwrap().parse().unwrap();
+    assert!((1..=61).contains(&retry_after), "Retry-After: {}", retry_after);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "rate_limited");
+
+    // Other clients are unaffected.
+    let resp = test::call_service(&app, login_from("10.0.0.2", "nobody@example.com", "wrong")).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn failed_logins_lock_the_account_from_every_address(pool: PgPool) {
+    let config = RateLimitConfig {
+        lockout_threshold: 3,
+        lockout_base_secs: 60,
+        ..RateLimitConfig::default()
+    };
+    let state = AppState::new(pool, jwt_config()).with_rate_limiter(limiter(config));
+    let app = init_app_with(&state).await;
+    register(&app, "alice@example.com").await;
+
+    for ip in ["10.0.0.1", "10.0.0.2", "10.0.0.3"] {
+        let resp = test::call_service(&app, login_from(ip, "alice@example.com", "wrong")).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    // Even the right password is refused while locked out.
+    let resp = test::call_service(&app, login_from("10.0.0.4", "Alice@Example.com", PASSWORD)).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = resp.headers().get(header::RETRY_AFTER).unwrap().to_str().unwrap().parse().unwrap();
+    assert!((59..=61).contains(&retry_after), "Retry-After: {}", retry_after);
+}
+
+#[actix_rt::test]
+async fn lockouts_double_up_to_the_maximum() {
+    let limiter = limiter(RateLimitConfig {
+        lockout_threshold: 2,
+        lockout_base_secs: 60,
+        lockout_max_secs: 200,
+        ..RateLimitConfig::default()
+    });
+
+    limiter.login_failed("alice@example.com").await.unwrap();
+    assert_eq!(lockout_secs(&limiter, "alice@example.com").await, None);
+
+    let mut lockouts = Vec::new();
+    for _ in 0..3 {
+        limiter.login_failed("alice@example.com").await.unwrap();
+        lockouts.push(lockout_secs(&limiter, "alice@example.com").await.unwrap());
+    }
+
+    assert!((59..=61).contains(&lockouts[0]), "{:?}", lockouts);
+    assert!((119..=121).contains(&lockouts[1]), "{:?}", lockouts);
+    assert!((199..=201).contains(&lockouts[2]), "{:?}", lockouts);
+}
+
+#[actix_rt::test]
+async fn a_successful_login_forgets_earlier_failures() {
+    let limiter = limiter(RateLimitConfig {
+        lockout_threshold: 2,
+        ..RateLimitConfig::default()
+    });
+
+    limiter.login_failed("alice@example.com").await.unwrap();
+    limiter.login_succeeded("alice@example.com").await.unwrap();
+    limiter.login_failed("alice@example.com").await.unwrap();
+
+    assert_eq!(lockout_secs(&limiter, "alice@example.com").await, None);
+}
+
+#[sqlx::test]
+async fn applications_are_limited_per_user(pool: PgPool) {
+    let config = RateLimitConfig {
+        applications_per_user: Quota::new(2, 60 * 60),
+        ..RateLimitConfig::default()
+    };
+    let state = AppState::new(pool, jwt_config()).with_rate_limiter(limiter(config));
+    let app = init_app_with(&state).await;
+
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let company = create_company(&app, &token, "Acme").await;
+    let mut jobs = Vec::new();
+    for _ in 0..3 {
+        jobs.push(create_job(&app, &token, &company["id"]).await);
+    }
+
+    let apply = |job: &serde_json::Value, token: &str| {
+        test::TestRequest::post()
+            .uri("/api/applications")
+            .insert_header(bearer(token))
+            .set_json(json!({ "job_id": job["id"], "resume_url": "https://example.com/cv.pdf" }))
+            .to_request()
+    };
+
+    for job in &jobs[..2] {
+        assert_eq!(test::call_service(&app, apply(job, &token)).await.status(), StatusCode::CREATED);
+    }
+    assert_eq!(
+        test::call_service(&app, apply(&jobs[2], &token)).await.status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    // Listing is not limited, nor are other users.
+    let req = test::TestRequest::get()
+        .uri("/api/applications")
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let (_, other) = register_and_login(&app, "bob@example.com").await;
+    assert_eq!(test::call_service(&app, apply(&jobs[2], &other)).await.status(), StatusCode::CREATED);
+}
+
+#[sqlx::test]
+async fn postgres_store_counts_within_a_window(pool: PgPool) {
+    let store = PgRateLimitStore::new(pool);
+
+    assert_eq!(store.increment("k", StdDuration::from_secs(60)).await.unwrap().count, 1);
+    let counter = store.increment("k", StdDuration::from_secs(60)).await.unwrap();
+    assert_eq!(counter.count, 2);
+    assert_eq!(store.get("k").await.unwrap(), Some(counter));
+
+    // An expired counter restarts.
+    let expired = Counter { count: 7, expires_at: Utc::now() - Duration::seconds(1) };
+    store.set("k", expired).await.unwrap();
+    assert_eq!(store.get("k").await.unwrap(), None);
+    assert_eq!(store.increment("k", StdDuration::from_secs(60)).await.unwrap().count, 1);
+
+    store.set("old", expired).await.unwrap();
+    assert_eq!(store.purge_expired().await.unwrap(), 1);
+
+    store.remove("k").await.unwrap();
+    assert_eq!(store.get("k").await.unwrap(), None);
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, Error,
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use common::{admin_token, bearer, create_company, init_app, register_and_login, send};
+
+fn review(rating: i16) -> Value {
+    json!({
+        "title": "Good place to grow",
+        "overall_rating": rating,
+        "pros": "Kind people",
+        "cons": "Long meetings",
+        "employment_status": "Current",
+    })
+}
+
+fn moderate(admin: &str, review: &Value, status: &str) -> Request {
+    test::TestRequest::put()
+        .uri(&format!("/api/reviews/{}/moderation", review["id"].as_str().unwrap()))
+        .insert_header(bearer(admin))
+        .set_json(json!({ "status": status }))
+        .to_request()
+}
+
+/// The company's cached `(rating_average, review_count)`.
+async fn rating<S, B>(app: &S, token: &str, company: &Value) -> (Value, Value)
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/companies/{}", company["id"].as_str().unwrap()))
+        .insert_header(bearer(token))
+        .to_request();
+    let (status, company) = send(app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", company);
+
+    (company["rating_average"].clone(), company["review_count"].clone())
+}
+
+#[sqlx::test]
+async fn only_approved_reviews_count_towards_the_rating(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let reviews_uri = format!("/api/companies/{}/reviews", company["id"].as_str().unwrap());
+
+    let (_, alice) = register_and_login(&app, "alice@example.com").await;
+    let (_, bob) = register_and_login(&app, "bob@example.com").await;
+    let mut submitted = Vec::new();
+    for (token, stars) in [(&alice, 4), (&bob, 2)] {
+        let req = test::TestRequest::post()
+            .uri(&reviews_uri)
+            .insert_header(bearer(token))
+            .set_json(review(stars))
+            .to_request();
+        let (status, body) = send(&app, req).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        assert_eq!(body["status"], "Pending");
+        submitted.push(body);
+    }
+    let (alice_review, bob_review) = (&submitted[0], &submitted[1]);
+
+    // Nothing is public or counted until moderated.
+    let req = test::TestRequest::get().uri(&reviews_uri).insert_header(bearer(&alice)).to_request();
+    assert_eq!(send(&app, req).await.1, json!([]));
+    assert_eq!(rating(&app, &alice, &company).await, (Value::Null, json!(0)));
+
+    assert_eq!(send(&app, moderate(&alice, bob_review, "Approved")).await.0, StatusCode::FORBIDDEN);
+
+    let req = test::TestRequest::get().uri("/api/reviews").insert_header(bearer(&admin)).to_request();
+    let (status, queue) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", queue);
+    assert_eq!(queue.as_array().unwrap().len(), 2);
+
+    let (status, approved) = send(&app, moderate(&admin, alice_review, "Approved")).await;
+    assert_eq!(status, StatusCode::OK, "{}", approved);
+    assert_eq!(approved["status"], "Approved");
+    assert_eq!(rating(&app, &alice, &company).await, (json!(4.0), json!(1)));
+
+    assert_eq!(send(&app, moderate(&admin, bob_review, "Approved")).await.0, StatusCode::OK);
+    assert_eq!(rating(&app, &alice, &company).await, (json!(3.0), json!(2)));
+
+    // An edit goes back through moderation and leaves the rating until approved.
+    let req = test::TestRequest::put()
+        .uri(&format!("{}/{}", reviews_uri, alice_review["id"].as_str().unwrap()))
+        .insert_header(bearer(&alice))
+        .set_json(review(5))
+        .to_request();
+    let (status, edited) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", edited);
+    assert_eq!(edited["status"], "Pending");
+    assert_eq!(rating(&app, &alice, &company).await, (json!(2.0), json!(1)));
+
+    assert_eq!(send(&app, moderate(&admin, bob_review, "Rejected")).await.0, StatusCode::OK);
+    assert_eq!(rating(&app, &alice, &company).await, (Value::Null, json!(0)));
+
+    assert_eq!(send(&app, moderate(&admin, alice_review, "Approved")).await.0, StatusCode::OK);
+    assert_eq!(rating(&app, &alice, &company).await, (json!(5.0), json!(1)));
+    let req = test::TestRequest::get().uri(&reviews_uri).insert_header(bearer(&bob)).to_request();
+    let (_, public) = send(&app, req).await;
+    assert_eq!(public.as_array().unwrap().len(), 1);
+    assert_eq!(public[0]["id"], alice_review["id"]);
+
+    // Deleting an approved review takes it out again.
+    let req = test::TestRequest::delete()
+        .uri(&format!("{}/{}", reviews_uri, alice_review["id"].as_str().unwrap()))
+        .insert_header(bearer(&alice))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NO_CONTENT);
+    assert_eq!(rating(&app, &alice, &company).await, (Value::Null, json!(0)));
+}
+
+#[sqlx::test]
+async fn users_review_a_company_once(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let reviews_uri = format!("/api/companies/{}/reviews", company["id"].as_str().unwrap());
+    let (_, alice) = register_and_login(&app, "alice@example.com").await;
+
+    let submit = || {
+        test::TestRequest::post()
+            .uri(&reviews_uri)
+            .insert_header(bearer(&alice))
+            .set_json(review(4))
+            .to_request()
+    };
+    assert_eq!(send(&app, submit()).await.0, StatusCode::CREATED);
+    let (status, body) = send(&app, submit()).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["message"], "You have already reviewed this company");
+}
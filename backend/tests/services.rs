@@ -0,0 +1,229 @@
+//! Services against in-memory repositories, without a database.
+
+use async_trait::async_trait;
+use careerhub_backend::{
+    error::AppError,
+    models::{
+        applications::{Application, ApplicationStatus},
+        offers::{CreateOfferDto, Offer, OfferParties, OfferStatus, OfferTarget},
+        users::{User, UserRole},
+    },
+    repositories::OfferRepository,
+    services::OfferService,
+};
+use chrono::{Duration, Utc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use uuid::Uuid;
+
+/// Offers and the parties of their applications, kept in memory.
+#[derive(Default)]
+struct FakeOfferRepository {
+    offers: Mutex<Vec<Offer>>,
+    parties: Mutex<HashMap<Uuid, OfferParties>>,
+}
+
+impl FakeOfferRepository {
+    /// Adds a pending offer from `company_id` to `candidate_id`, expiring in `expires_in`.
+    fn add(&self, candidate_id: Uuid, company_id: Uuid, expires_in: Duration) -> Offer {
+        let now = Utc::now();
+        let offer = Offer {
+            id: Uuid::new_v4(),
+            application_id: Uuid::new_v4(),
+            job_id: Uuid::new_v4(),
+            created_by: Uuid::new_v4(),
+            salary_amount: 80_000,
+            salary_currency: "EUR".to_string(),
+            start_date: now.date_naive(),
+            expires_at: now + expires_in,
+            terms_url: None,
+            status: OfferStatus::Pending,
+            responded_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.parties
+            .lock()
+            .unwrap()
+            .insert(offer.application_id, OfferParties { candidate_id, company_id });
+        self.offers.lock().unwrap().push(offer.clone());
+
+        offer
+    }
+
+    fn status(&self, id: Uuid) -> OfferStatus {
+        self.offers.lock().unwrap().iter().find(|o| o.id == id).unwrap().status
+    }
+}
+
+#[async_trait]
+impl OfferRepository for FakeOfferRepository {
+    async fn list(&self, user_id: Uuid, company_id: Option<Uuid>, all: bool) -> Result<Vec<Offer>, sqlx::Error> {
+        let parties = self.parties.lock().unwrap();
+        Ok(self
+            .offers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|o| {
+                let p = &parties[&o.application_id];
+                all || p.candidate_id == user_id || Some(p.company_id) == company_id
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Offer>, sqlx::Error> {
+        Ok(self.offers.lock().unwrap().iter().find(|o| o.id == id).cloned())
+    }
+
+    async fn find_parties(&self, application_id: Uuid) -> Result<Option<OfferParties>, sqlx::Error> {
+        Ok(self.parties.lock().unwrap().get(&application_id).copied())
+    }
+
+    async fn find_target(&self, _application_id: Uuid) -> Result<Option<OfferTarget>, sqlx::Error> {
+        Ok(None)
+    }
+
+    async fn create(
+        &self,
+        _created_by: Uuid,
+        _job_id: Uuid,
+        _currency: &str,
+        _dto: &CreateOfferDto,
+    ) -> Result<Offer, sqlx::Error> {
+        Err(sqlx::Error::RowNotFound)
+    }
+
+    async fn accept(&self, id: Uuid) -> Result<Option<(Offer, Vec<Application>)>, sqlx::Error> {
+        let mut offers = self.offers.lock().unwrap();
+        let Some(offer) = offers
+            .iter_mut()
+            .find(|o| o.id == id && o.status == OfferStatus::Pending && o.expires_at > Utc::now())
+        else {
+            return Ok(None);
+        };
+
+        offer.status = OfferStatus::Accepted;
+        offer.responded_at = Some(Utc::now());
+
+        let application = Application {
+            id: offer.application_id,
+            user_id: self.parties.lock().unwrap()[&offer.application_id].candidate_id,
+            job_id: offer.job_id,
+            status: ApplicationStatus::Accepted,
+            resume_url: "https://example.com/cv.pdf".to_string(),
+            cover_letter: None,
+            created_at: offer.created_at,
+            updated_at: Utc::now(),
+        };
+
+        Ok(Some((offer.clone(), vec![application])))
+    }
+
+    async fn respond(&self, id: Uuid, status: OfferStatus) -> Result<Option<Offer>, sqlx::Error> {
+        let mut offers = self.offers.lock().unwrap();
+        let Some(offer) = offers.iter_mut().find(|o| o.id == id && o.status == OfferStatus::Pending) else {
+            return Ok(None);
+        };
+
+        offer.status = status;
+        offer.responded_at = Some(Utc::now());
+
+        Ok(Some(offer.clone()))
+    }
+
+    async fn expire_overdue(&self) -> Result<u64, sqlx::Error> {
+        let mut expired = 0;
+        for offer in self.offers.lock().unwrap().iter_mut() {
+            if offer.status == OfferStatus::Pending && offer.expires_at <= Utc::now() {
+                offer.status = OfferStatus::Expired;
+                expired += 1;
+            }
+        }
+
+        Ok(expired)
+    }
+}
+
+fn user(role: UserRole, company_id: Option<Uuid>) -> User {
+    User {
+        id: Uuid::new_v4(),
+        email: "user@example.com".to_string(),
+        password_hash: None,
+        name: "Test User".to_string(),
+        role,
+        company_id,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn service() -> (Arc<FakeOfferRepository>, OfferService) {
+    let repository = Arc::new(FakeOfferRepository::default());
+    (repository.clone(), OfferService::new(repository))
+}
+
+#[tokio::test]
+async fn only_the_candidate_accepts_an_offer() {
+    let (repository, offers) = service();
+    let candidate = user(UserRole::User, None);
+    let company_id = Uuid::new_v4();
+    let offer = repository.add(candidate.id, company_id, Duration::days(7));
+
+    let recruiter = user(UserRole::Recruiter, Some(company_id));
+    assert!(matches!(offers.accept(recruiter.id, offer.id).await, Err(AppError::NotFound(_))));
+    assert_eq!(repository.status(offer.id), OfferStatus::Pending);
+
+    let accepted = offers.accept(candidate.id, offer.id).await.unwrap();
+    assert_eq!(accepted.offer.before.status, OfferStatus::Pending);
+    assert_eq!(accepted.offer.after.status, OfferStatus::Accepted);
+    assert_eq!(accepted.company_id, company_id);
+    assert_eq!(accepted.applications[0].id, offer.application_id);
+
+    // Answered offers stay answered.
+    assert!(matches!(offers.decline(candidate.id, offer.id).await, Err(AppError::Conflict(_))));
+    assert!(matches!(offers.withdraw(&recruiter, offer.id).await, Err(AppError::Conflict(_))));
+}
+
+#[tokio::test]
+async fn responding_to_an_overdue_offer_expires_it() {
+    let (repository, offers) = service();
+    let candidate = user(UserRole::User, None);
+    let offer = repository.add(candidate.id, Uuid::new_v4(), Duration::minutes(-1));
+
+    match offers.accept(candidate.id, offer.id).await {
+        Err(AppError::Conflict(message)) => assert_eq!(message, "Offer has expired"),
+        other => panic!("expected a conflict, got {:?}", other),
+    }
+    assert_eq!(repository.status(offer.id), OfferStatus::Expired);
+}
+
+#[tokio::test]
+async fn offers_are_visible_to_their_parties_only() {
+    let (repository, offers) = service();
+    let candidate = user(UserRole::User, None);
+    let company_id = Uuid::new_v4();
+    let offer = repository.add(candidate.id, company_id, Duration::days(7));
+    repository.add(Uuid::new_v4(), Uuid::new_v4(), Duration::days(7));
+
+    let recruiter = user(UserRole::Recruiter, Some(company_id));
+    let outsider = user(UserRole::Recruiter, Some(Uuid::new_v4()));
+    let admin = user(UserRole::Admin, None);
+
+    assert_eq!(offers.get(&candidate, offer.id).await.unwrap().id, offer.id);
+    assert_eq!(offers.get(&recruiter, offer.id).await.unwrap().id, offer.id);
+    assert!(matches!(offers.get(&outsider, offer.id).await, Err(AppError::NotFound(_))));
+
+    assert_eq!(offers.list(&candidate).await.unwrap().len(), 1);
+    assert_eq!(offers.list(&recruiter).await.unwrap().len(), 1);
+    assert_eq!(offers.list(&outsider).await.unwrap().len(), 0);
+    assert_eq!(offers.list(&admin).await.unwrap().len(), 2);
+
+    assert!(matches!(offers.withdraw(&outsider, offer.id).await, Err(AppError::NotFound(_))));
+    let change = offers.withdraw(&recruiter, offer.id).await.unwrap();
+    assert_eq!(change.after.status, OfferStatus::Withdrawn);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use careerhub_backend::{config::LogFormat, telemetry};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+use tracing_subscriber::{fmt::MakeWriter, layer::SubscriberExt, Registry};
+
+use common::{bearer, init_app, register_and_login};
+
+/// Collects everything the subscriber writes.
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Captured {
+    fn lines(&self) -> Vec<Value> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("log line is not JSON"))
+            .collect()
+    }
+}
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Captured {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// The fields of the `http_request` span an event was logged in.
+fn request_span(line: &Value) -> Option<&Value> {
+    line["spans"].as_array()?.iter().find(|span| span["name"] == "http_request")
+}
+
+#[sqlx::test]
+async fn request_logs_carry_request_and_user_ids(pool: PgPool) {
+    let captured = Captured::default();
+    let subscriber = Registry::default().with(telemetry::fmt_layer(LogFormat::Json, captured.clone()));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app = init_app(pool).await;
+    let (id, token) = register_and_login(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/profile")
+        .insert_header(bearer(&token))
+        .insert_header(("x-request-id", "trace-me"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let lines = captured.lines();
+    let completed = lines
+        .iter()
+        .filter(|line| line["message"] == "Request completed")
+        .find(|line| request_span(line).is_some_and(|span| span["request_id"] == "trace-me"))
+        .expect("no log line for the request");
+
+    assert_eq!(completed["status"], 200);
+    let span = request_span(completed).unwrap();
+    assert_eq!(span["user_id"], id.to_string());
+    assert_eq!(span["http.route"], "/api/users/profile");
+    assert_eq!(span["http.request.method"], "GET");
+
+    // Unauthenticated requests are logged without a user.
+    let login = lines
+        .iter()
+        .filter_map(request_span)
+        .find(|span| span["url.path"] == "/api/auth/login")
+        .expect("no log line for the login");
+    assert!(login.get("user_id").is_none());
+}
+
+#[sqlx::test]
+async fn repository_calls_open_db_spans(pool: PgPool) {
+    let captured = Captured::default();
+    let subscriber = Registry::default().with(telemetry::fmt_layer(LogFormat::Json, captured.clone()));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/jobs/{}", uuid::Uuid::new_v4()))
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NOT_FOUND);
+
+    // Query events from sqlx are recorded inside the repository's span.
+    let traced = captured.lines().into_iter().any(|line| {
+        line["target"] == "sqlx::query"
+            && line["spans"]
+                .as_array()
+                .is_some_and(|spans| spans.iter().any(|span| span["name"] == "db.jobs.find_visible"))
+    });
+    assert!(traced, "no sqlx query recorded under db.jobs.find_visible");
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, Error,
+};
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use common::{bearer, init_app, register_and_login, send, PASSWORD};
+
+/// The code an authenticator app shows for `secret`, `steps` periods from now.
+fn code(secret: &str, steps: i64) -> String {
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        30,
+        Secret::Encoded(secret.to_string()).to_bytes().unwrap(),
+        None,
+        String::new(),
+    )
+    .unwrap();
+
+    totp.generate((Utc::now().timestamp() + steps * 30) as u64)
+}
+
+fn post(uri: &str, token: &str, body: Value) -> Request {
+    test::TestRequest::post()
+        .uri(uri)
+        .insert_header(bearer(token))
+        .set_json(body)
+        .to_request()
+}
+
+fn login(email: &str) -> Request {
+    test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": email, "password": PASSWORD }))
+        .to_request()
+}
+
+fn second_step(challenge: &Value, code: &str) -> Request {
+    test::TestRequest::post()
+        .uri("/api/auth/login/2fa")
+        .set_json(json!({ "challenge_token": challenge, "code": code }))
+        .to_request()
+}
+
+/// Enrolls and confirms two-factor authentication; returns the secret, the
+/// recovery codes and the code that confirmed the enrollment.
+async fn enable<S, B>(app: &S, token: &str) -> (String, Vec<String>, String)
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let (status, enrollment) = send(app, post("/api/users/2fa", token, json!({}))).await;
+    assert_eq!(status, StatusCode::OK, "{}", enrollment);
+    let secret = enrollment["secret"].as_str().unwrap().to_string();
+
+    let confirmation = code(&secret, 0);
+    let (status, body) = send(app, post("/api/users/2fa/confirm", token, json!({ "code": confirmation }))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let recovery_codes = serde_json::from_value(body["recovery_codes"].clone()).unwrap();
+
+    (secret, recovery_codes, confirmation)
+}
+
+#[sqlx::test]
+async fn enrollment_needs_a_valid_code(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    let (status, enrollment) = send(&app, post("/api/users/2fa", &token, json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    let uri = enrollment["otpauth_uri"].as_str().unwrap();
+    assert!(uri.starts_with("otpauth://totp/CareerHub:alice%40example.com?"), "{}", uri);
+    assert!(uri.contains(&format!("secret={}", enrollment["secret"].as_str().unwrap())));
+
+    let (status, _) = send(&app, post("/api/users/2fa/confirm", &token, json!({ "code": "000000" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Until confirmed, logging in still takes only a password.
+    let (status, body) = send(&app, login("alice@example.com")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["token"].is_string());
+
+    let secret = enrollment["secret"].as_str().unwrap();
+    let (status, body) = send(&app, post("/api/users/2fa/confirm", &token, json!({ "code": code(secret, 0) }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["recovery_codes"].as_array().unwrap().len(), 10);
+
+    let req = test::TestRequest::get().uri("/api/users/2fa").insert_header(bearer(&token)).to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["enabled"], true);
+    assert_eq!(body["recovery_codes_remaining"], 10);
+
+    // Enrolling again would silently replace the secret.
+    let (status, _) = send(&app, post("/api/users/2fa", &token, json!({}))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[sqlx::test]
+async fn login_takes_a_second_step(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let (secret, _, confirmation) = enable(&app, &token).await;
+
+    let (status, body) = send(&app, login("alice@example.com")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["two_factor_required"], true);
+    assert!(body.get("token").is_none());
+    let challenge = body["challenge_token"].clone();
+
+    // The challenge is not a session...
+    let req = test::TestRequest::get()
+        .uri("/api/users/profile")
+        .insert_header(bearer(challenge.as_str().unwrap()))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::UNAUTHORIZED);
+
+    // ...and a session is not a challenge.
+    assert_eq!(send(&app, second_step(&json!(token), &code(&secret, 1))).await.0, StatusCode::UNAUTHORIZED);
+
+    // The code used to confirm enrollment cannot be replayed, even once the
+    // clock has moved on to the next step.
+    assert_eq!(send(&app, second_step(&challenge, &confirmation)).await.0, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = send(&app, second_step(&challenge, &code(&secret, 1))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let req = test::TestRequest::get()
+        .uri("/api/users/profile")
+        .insert_header(bearer(body["token"].as_str().unwrap()))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn recovery_codes_work_once(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let (_, recovery_codes, _) = enable(&app, &token).await;
+
+    let (_, body) = send(&app, login("alice@example.com")).await;
+    let challenge = body["challenge_token"].clone();
+
+    // Case and dashes do not matter.
+    let typed = recovery_codes[0].replace('-', "").to_uppercase();
+    assert_eq!(send(&app, second_step(&challenge, &typed)).await.0, StatusCode::OK);
+    assert_eq!(send(&app, second_step(&challenge, &recovery_codes[0])).await.0, StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get().uri("/api/users/2fa").insert_header(bearer(&token)).to_request();
+    assert_eq!(send(&app, req).await.1["recovery_codes_remaining"], 9);
+}
+
+#[sqlx::test]
+async fn disabling_needs_a_code(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+    let (_, recovery_codes, _) = enable(&app, &token).await;
+
+    let disable = |code: &str| {
+        test::TestRequest::delete()
+            .uri("/api/users/2fa")
+            .insert_header(bearer(&token))
+            .set_json(json!({ "code": code }))
+            .to_request()
+    };
+
+    assert_eq!(send(&app, disable("123456")).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, disable(&recovery_codes[1])).await.0, StatusCode::NO_CONTENT);
+
+    let (status, body) = send(&app, login("alice@example.com")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["token"].is_string());
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use careerhub_backend::{
+    auth::jwt::{generate_token, JwtConfig, JwtKey},
+    models::users::User,
+    repositories::{PgUserRepository, UserRepository},
+};
+use chrono::Duration;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{bearer, init_app, jwt_config, register, register_and_login, send};
+
+#[sqlx::test]
+async fn profile_routes_require_a_token(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let requests = [
+        test::TestRequest::get().uri("/api/users/profile"),
+        test::TestRequest::put().uri("/api/users/profile"),
+    ];
+
+    for req in requests {
+        let (status, _) = send(&app, req.to_request()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[sqlx::test]
+async fn profile_rejects_tokens_that_should_not_be_honoured(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let (id, _) = register_and_login(&app, "alice@example.com").await;
+
+    let user = PgUserRepository::new(pool.clone()).find_by_id(id).await.unwrap().unwrap();
+    let foreign = JwtConfig::new(JwtKey::hmac(b"someone-elses-secret"), Duration::hours(1));
+    let expired = JwtConfig {
+        expiration: Duration::hours(-1),
+        ..jwt_config()
+    };
+    let other_audience = JwtConfig {
+        audience: "another-service".to_string(),
+        ..jwt_config()
+    };
+    let tokens = [
+        generate_token(&user, &foreign).unwrap(),
+        generate_token(&user, &expired).unwrap(),
+        generate_token(&user, &other_audience).unwrap(),
+        generate_token(&User { id: Uuid::new_v4(), ..user }, &jwt_config()).unwrap(),
+    ];
+
+    for token in tokens {
+        let req = test::TestRequest::get()
+            .uri("/api/users/profile")
+            .insert_header(bearer(&token))
+            .to_request();
+        let (status, body) = send(&app, req).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["message"], "Invalid token");
+    }
+}
+
+#[sqlx::test]
+async fn profile_honours_suspension_and_revocation(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let (id, token) = register_and_login(&app, "alice@example.com").await;
+
+    sqlx::query("UPDATE users SET tokens_revoked_at = NOW() + INTERVAL '1 second' WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/profile")
+        .insert_header(bearer(&token))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["message"], "Token revoked");
+
+    sqlx::query("UPDATE users SET suspended_at = NOW() WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/profile")
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test]
+async fn get_profile_returns_the_caller(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (id, token) = register_and_login(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/profile")
+        .insert_header(bearer(&token))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], json!(id));
+    assert_eq!(body["email"], "alice@example.com");
+    assert_eq!(body["role"], "User");
+}
+
+#[sqlx::test]
+async fn update_profile_changes_name_and_password(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::put()
+        .uri("/api/users/profile")
+        .insert_header(bearer(&token))
+        .set_json(json!({ "name": "Alice Liddell", "password": "a-brand-new-password" }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["name"], "Alice Liddell");
+    assert_eq!(body["email"], "alice@example.com");
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "alice@example.com", "password": "a-brand-new-password" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn update_profile_validates_the_payload(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::put()
+        .uri("/api/users/profile")
+        .insert_header(bearer(&token))
+        .set_json(json!({ "email": "nope", "password": "short" }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"]["details"]["email"].is_array());
+    assert!(body["error"]["details"]["password"].is_array());
+}
+
+#[sqlx::test]
+async fn update_profile_rejects_a_taken_email(pool: PgPool) {
+    let app = init_app(pool).await;
+    register(&app, "bob@example.com").await;
+    let (_, token) = register_and_login(&app, "alice@example.com").await;
+
+    let req = test::TestRequest::put()
+        .uri("/api/users/profile")
+        .insert_header(bearer(&token))
+        .set_json(json!({ "email": "bob@example.com" }))
+        .to_request();
+    let (status, body) = send(&app, req).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["message"], "Email already exists");
+}
+
+#[sqlx::test]
+async fn errors_carry_the_request_id(pool: PgPool) {
+    let app = init_app(pool).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/profile")
+        .insert_header(("x-request-id", "test-request-1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "test-request-1");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["request_id"], "test-request-1");
+}
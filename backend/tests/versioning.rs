@@ -0,0 +1,154 @@
+mod common;
+
+use actix_web::{http::StatusCode, test};
+use careerhub_backend::{
+    config::{ApiConfig, DeprecationConfig},
+    versioning::ApiVersion,
+    AppState,
+};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{
+    admin_token, bearer, create_company, create_job, init_app, init_app_with, job_payload, jwt_config,
+    register_and_login, send,
+};
+
+fn at(timestamp: &str) -> DateTime<Utc> {
+    timestamp.parse().unwrap()
+}
+
+#[sqlx::test]
+async fn versions_run_side_by_side(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    for _ in 0..3 {
+        create_job(&app, &admin, &company["id"]).await;
+    }
+
+    let req = test::TestRequest::get().uri("/api/v1/jobs?per_page=2").insert_header(bearer(&admin)).to_request();
+    let (status, jobs) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(jobs.as_array().unwrap().len(), 2);
+
+    let req = test::TestRequest::get().uri("/api/v2/jobs?per_page=2&page=2").insert_header(bearer(&admin)).to_request();
+    let (status, page) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+    assert_eq!((page["page"].as_i64(), page["per_page"].as_i64(), page["total"].as_i64()), (Some(2), Some(2), Some(3)));
+
+    // The total counts the same listings the page is taken from.
+    for (filter, total) in [("title=backend", 3), ("title=frontend", 0), ("location=berlin&job_type=Fulltime", 3)] {
+        let req = test::TestRequest::get().uri(&format!("/api/v2/jobs?per_page=1&{}", filter)).insert_header(bearer(&admin)).to_request();
+        let (status, page) = send(&app, req).await;
+        assert_eq!(status, StatusCode::OK, "{}", filter);
+        assert_eq!(page["total"], total, "{}", filter);
+        assert_eq!(page["items"].as_array().unwrap().len(), total.min(1), "{}", filter);
+    }
+
+    // Routes v2 left alone are the same in both.
+    for prefix in ["/api/v1", "/api/v2", "/api"] {
+        let req = test::TestRequest::get()
+            .uri(&format!("{}/companies/{}", prefix, company["id"].as_str().unwrap()))
+            .insert_header(bearer(&admin))
+            .to_request();
+        let (status, body) = send(&app, req).await;
+        assert_eq!(status, StatusCode::OK, "{}", prefix);
+        assert_eq!(body["name"], "Acme");
+    }
+}
+
+#[sqlx::test]
+async fn unversioned_routes_are_deprecated_in_favour_of_v1(pool: PgPool) {
+    let app = init_app(pool).await;
+    let (_, token) = register_and_login(&app, "user@example.com").await;
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/jobs").insert_header(bearer(&token)).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let headers = resp.headers();
+    assert_eq!(headers.get("Deprecation").unwrap(), "@1792368000");
+    assert_eq!(headers.get("Link").unwrap(), "</api/v1>; rel=\"successor-version\"");
+    assert!(headers.get("Sunset").is_none());
+
+    // Errors carry them too, so clients notice on any response.
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/jobs").to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert!(resp.headers().contains_key("Deprecation"));
+
+    // So does the v1 document where it was served before versioning.
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/openapi.json").to_request()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let headers = resp.headers();
+    assert_eq!(headers.get("Deprecation").unwrap(), "@1792368000");
+    assert_eq!(headers.get("Link").unwrap(), "</api/v1>; rel=\"successor-version\"");
+    let spec: serde_json::Value = test::read_body_json(resp).await;
+    assert!(spec["paths"]["/api/v1/jobs"]["get"].is_object());
+
+    for uri in ["/api/v1/jobs", "/api/v2/jobs", "/api/v1/openapi.json"] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).insert_header(bearer(&token)).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(!resp.headers().contains_key("Deprecation"), "{}", uri);
+        assert!(!resp.headers().contains_key("Sunset"), "{}", uri);
+    }
+}
+
+#[sqlx::test]
+async fn deprecated_versions_announce_their_sunset(pool: PgPool) {
+    let config = ApiConfig {
+        legacy_sunset_at: Some(at("2027-04-01T00:00:00Z")),
+        deprecations: [(
+            ApiVersion::V1,
+            DeprecationConfig { deprecated_at: at("2027-01-01T00:00:00Z"), sunset_at: Some(at("2027-07-01T00:00:00Z")) },
+        )]
+        .into(),
+    };
+    let state = AppState::new(pool, jwt_config()).with_api(&config);
+    let app = init_app_with(&state).await;
+    let (_, token) = register_and_login(&app, "user@example.com").await;
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/v1/jobs").insert_header(bearer(&token)).to_request()).await;
+    let headers = resp.headers();
+    assert_eq!(headers.get("Deprecation").unwrap(), "@1798761600");
+    assert_eq!(headers.get("Sunset").unwrap(), "Thu, 01 Jul 2027 00:00:00 GMT");
+    assert_eq!(headers.get("Link").unwrap(), "</api/v2>; rel=\"successor-version\"");
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/jobs").insert_header(bearer(&token)).to_request()).await;
+    assert_eq!(resp.headers().get("Sunset").unwrap(), "Thu, 01 Apr 2027 00:00:00 GMT");
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/openapi.json").to_request()).await;
+    assert_eq!(resp.headers().get("Sunset").unwrap(), "Thu, 01 Apr 2027 00:00:00 GMT");
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/v2/jobs").insert_header(bearer(&token)).to_request()).await;
+    assert!(!resp.headers().contains_key("Deprecation"));
+}
+
+#[sqlx::test]
+async fn api_keys_work_under_every_prefix(pool: PgPool) {
+    let app = init_app(pool.clone()).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/v1/companies/{}/api-keys", company["id"].as_str().unwrap()))
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "name": "Job board", "scopes": ["jobs:write"] }))
+        .to_request();
+    let (status, created) = send(&app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", created);
+    let key = created["key"].as_str().unwrap();
+
+    for prefix in ["/api/v1", "/api/v2", "/api"] {
+        let req = test::TestRequest::post()
+            .uri(&format!("{}/jobs", prefix))
+            .insert_header(bearer(key))
+            .set_json(job_payload(&company["id"]))
+            .to_request();
+        let (status, body) = send(&app, req).await;
+        assert_eq!(status, StatusCode::CREATED, "{}: {}", prefix, body);
+    }
+
+    // Only the routes listed for keys accept them, whatever the prefix.
+    let req = test::TestRequest::get().uri("/api/v2/jobs").insert_header(bearer(key)).to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+}
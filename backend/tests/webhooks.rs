@@ -0,0 +1,487 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, web, App, Error, HttpRequest, HttpResponse, HttpServer,
+};
+use careerhub_backend::{
+    config::WebhookConfig,
+    services::webhooks::{sign, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER},
+    AppState,
+};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::{
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicU16, Ordering},
+        mpsc, Arc, Mutex,
+    },
+};
+
+use common::{
+    admin_token, bearer, create_company, create_job, init_app_with, job_payload, jwt_config, register_and_login, send,
+};
+
+/// A request the receiver got.
+struct Received {
+    signature: String,
+    event: String,
+    delivery: String,
+    body: String,
+}
+
+/// An endpoint that records what it is sent and answers with a status the
+/// test can change.
+struct Receiver {
+    url: String,
+    status: AtomicU16,
+    received: Mutex<Vec<Received>>,
+}
+
+impl Receiver {
+    /// Serves a fresh receiver from its own thread and actix system.
+    fn start() -> Arc<Receiver> {
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            actix_rt::System::new().block_on(async move {
+                let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                let receiver = Arc::new(Receiver {
+                    url: format!("http://{}/hooks", listener.local_addr().unwrap()),
+                    status: AtomicU16::new(200),
+                    received: Mutex::default(),
+                });
+
+                let data = web::Data::from(receiver.clone());
+                let server = HttpServer::new(move || {
+                    App::new().app_data(data.clone()).route("/hooks", web::post().to(receive))
+                })
+                .workers(1)
+                .listen(listener)
+                .unwrap()
+                .run();
+
+                tx.send(receiver).unwrap();
+                server.await.unwrap();
+            });
+        });
+
+        rx.recv().unwrap()
+    }
+
+    fn respond_with(&self, status: u16) {
+        self.status.store(status, Ordering::SeqCst);
+    }
+
+    fn take(&self) -> Vec<Received> {
+        std::mem::take(&mut *self.received.lock().unwrap())
+    }
+}
+
+async fn receive(receiver: web::Data<Receiver>, req: HttpRequest, body: String) -> HttpResponse {
+    let header = |name: &str| req.headers().get(name).unwrap().to_str().unwrap().to_string();
+
+    receiver.received.lock().unwrap().push(Received {
+        signature: header(SIGNATURE_HEADER),
+        event: header(EVENT_HEADER),
+        delivery: header(DELIVERY_HEADER),
+        body,
+    });
+
+    HttpResponse::build(StatusCode::from_u16(receiver.status.load(Ordering::SeqCst)).unwrap()).body("nope")
+}
+
+/// Receivers listen on loopback, which endpoints may only point at when allowed.
+fn state(pool: PgPool) -> AppState {
+    AppState::new(pool, jwt_config()).with_webhooks(&WebhookConfig {
+        max_attempts: 2,
+        allowed_hosts: vec!["127.0.0.1".to_string()],
+        ..WebhookConfig::default()
+    })
+}
+
+async fn create_webhook<S, B>(app: &S, token: &str, company_id: &Value, body: Value) -> (StatusCode, Value)
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/companies/{}/webhooks", company_id.as_str().unwrap()))
+        .insert_header(bearer(token))
+        .set_json(body)
+        .to_request();
+
+    send(app, req).await
+}
+
+/// Applies to `job` as a new candidate and returns the application.
+async fn apply<S, B>(app: &S, email: &str, job: &Value) -> Value
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let (_, candidate) = register_and_login(app, email).await;
+    let req = test::TestRequest::post()
+        .uri("/api/applications")
+        .insert_header(bearer(&candidate))
+        .set_json(json!({ "job_id": job["id"], "resume_url": "https://example.com/cv.pdf" }))
+        .to_request();
+    let (status, application) = send(app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", application);
+
+    application
+}
+
+/// Makes every pending delivery due now instead of after its backoff.
+async fn make_due(pool: &PgPool) {
+    sqlx::query("UPDATE webhook_deliveries SET next_attempt_at = NOW() WHERE status = 'pending'")
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn subscribed_events_are_delivered_signed(pool: PgPool) {
+    let receiver = Receiver::start();
+    let state = state(pool.clone());
+    let app = init_app_with(&state).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+
+    let (status, webhook) = create_webhook(
+        &app,
+        &admin,
+        &company["id"],
+        json!({ "url": receiver.url, "events": ["application.status_changed"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", webhook);
+    let secret = webhook["secret"].as_str().unwrap();
+    assert!(secret.starts_with("whsec_"));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/companies/{}/webhooks", company["id"].as_str().unwrap()))
+        .insert_header(bearer(&admin))
+        .to_request();
+    let (_, webhooks) = send(&app, req).await;
+    assert_eq!(webhooks.as_array().unwrap().len(), 1);
+    assert!(webhooks[0].get("secret").is_none());
+
+    // Not subscribed to new applications.
+    let application = apply(&app, "candidate@example.com", &job).await;
+    assert_eq!(state.services.webhooks.deliver_due().await.unwrap(), 0);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/applications/{}/status", application["id"].as_str().unwrap()))
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "status": "Shortlisted" }))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+
+    assert_eq!(state.services.webhooks.deliver_due().await.unwrap(), 1);
+    let received = receiver.take();
+    assert_eq!(received.len(), 1);
+    let request = &received[0];
+    assert_eq!(request.event, "application.status_changed");
+
+    let timestamp: i64 = request.signature.strip_prefix("t=").unwrap().split(',').next().unwrap().parse().unwrap();
+    assert_eq!(request.signature, sign(secret, timestamp, &request.body));
+
+    let body: Value = serde_json::from_str(&request.body).unwrap();
+    assert_eq!(body["event"], "application.status_changed");
+    assert_eq!(body["data"]["id"], application["id"]);
+    assert_eq!(body["data"]["status"], "Shortlisted");
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/companies/{}/webhooks/{}/deliveries/{}",
+            company["id"].as_str().unwrap(),
+            webhook["id"].as_str().unwrap(),
+            request.delivery
+        ))
+        .insert_header(bearer(&admin))
+        .to_request();
+    let (status, delivery) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", delivery);
+    assert_eq!(delivery["status"], "delivered");
+    assert_eq!(delivery["attempts"], 1);
+    assert_eq!(delivery["attempt_log"][0]["response_status"], 200);
+
+    // Nothing left to send.
+    assert_eq!(state.services.webhooks.deliver_due().await.unwrap(), 0);
+}
+
+#[sqlx::test]
+async fn failed_deliveries_are_retried_and_can_be_redelivered(pool: PgPool) {
+    let receiver = Receiver::start();
+    let state = state(pool.clone());
+    let app = init_app_with(&state).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+    let (_, webhook) = create_webhook(
+        &app,
+        &admin,
+        &company["id"],
+        json!({ "url": receiver.url, "events": ["application.created"] }),
+    )
+    .await;
+    let deliveries_uri = format!(
+        "/api/companies/{}/webhooks/{}/deliveries",
+        company["id"].as_str().unwrap(),
+        webhook["id"].as_str().unwrap()
+    );
+
+    receiver.respond_with(503);
+    apply(&app, "candidate@example.com", &job).await;
+    assert_eq!(state.services.webhooks.deliver_due().await.unwrap(), 1);
+
+    // Backing off: not due again yet.
+    assert_eq!(state.services.webhooks.deliver_due().await.unwrap(), 0);
+    let req = test::TestRequest::get().uri(&deliveries_uri).insert_header(bearer(&admin)).to_request();
+    let (_, deliveries) = send(&app, req).await;
+    assert_eq!(deliveries[0]["status"], "pending");
+    assert_eq!(deliveries[0]["attempts"], 1);
+
+    make_due(&pool).await;
+    assert_eq!(state.services.webhooks.deliver_due().await.unwrap(), 1);
+    let received = receiver.take();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].delivery, received[1].delivery);
+    assert_eq!(received[0].body, received[1].body);
+
+    let delivery_uri = format!("{}/{}", deliveries_uri, received[0].delivery);
+    let req = test::TestRequest::get().uri(&delivery_uri).insert_header(bearer(&admin)).to_request();
+    let (_, delivery) = send(&app, req).await;
+    assert_eq!(delivery["status"], "failed");
+    assert_eq!(delivery["attempts"], 2);
+    assert_eq!(delivery["attempt_log"].as_array().unwrap().len(), 2);
+    assert_eq!(delivery["attempt_log"][0]["response_status"], 503);
+    // The receiver's body is not kept.
+    assert_eq!(delivery["attempt_log"][0]["error"], "HTTP 503 Service Unavailable");
+
+    receiver.respond_with(204);
+    let req = test::TestRequest::post()
+        .uri(&format!("{}/redeliver", delivery_uri))
+        .insert_header(bearer(&admin))
+        .to_request();
+    let (status, delivery) = send(&app, req).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(delivery["status"], "pending");
+    assert_eq!(delivery["attempts"], 0);
+
+    assert_eq!(state.services.webhooks.deliver_due().await.unwrap(), 1);
+    assert_eq!(receiver.take()[0].delivery, received[0].delivery);
+    let req = test::TestRequest::get().uri(&delivery_uri).insert_header(bearer(&admin)).to_request();
+    let (_, delivery) = send(&app, req).await;
+    assert_eq!(delivery["status"], "delivered");
+    assert_eq!(delivery["attempt_log"].as_array().unwrap().len(), 3);
+}
+
+#[sqlx::test]
+async fn only_the_companys_recruiters_manage_its_webhooks(pool: PgPool) {
+    let receiver = Receiver::start();
+    let state = state(pool.clone());
+    let app = init_app_with(&state).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let other = create_company(&app, &admin, "Globex").await;
+    let other_job = create_job(&app, &admin, &other["id"]).await;
+
+    let (_, outsider) = register_and_login(&app, "outsider@example.com").await;
+    let payload = json!({ "url": receiver.url, "events": ["application.created"] });
+    assert_eq!(create_webhook(&app, &outsider, &company["id"], payload.clone()).await.0, StatusCode::FORBIDDEN);
+
+    let (status, _) = create_webhook(&app, &admin, &company["id"], json!({ "url": "ftp://example.com", "events": ["application.created"] })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = create_webhook(&app, &admin, &company["id"], json!({ "url": receiver.url, "events": [] })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = create_webhook(&app, &admin, &company["id"], json!({ "url": receiver.url, "events": ["job.created"] })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, webhook) = create_webhook(&app, &admin, &company["id"], payload).await;
+    let webhook_uri = format!(
+        "/api/companies/{}/webhooks/{}",
+        company["id"].as_str().unwrap(),
+        webhook["id"].as_str().unwrap()
+    );
+
+    // Applications to other companies' jobs are not sent here.
+    apply(&app, "candidate@example.com", &other_job).await;
+    assert_eq!(state.services.webhooks.deliver_due().await.unwrap(), 0);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("{}/deliveries", webhook_uri))
+        .insert_header(bearer(&outsider))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+
+    // The webhook is not reachable through another company.
+    let req = test::TestRequest::delete()
+        .uri(&format!(
+            "/api/companies/{}/webhooks/{}",
+            other["id"].as_str().unwrap(),
+            webhook["id"].as_str().unwrap()
+        ))
+        .insert_header(bearer(&admin))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::delete().uri(&webhook_uri).insert_header(bearer(&outsider)).to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::FORBIDDEN);
+    let req = test::TestRequest::delete().uri(&webhook_uri).insert_header(bearer(&admin)).to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NO_CONTENT);
+    let req = test::TestRequest::get()
+        .uri(&format!("{}/deliveries", webhook_uri))
+        .insert_header(bearer(&admin))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn endpoints_must_be_public(pool: PgPool) {
+    let receiver = Receiver::start();
+    let state = state(pool.clone());
+    let app = init_app_with(&state).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let job = create_job(&app, &admin, &company["id"]).await;
+
+    // Only the allowed host itself, not others like it.
+    for url in [
+        "http://localhost/hooks",
+        "http://127.0.0.2/hooks",
+        "http://[::1]/hooks",
+        "http://[::ffff:127.0.0.1]/hooks",
+        "http://10.0.0.1/hooks",
+        "http://192.168.1.10/hooks",
+        "http://169.254.169.254/latest/meta-data",
+        "http://[fd00::1]/hooks",
+        "http://[fe80::1]/hooks",
+        "http://0.0.0.0/hooks",
+    ] {
+        let (status, body) = create_webhook(&app, &admin, &company["id"], json!({ "url": url, "events": ["application.created"] })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}: {}", url, body);
+    }
+
+    let (status, webhook) = create_webhook(
+        &app,
+        &admin,
+        &company["id"],
+        json!({ "url": receiver.url, "events": ["application.created"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", webhook);
+
+    // Once the host is no longer allowed, deliveries to it stop too.
+    let strict = AppState::new(pool, jwt_config()).with_webhooks(&WebhookConfig {
+        max_attempts: 2,
+        ..WebhookConfig::default()
+    });
+    apply(&app, "candidate@example.com", &job).await;
+    assert_eq!(strict.services.webhooks.deliver_due().await.unwrap(), 1);
+    assert!(receiver.take().is_empty());
+
+    let deliveries_uri = format!(
+        "/api/companies/{}/webhooks/{}/deliveries",
+        company["id"].as_str().unwrap(),
+        webhook["id"].as_str().unwrap()
+    );
+    let req = test::TestRequest::get().uri(&deliveries_uri).insert_header(bearer(&admin)).to_request();
+    let (_, deliveries) = send(&app, req).await;
+    let req = test::TestRequest::get()
+        .uri(&format!("{}/{}", deliveries_uri, deliveries[0]["id"].as_str().unwrap()))
+        .insert_header(bearer(&admin))
+        .to_request();
+    let (_, delivery) = send(&app, req).await;
+    assert_eq!(delivery["status"], "pending");
+    assert_eq!(delivery["attempt_log"][0]["response_status"], Value::Null);
+    assert_eq!(delivery["attempt_log"][0]["error"], "127.0.0.1 is not a public address");
+}
+
+#[sqlx::test]
+async fn deliveries_are_queued_with_the_change(pool: PgPool) {
+    let state = state(pool.clone());
+    let app = init_app_with(&state).await;
+    let admin = admin_token(&app, &pool).await;
+    let company = create_company(&app, &admin, "Acme").await;
+    let mut payload = job_payload(&company["id"]);
+    payload["close_applications_on_accept"] = json!(true);
+    let req = test::TestRequest::post().uri("/api/jobs").insert_header(bearer(&admin)).set_json(payload).to_request();
+    let (_, job) = send(&app, req).await;
+    let events = json!(["application.created", "application.status_changed"]);
+    let (status, _) = create_webhook(&app, &admin, &company["id"], json!({ "url": "http://127.0.0.1:9/hooks", "events": events })).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // An application whose deliveries cannot be queued is not made either.
+    sqlx::raw_sql(
+        "CREATE FUNCTION refuse() RETURNS trigger LANGUAGE plpgsql AS $$ BEGIN RAISE EXCEPTION 'refused'; END $$;
+         CREATE TRIGGER refuse BEFORE INSERT ON webhook_deliveries FOR EACH ROW EXECUTE FUNCTION refuse();",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let (_, candidate) = register_and_login(&app, "candidate@example.com").await;
+    let application = json!({ "job_id": job["id"], "resume_url": "https://example.com/cv.pdf" });
+    let req = test::TestRequest::post()
+        .uri("/api/applications")
+        .insert_header(bearer(&candidate))
+        .set_json(&application)
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::INTERNAL_SERVER_ERROR);
+    let applications: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM applications").fetch_one(&pool).await.unwrap();
+    assert_eq!(applications, 0);
+    sqlx::raw_sql("DROP TRIGGER refuse ON webhook_deliveries").execute(&pool).await.unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/applications")
+        .insert_header(bearer(&candidate))
+        .set_json(&application)
+        .to_request();
+    let (_, accepted) = send(&app, req).await;
+    let closed = apply(&app, "other@example.com", &job).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/offers")
+        .insert_header(bearer(&admin))
+        .set_json(json!({
+            "application_id": accepted["id"],
+            "salary_amount": 80000,
+            "start_date": (Utc::now() + Duration::days(30)).date_naive(),
+            "expires_at": Utc::now() + Duration::days(7),
+        }))
+        .to_request();
+    let (status, offer) = send(&app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", offer);
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/offers/{}/accept", offer["id"].as_str().unwrap()))
+        .insert_header(bearer(&candidate))
+        .to_request();
+    assert_eq!(send(&app, req).await.0, StatusCode::OK);
+
+    // Queued by the time each request returned: one per application made,
+    // then one per application the accepted offer moved on.
+    let queued: Vec<(String, Value)> = sqlx::query_as("SELECT event, payload FROM webhook_deliveries ORDER BY created_at, payload->'data'->>'status'")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    let queued: Vec<_> = queued
+        .iter()
+        .map(|(event, payload)| (event.as_str(), payload["data"]["id"].clone(), payload["data"]["status"].clone()))
+        .collect();
+    assert_eq!(
+        queued,
+        [
+            ("application.created", accepted["id"].clone(), json!("Pending")),
+            ("application.created", closed["id"].clone(), json!("Pending")),
+            ("application.status_changed", accepted["id"].clone(), json!("Accepted")),
+            ("application.status_changed", closed["id"].clone(), json!("Closed")),
+        ]
+    );
+}